@@ -0,0 +1,20 @@
+-- Add meal type to food logs and user time zone
+
+ALTER TABLE users
+    ADD COLUMN timezone TEXT NOT NULL DEFAULT 'UTC';
+
+ALTER TABLE food_logs
+    ADD COLUMN meal_type TEXT;
+
+-- Existing entries were logged without a time zone, so infer from UTC time
+UPDATE food_logs
+SET meal_type = CASE
+    WHEN EXTRACT(HOUR FROM created_at AT TIME ZONE 'UTC') BETWEEN 5 AND 10 THEN 'breakfast'
+    WHEN EXTRACT(HOUR FROM created_at AT TIME ZONE 'UTC') BETWEEN 11 AND 15 THEN 'lunch'
+    WHEN EXTRACT(HOUR FROM created_at AT TIME ZONE 'UTC') BETWEEN 17 AND 21 THEN 'dinner'
+    ELSE 'snack'
+END;
+
+ALTER TABLE food_logs
+    ALTER COLUMN meal_type SET DEFAULT 'snack',
+    ALTER COLUMN meal_type SET NOT NULL;
@@ -0,0 +1,13 @@
+-- Favorite meals that can be re-logged without calling the model
+
+CREATE TABLE favorite_meals (
+    id SERIAL PRIMARY KEY,
+    chat_id BIGINT NOT NULL,
+    name TEXT NOT NULL,
+    calories REAL,
+    proteins REAL,
+    fats REAL,
+    carbs REAL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (chat_id, name)
+);
@@ -0,0 +1,23 @@
+-- Local food composition database (values per 100 g)
+
+CREATE TABLE foods (
+    id SERIAL PRIMARY KEY,
+    source TEXT NOT NULL DEFAULT 'custom',
+    external_id TEXT,
+    name TEXT NOT NULL,
+    calories REAL NOT NULL,
+    proteins REAL NOT NULL,
+    fats REAL NOT NULL,
+    carbs REAL NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (source, name)
+);
+
+-- Alternative and translated names used by the matcher
+CREATE TABLE food_aliases (
+    id SERIAL PRIMARY KEY,
+    food_id INTEGER NOT NULL REFERENCES foods(id) ON DELETE CASCADE,
+    lang TEXT,
+    alias TEXT NOT NULL,
+    UNIQUE (food_id, alias)
+);
@@ -0,0 +1,14 @@
+-- Packaged products looked up by barcode (Open Food Facts dump format)
+
+CREATE TABLE products (
+    barcode TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    brand TEXT,
+    serving_size_g REAL,
+    calories REAL NOT NULL,
+    proteins REAL,
+    fats REAL,
+    carbs REAL,
+    source TEXT NOT NULL DEFAULT 'openfoodfacts',
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
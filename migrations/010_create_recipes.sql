@@ -0,0 +1,27 @@
+-- User recipes: totals are stored for the whole cooked dish
+
+CREATE TABLE recipes (
+    id SERIAL PRIMARY KEY,
+    chat_id BIGINT NOT NULL,
+    name TEXT NOT NULL,
+    cooked_weight_g REAL NOT NULL,
+    servings INTEGER NOT NULL DEFAULT 1,
+    calories REAL NOT NULL,
+    proteins REAL NOT NULL,
+    fats REAL NOT NULL,
+    carbs REAL NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (chat_id, name)
+);
+
+CREATE TABLE recipe_ingredients (
+    id SERIAL PRIMARY KEY,
+    recipe_id INTEGER NOT NULL REFERENCES recipes(id) ON DELETE CASCADE,
+    description TEXT NOT NULL,
+    calories REAL NOT NULL,
+    proteins REAL NOT NULL,
+    fats REAL NOT NULL,
+    carbs REAL NOT NULL
+);
+
+CREATE INDEX idx_recipe_ingredients_recipe_id ON recipe_ingredients (recipe_id);
@@ -0,0 +1,11 @@
+-- Fibre, sugar and saturated fat in grams; minerals and vitamins in milligrams
+
+ALTER TABLE food_logs
+    ADD COLUMN fiber REAL,
+    ADD COLUMN sugar REAL,
+    ADD COLUMN saturated_fat REAL,
+    ADD COLUMN sodium_mg REAL,
+    ADD COLUMN vitamin_c_mg REAL,
+    ADD COLUMN calcium_mg REAL,
+    ADD COLUMN iron_mg REAL,
+    ADD COLUMN potassium_mg REAL;
@@ -0,0 +1,10 @@
+-- Water intake, one row per drink
+
+CREATE TABLE water_logs (
+    id SERIAL PRIMARY KEY,
+    chat_id BIGINT NOT NULL,
+    amount_ml INTEGER NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_water_logs_chat_id_created_at ON water_logs (chat_id, created_at);
@@ -0,0 +1,18 @@
+-- Body weight history and the daily calorie target derived from the profile
+
+CREATE TABLE weight_logs (
+    id SERIAL PRIMARY KEY,
+    chat_id BIGINT NOT NULL,
+    weight_kg DOUBLE PRECISION NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_weight_logs_chat_id_created_at ON weight_logs (chat_id, created_at);
+
+ALTER TABLE users ADD COLUMN calorie_target INTEGER;
+
+-- Seed the history with the weight already stored in profiles
+INSERT INTO weight_logs (chat_id, weight_kg, created_at)
+SELECT chat_id, weight_kg, COALESCE(updated_at, created_at)
+FROM users
+WHERE weight_kg IS NOT NULL;
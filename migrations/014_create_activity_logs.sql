@@ -0,0 +1,14 @@
+-- Exercise and activities with estimated calories burned
+
+CREATE TABLE activity_logs (
+    id SERIAL PRIMARY KEY,
+    chat_id BIGINT NOT NULL,
+    activity TEXT NOT NULL,
+    description TEXT NOT NULL,
+    duration_min REAL NOT NULL,
+    distance_km REAL,
+    calories REAL NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_activity_logs_chat_id_created_at ON activity_logs (chat_id, created_at);
@@ -0,0 +1,18 @@
+-- Intermittent fasting sessions and the user's preferred protocol
+
+CREATE TABLE fasts (
+    id SERIAL PRIMARY KEY,
+    chat_id BIGINT NOT NULL,
+    protocol TEXT NOT NULL,
+    target_hours REAL NOT NULL,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    ended_at TIMESTAMPTZ,
+    notified BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX idx_fasts_chat_id_started_at ON fasts (chat_id, started_at);
+
+-- At most one running fast per user
+CREATE UNIQUE INDEX idx_fasts_active ON fasts (chat_id) WHERE ended_at IS NULL;
+
+ALTER TABLE users ADD COLUMN fasting_protocol TEXT;
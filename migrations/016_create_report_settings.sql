@@ -0,0 +1,11 @@
+-- Opt-in scheduled weekly/monthly progress reports
+
+CREATE TABLE report_settings (
+    chat_id BIGINT PRIMARY KEY,
+    weekly BOOLEAN NOT NULL DEFAULT FALSE,
+    monthly BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Local time of day in the user's time zone
+    send_time TIME NOT NULL DEFAULT '09:00',
+    last_weekly_sent DATE,
+    last_monthly_sent DATE
+);
@@ -0,0 +1,17 @@
+-- Per-meal logging reminders with snooze and quiet hours
+
+CREATE TABLE meal_reminders (
+    id SERIAL PRIMARY KEY,
+    chat_id BIGINT NOT NULL,
+    meal_type TEXT NOT NULL,
+    -- Local time of day in the user's time zone
+    remind_at TIME NOT NULL,
+    -- Skip the reminder if the meal is already logged today
+    only_if_not_logged BOOLEAN NOT NULL DEFAULT TRUE,
+    last_sent DATE,
+    snoozed_until TIMESTAMPTZ,
+    UNIQUE (chat_id, meal_type)
+);
+
+ALTER TABLE users ADD COLUMN quiet_hours_start TIME;
+ALTER TABLE users ADD COLUMN quiet_hours_end TIME;
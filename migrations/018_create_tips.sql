@@ -0,0 +1,14 @@
+-- Daily tips: opt-out delivery settings and per-user rotation history
+
+ALTER TABLE users ADD COLUMN tips_enabled BOOLEAN NOT NULL DEFAULT TRUE;
+-- Local time of day in the user's time zone
+ALTER TABLE users ADD COLUMN tip_time TIME NOT NULL DEFAULT '10:00';
+ALTER TABLE users ADD COLUMN last_tip_sent DATE;
+
+-- When each catalogue tip was last shown, to avoid repeats
+CREATE TABLE tip_history (
+    chat_id BIGINT NOT NULL,
+    tip_id TEXT NOT NULL,
+    sent_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (chat_id, tip_id)
+);
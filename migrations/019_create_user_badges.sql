@@ -0,0 +1,8 @@
+-- Achievement badges awarded to users
+
+CREATE TABLE user_badges (
+    chat_id BIGINT NOT NULL,
+    badge TEXT NOT NULL,
+    awarded_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (chat_id, badge)
+);
@@ -0,0 +1,16 @@
+-- Audit log of accounts erased at the user's request (/deleteme)
+
+CREATE TABLE account_deletions (
+    id SERIAL PRIMARY KEY,
+    chat_id BIGINT NOT NULL,
+    user_id INTEGER,
+    erased_rows BIGINT NOT NULL,
+    -- Payment records are retained for accounting; their link to the user is cleared
+    retained_payment_ids INTEGER[] NOT NULL DEFAULT '{}',
+    deleted_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+ALTER TABLE payments
+    DROP CONSTRAINT payments_user_id_fkey,
+    ADD CONSTRAINT payments_user_id_fkey
+        FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE SET NULL;
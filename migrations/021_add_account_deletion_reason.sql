@@ -0,0 +1,3 @@
+-- Accounts are also erased by the retention job, not only at the user's request
+
+ALTER TABLE account_deletions ADD COLUMN reason TEXT NOT NULL DEFAULT 'user_request';
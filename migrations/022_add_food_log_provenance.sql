@@ -0,0 +1,12 @@
+-- Provenance of diary entries: what the user sent and what the model answered,
+-- so entries can be audited and re-analyzed later
+
+ALTER TABLE food_logs
+    -- text, photo, voice, barcode, favorite, recipe, import
+    ADD COLUMN source TEXT,
+    ADD COLUMN input_text TEXT,
+    ADD COLUMN telegram_file_id TEXT,
+    ADD COLUMN transcript TEXT,
+    -- Full model reply shown to the user when the entry was logged
+    ADD COLUMN suggestion TEXT,
+    ADD COLUMN model TEXT;
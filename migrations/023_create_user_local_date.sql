@@ -0,0 +1,10 @@
+-- Calendar day of a timestamp in the user's time zone (UTC for unknown users),
+-- so daily totals, history and reports follow the user's local midnight
+
+CREATE FUNCTION user_local_date(p_chat_id BIGINT, ts TIMESTAMPTZ) RETURNS DATE
+LANGUAGE SQL STABLE AS $$
+    SELECT (ts AT TIME ZONE COALESCE(
+        (SELECT timezone FROM users WHERE chat_id = p_chat_id),
+        'UTC'
+    ))::date
+$$;
@@ -0,0 +1,4 @@
+-- Speech-to-text model of voice entries, recorded separately from the model
+-- that estimated the nutrition (which may be none for local database matches)
+
+ALTER TABLE food_logs ADD COLUMN transcription_model TEXT;
@@ -0,0 +1,29 @@
+-- Favorites keep the micronutrients of the entry they were saved from,
+-- so re-logging them does not drop fibre, sugar, sodium and the rest
+
+ALTER TABLE favorite_meals
+    ADD COLUMN fiber REAL,
+    ADD COLUMN sugar REAL,
+    ADD COLUMN saturated_fat REAL,
+    ADD COLUMN sodium_mg REAL,
+    ADD COLUMN vitamin_c_mg REAL,
+    ADD COLUMN calcium_mg REAL,
+    ADD COLUMN iron_mg REAL,
+    ADD COLUMN potassium_mg REAL;
+
+-- Existing favorites take the values of the latest entry with the same name
+UPDATE favorite_meals f
+SET fiber = l.fiber,
+    sugar = l.sugar,
+    saturated_fat = l.saturated_fat,
+    sodium_mg = l.sodium_mg,
+    vitamin_c_mg = l.vitamin_c_mg,
+    calcium_mg = l.calcium_mg,
+    iron_mg = l.iron_mg,
+    potassium_mg = l.potassium_mg
+FROM (
+    SELECT DISTINCT ON (chat_id, food_name) *
+    FROM food_logs
+    ORDER BY chat_id, food_name, created_at DESC
+) l
+WHERE l.chat_id = f.chat_id AND l.food_name = f.name;
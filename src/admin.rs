@@ -0,0 +1,91 @@
+use crate::services::retention::{self, RetentionPolicy};
+use crate::services::food_db::{self, ImportReport};
+use crate::services::products;
+use std::path::Path;
+
+const USAGE: &str = "\
+Usage: Kalorik <command> [args]
+
+Commands:
+  import-foods <file.csv> [source]   Import a food composition table (values per 100 g)
+  import-products <file.tsv>         Import an Open Food Facts export for barcode lookup
+  retention                          Show the data retention rules from the environment
+  purge [--dry-run]                  Apply the retention rules now (--dry-run only counts)
+";
+
+/// Административные команды, запускаемые из командной строки вместо бота.
+pub async fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    match args.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["import-foods", path, rest @ ..] => {
+            let source = rest.first().copied().unwrap_or("custom");
+            let report = food_db::import_csv(Path::new(path), source).await?;
+            println!("✅ Imported {} foods from {}", report.imported, path);
+            print_skipped(&report);
+            Ok(())
+        }
+        ["import-products", path] => {
+            let report = products::import_off_dump(Path::new(path)).await?;
+            println!("✅ Imported {} products from {}", report.imported, path);
+            print_skipped(&report);
+            Ok(())
+        }
+        ["retention"] => {
+            let policy = RetentionPolicy::from_env();
+            for rule in retention::Rule::ALL {
+                match policy.rules.iter().find(|(r, _)| *r == rule) {
+                    Some((_, days)) => println!("{:<34} {} days", rule.env_var(), days),
+                    None => println!("{:<34} off", rule.env_var()),
+                }
+                println!("    {}", rule.description());
+            }
+            println!(
+                "Scheduled purge at {:02}:00 UTC{}",
+                policy.purge_hour,
+                if policy.dry_run { " (dry run)" } else { "" }
+            );
+            Ok(())
+        }
+        ["purge", rest @ ..] if rest.iter().all(|arg| *arg == "--dry-run") => {
+            let dry_run = !rest.is_empty();
+            let policy = RetentionPolicy::from_env();
+            let results = retention::purge(&policy, chrono::Utc::now(), dry_run).await?;
+            for result in &results {
+                println!(
+                    "{} {} {} ({} days, before {})",
+                    if dry_run { "🔎" } else { "🗑" },
+                    result.affected,
+                    result.rule.description(),
+                    result.days,
+                    result.cutoff.format("%Y-%m-%d %H:%M")
+                );
+            }
+            if dry_run {
+                println!("Dry run: nothing was deleted");
+            }
+            Ok(())
+        }
+        _ => {
+            eprint!("{}", USAGE);
+            Err("unknown command".into())
+        }
+    }
+}
+
+/// Пропущенные строки импорта: число по причинам и первые примеры.
+fn print_skipped(report: &ImportReport) {
+    if report.skipped.is_empty() {
+        return;
+    }
+    println!("⚠ Skipped {} lines:", report.skipped_total());
+    for (reason, count) in &report.skipped {
+        println!("    {:>8}  {}", count, reason);
+    }
+    println!("First skipped lines:");
+    for (line, reason, detail) in &report.examples {
+        if detail.is_empty() {
+            println!("    line {}: {}", line, reason);
+        } else {
+            println!("    line {}: {} ({})", line, reason, detail);
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
-use chrono::{DateTime, Utc, Duration};
+use chrono::{DateTime, Utc, Duration, NaiveDate, NaiveTime};
+use chrono_tz::Tz;
 
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
 pub struct User {
@@ -17,9 +18,18 @@ pub struct User {
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
     pub subscription_ends_at: Option<DateTime<Utc>>, // <-- добавлено поле подписки
+    pub timezone: String,
+    /// Суточная норма калорий, пересчитывается при изменении веса.
+    pub calorie_target: Option<i32>,
 }
 
 impl User {
+    /// Часовой пояс пользователя (UTC, если сохранённое значение некорректно).
+    pub fn tz(&self) -> Tz {
+        self.timezone.parse().unwrap_or(Tz::UTC)
+    }
+
+    #[allow(dead_code)]
     pub fn is_subscription_active(&self) -> bool {
         match self.subscription_ends_at {
             Some(ends_at) => ends_at > Utc::now(),
@@ -27,9 +37,277 @@ impl User {
         }
     }
 
+    #[allow(dead_code)]
     pub fn extend_subscription(&mut self, months: i64) {
         let now = Utc::now();
         let base = self.subscription_ends_at.unwrap_or(now);
         self.subscription_ends_at = Some(base + Duration::days(30 * months));
     }
 }
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct FoodLog {
+    pub id: i32,
+    pub chat_id: i64,
+    pub food_name: String,
+    pub calories: Option<f32>,
+    pub proteins: Option<f32>,
+    pub fats: Option<f32>,
+    pub carbs: Option<f32>,
+    pub meal_type: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Данные для новой записи в `food_logs`.
+#[derive(Debug, Clone)]
+pub struct NewFoodLog {
+    pub food_name: String,
+    pub calories: Option<f32>,
+    pub proteins: Option<f32>,
+    pub fats: Option<f32>,
+    pub carbs: Option<f32>,
+    pub meal_type: String,
+    pub eaten_at: DateTime<Utc>,
+    pub micros: Micronutrients,
+    pub provenance: FoodProvenance,
+}
+
+/// Откуда взялась запись дневника: что прислал пользователь и что ответила модель.
+/// Хранится, чтобы запись можно было проверить и пересчитать более новой моделью.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, sqlx::FromRow)]
+pub struct FoodProvenance {
+    /// `text`, `photo`, `voice`, `barcode`, `favorite`, `recipe` или `import`.
+    pub source: Option<String>,
+    /// Текст сообщения или подпись к фото.
+    pub input_text: Option<String>,
+    /// `file_id` фото или голосового сообщения в Telegram.
+    pub telegram_file_id: Option<String>,
+    /// Распознанная речь голосового сообщения.
+    pub transcript: Option<String>,
+    /// Полный ответ, показанный пользователю при записи.
+    pub suggestion: Option<String>,
+    /// Модель, посчитавшая КБЖУ; `None` — без обращения к модели.
+    pub model: Option<String>,
+    /// Модель распознавания речи голосового сообщения.
+    pub transcription_model: Option<String>,
+}
+
+/// Клетчатка, сахар, насыщенные жиры (г), натрий, витамин C, кальций, железо и калий (мг).
+/// `None` — модель не дала оценку.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Micronutrients {
+    pub fiber: Option<f32>,
+    pub sugar: Option<f32>,
+    pub saturated_fat: Option<f32>,
+    pub sodium_mg: Option<f32>,
+    pub vitamin_c_mg: Option<f32>,
+    pub calcium_mg: Option<f32>,
+    pub iron_mg: Option<f32>,
+    pub potassium_mg: Option<f32>,
+}
+
+/// Итоги за один день дневника питания.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DailyTotals {
+    pub date: NaiveDate,
+    pub entries: i64,
+    pub calories: f32,
+    pub proteins: f32,
+    pub fats: f32,
+    pub carbs: f32,
+}
+
+/// Итоги по одному приёму пищи (завтрак, обед, ...).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MealTotals {
+    pub meal_type: String,
+    pub calories: f32,
+    pub proteins: f32,
+    pub fats: f32,
+    pub carbs: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct FavoriteMeal {
+    pub id: i32,
+    pub chat_id: i64,
+    pub name: String,
+    pub calories: Option<f32>,
+    pub proteins: Option<f32>,
+    pub fats: Option<f32>,
+    pub carbs: Option<f32>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Часто повторяющееся блюдо из `food_logs` (`id` — последняя такая запись).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FrequentMeal {
+    pub id: i32,
+    pub food_name: String,
+    pub calories: Option<f32>,
+    pub times: i64,
+}
+
+/// Продукт из локальной базы (КБЖУ на 100 г).
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Food {
+    pub id: i32,
+    pub source: String,
+    pub external_id: Option<String>,
+    pub name: String,
+    pub calories: f32,
+    pub proteins: f32,
+    pub fats: f32,
+    pub carbs: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct FoodAlias {
+    pub food_id: i32,
+    pub alias: String,
+}
+
+/// Упакованный продукт по штрихкоду (КБЖУ на 100 г, как на этикетке).
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Product {
+    pub barcode: String,
+    pub name: String,
+    pub brand: Option<String>,
+    pub serving_size_g: Option<f32>,
+    pub calories: f32,
+    pub proteins: Option<f32>,
+    pub fats: Option<f32>,
+    pub carbs: Option<f32>,
+}
+
+/// Рецепт пользователя; КБЖУ хранится для всего готового блюда.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Recipe {
+    pub id: i32,
+    pub chat_id: i64,
+    pub name: String,
+    pub cooked_weight_g: f32,
+    pub servings: i32,
+    pub calories: f32,
+    pub proteins: f32,
+    pub fats: f32,
+    pub carbs: f32,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct RecipeIngredient {
+    pub description: String,
+    pub calories: f32,
+    pub proteins: f32,
+    pub fats: f32,
+    pub carbs: f32,
+}
+
+/// Рецепт, который пользователь заполняет в диалоге `/recipe new`.
+#[derive(Debug, Clone, Default)]
+pub struct NewRecipe {
+    pub name: String,
+    pub ingredients: Vec<RecipeIngredient>,
+    pub cooked_weight_g: f32,
+    pub servings: i32,
+}
+
+/// Запись журнала веса.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct WeightLog {
+    pub weight_kg: f64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Тренировка или другая активность с оценкой сожжённых калорий.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ActivityLog {
+    pub id: i32,
+    pub chat_id: i64,
+    pub activity: String,
+    pub description: String,
+    pub duration_min: f32,
+    pub distance_km: Option<f32>,
+    pub calories: f32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Данные для новой записи в `activity_logs`.
+#[derive(Debug, Clone)]
+pub struct NewActivityLog {
+    pub activity: String,
+    pub description: String,
+    pub duration_min: f32,
+    pub distance_km: Option<f32>,
+    pub calories: f32,
+}
+
+/// Сессия интервального голодания; `ended_at` пуст, пока голодание идёт.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Fast {
+    pub id: i32,
+    pub chat_id: i64,
+    pub protocol: String,
+    pub target_hours: f32,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub notified: bool,
+}
+
+/// Подписка на регулярные отчёты о прогрессе; время отправки — местное для пользователя.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ReportSettings {
+    pub chat_id: i64,
+    pub weekly: bool,
+    pub monthly: bool,
+    pub send_time: NaiveTime,
+    pub last_weekly_sent: Option<NaiveDate>,
+    pub last_monthly_sent: Option<NaiveDate>,
+}
+
+impl ReportSettings {
+    /// Настройки по умолчанию: отчёты выключены, отправка в 9:00.
+    pub fn new(chat_id: i64) -> Self {
+        ReportSettings {
+            chat_id,
+            weekly: false,
+            monthly: false,
+            send_time: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            last_weekly_sent: None,
+            last_monthly_sent: None,
+        }
+    }
+}
+
+/// Напоминание записать приём пищи; время — местное для пользователя.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct MealReminder {
+    pub id: i32,
+    pub chat_id: i64,
+    pub meal_type: String,
+    pub remind_at: NaiveTime,
+    /// Напоминать, только если приём пищи ещё не записан сегодня.
+    pub only_if_not_logged: bool,
+    /// Местная дата последней отправки (или пропуска, если еда уже записана).
+    pub last_sent: Option<NaiveDate>,
+    pub snoozed_until: Option<DateTime<Utc>>,
+}
+
+/// Настройки ежедневных советов пользователя (хранятся в `users`).
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct TipSettings {
+    pub chat_id: i64,
+    pub enabled: bool,
+    /// Местное время отправки.
+    pub send_time: NaiveTime,
+    pub last_sent: Option<NaiveDate>,
+}
+
+/// Полученный пользователем значок достижения.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct UserBadge {
+    pub chat_id: i64,
+    pub badge: String,
+    pub awarded_at: DateTime<Utc>,
+}
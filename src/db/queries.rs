@@ -1,7 +1,11 @@
-use crate::db::models::User;
+use crate::db::models::{
+    ActivityLog, DailyTotals, FavoriteMeal, Fast, Food, FoodAlias, FoodLog, FoodProvenance, FrequentMeal, MealReminder,
+    MealTotals, Micronutrients, NewActivityLog, NewFoodLog, NewRecipe, Product, Recipe, ReportSettings, TipSettings,
+    User, UserBadge, WeightLog,
+};
 use sqlx::PgPool;
 use std::sync::OnceLock;
-use chrono::{NaiveDate, Utc, DateTime, NaiveDateTime};
+use chrono::{NaiveDate, NaiveTime, Utc, DateTime, NaiveDateTime};
 use chrono::TimeZone;
 
 pub static DB_POOL: OnceLock<PgPool> = OnceLock::new();
@@ -60,6 +64,90 @@ pub async fn update_language(chat_id: i64, lang: &str) -> Result<(), sqlx::Error
     Ok(())
 }
 
+/// Обновляет часовой пояс пользователя (IANA, например `Europe/Moscow`).
+pub async fn update_timezone(chat_id: i64, timezone: &str) -> Result<(), sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    sqlx::query!(
+        "UPDATE users SET timezone = $1, updated_at = now() WHERE chat_id = $2",
+        timezone,
+        chat_id
+    )
+        .execute(pool)
+        .await
+        .map_err(|e| {
+            log::warn!("Failed to update timezone for {}: {}", chat_id, e);
+            e
+        })?;
+
+    Ok(())
+}
+
+/// Добавляет запись в журнал веса и обновляет профиль: вес, ИМТ и норму калорий.
+pub async fn log_weight(
+    chat_id: i64,
+    weight_kg: f64,
+    imt: Option<f64>,
+    calorie_target: Option<i32>,
+) -> Result<(), sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let mut tx = pool.begin().await?;
+
+    sqlx::query!(
+        "INSERT INTO weight_logs (chat_id, weight_kg) VALUES ($1, $2)",
+        chat_id,
+        weight_kg
+    )
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query!(
+        r#"
+        UPDATE users
+        SET weight_kg = $1, imt = $2, calorie_target = $3, updated_at = now()
+        WHERE chat_id = $4
+        "#,
+        weight_kg,
+        imt,
+        calorie_target,
+        chat_id
+    )
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Получает журнал веса за последние `days` суток (от старых к новым).
+pub async fn get_weight_logs(chat_id: i64, days: i32) -> Result<Vec<WeightLog>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let logs = sqlx::query_as!(
+        WeightLog,
+        r#"
+        SELECT weight_kg, created_at
+        FROM weight_logs
+        WHERE chat_id = $1 AND created_at > now() - make_interval(days => $2)
+        ORDER BY created_at
+        "#,
+        chat_id,
+        days
+    )
+        .fetch_all(pool)
+        .await?;
+
+    Ok(logs)
+}
+
 /// Получает данные пользователя по chat_id.
 pub async fn get_user(chat_id: i64) -> Result<Option<User>, sqlx::Error> {
     let Some(pool) = DB_POOL.get() else {
@@ -70,7 +158,8 @@ pub async fn get_user(chat_id: i64) -> Result<Option<User>, sqlx::Error> {
         User,
         r#"
         SELECT id, chat_id, username, age, weight_kg, height_cm, gender,
-               activity_level, goal, imt, created_at, language_code, updated_at, subscription_ends_at
+               activity_level, goal, imt, created_at, language_code, updated_at, subscription_ends_at,
+               timezone, calorie_target
         FROM users
         WHERE chat_id = $1
         "#,
@@ -86,22 +175,12 @@ pub async fn get_user(chat_id: i64) -> Result<Option<User>, sqlx::Error> {
     Ok(user)
 }
 
-/// Получает суммарные калории и БЖУ за текущие сутки.
+/// Получает суммарные калории и БЖУ за текущий день по местному времени пользователя.
 pub async fn get_daily_summary(chat_id: i64) -> Result<(f32, f32, f32, f32), sqlx::Error> {
     let Some(pool) = DB_POOL.get() else {
         return Err(sqlx::Error::PoolTimedOut);
     };
 
-    let today = Utc::now().date_naive();
-    let start: DateTime<Utc> = DateTime::from_naive_utc_and_offset(
-        today.and_hms_opt(0, 0, 0).unwrap(),
-        Utc,
-    );
-    let end: DateTime<Utc> = DateTime::from_naive_utc_and_offset(
-        today.and_hms_opt(23, 59, 59).unwrap(),
-        Utc,
-    );
-
     let result = sqlx::query!(
         r#"
         SELECT
@@ -111,11 +190,9 @@ pub async fn get_daily_summary(chat_id: i64) -> Result<(f32, f32, f32, f32), sql
             COALESCE(SUM(carbs), 0) as carbs
         FROM food_logs
         WHERE chat_id = $1
-        AND created_at BETWEEN $2 AND $3
+        AND user_local_date(chat_id, created_at) = user_local_date($1, now())
         "#,
-        chat_id,
-        start,
-        end
+        chat_id
     )
         .fetch_one(pool)
         .await?;
@@ -128,77 +205,2059 @@ pub async fn get_daily_summary(chat_id: i64) -> Result<(f32, f32, f32, f32), sql
     ))
 }
 
-/// Добавляет запись о приёме пищи в лог.
-pub async fn add_food_log(
-    chat_id: i64,
-    food_name: &str,
-    calories: Option<f32>,
-    proteins: Option<f32>,
-    fats: Option<f32>,
-    carbs: Option<f32>,
-) -> Result<(), sqlx::Error> {
+/// Добавляет запись о приёме пищи в лог. Возвращает id записи.
+pub async fn add_food_log(chat_id: i64, entry: &NewFoodLog) -> Result<i32, sqlx::Error> {
     let Some(pool) = DB_POOL.get() else {
         return Err(sqlx::Error::PoolTimedOut);
     };
 
-    sqlx::query!(
+    let id = sqlx::query_scalar!(
         r#"
-        INSERT INTO food_logs (chat_id, food_name, calories, proteins, fats, carbs, created_at)
-        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        INSERT INTO food_logs (
+            chat_id, food_name, calories, proteins, fats, carbs, meal_type, created_at,
+            fiber, sugar, saturated_fat, sodium_mg, vitamin_c_mg, calcium_mg, iron_mg, potassium_mg,
+            source, input_text, telegram_file_id, transcript, suggestion, model, transcription_model
+        )
+        VALUES (
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16,
+            $17, $18, $19, $20, $21, $22, $23
+        )
+        RETURNING id
         "#,
         chat_id,
-        food_name,
-        calories,
-        proteins,
-        fats,
-        carbs,
-        Utc::now()
+        entry.food_name,
+        entry.calories,
+        entry.proteins,
+        entry.fats,
+        entry.carbs,
+        entry.meal_type,
+        entry.eaten_at,
+        entry.micros.fiber,
+        entry.micros.sugar,
+        entry.micros.saturated_fat,
+        entry.micros.sodium_mg,
+        entry.micros.vitamin_c_mg,
+        entry.micros.calcium_mg,
+        entry.micros.iron_mg,
+        entry.micros.potassium_mg,
+        entry.provenance.source,
+        entry.provenance.input_text,
+        entry.provenance.telegram_file_id,
+        entry.provenance.transcript,
+        entry.provenance.suggestion,
+        entry.provenance.model,
+        entry.provenance.transcription_model
     )
-        .execute(pool)
+        .fetch_one(pool)
         .await
         .map_err(|e| {
             log::warn!("Failed to insert food log for {}: {}", chat_id, e);
             e
         })?;
 
-    Ok(())
+    Ok(id)
+}
+
+/// Добавляет записи одной транзакцией (импорт дневника). Возвращает количество добавленных.
+pub async fn add_food_logs(chat_id: i64, entries: &[NewFoodLog]) -> Result<u64, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let mut tx = pool.begin().await?;
+    for entry in entries {
+        sqlx::query!(
+            r#"
+            INSERT INTO food_logs (
+                chat_id, food_name, calories, proteins, fats, carbs, meal_type, created_at,
+                fiber, sugar, saturated_fat, sodium_mg, vitamin_c_mg, calcium_mg, iron_mg, potassium_mg,
+                source, input_text
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)
+            "#,
+            chat_id,
+            entry.food_name,
+            entry.calories,
+            entry.proteins,
+            entry.fats,
+            entry.carbs,
+            entry.meal_type,
+            entry.eaten_at,
+            entry.micros.fiber,
+            entry.micros.sugar,
+            entry.micros.saturated_fat,
+            entry.micros.sodium_mg,
+            entry.micros.vitamin_c_mg,
+            entry.micros.calcium_mg,
+            entry.micros.iron_mg,
+            entry.micros.potassium_mg,
+            entry.provenance.source,
+            entry.provenance.input_text
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+
+    Ok(entries.len() as u64)
+}
+
+/// Получает запись о еде по id.
+pub async fn get_food_log(chat_id: i64, log_id: i32) -> Result<Option<FoodLog>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let log = sqlx::query_as!(
+        FoodLog,
+        r#"
+        SELECT id, chat_id, food_name, calories, proteins, fats, carbs, meal_type, created_at
+        FROM food_logs
+        WHERE id = $1 AND chat_id = $2
+        "#,
+        log_id,
+        chat_id
+    )
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(log)
+}
+
+/// Микронутриенты записи дневника — копируются при повторной записи блюда.
+pub async fn get_food_log_micronutrients(
+    chat_id: i64,
+    log_id: i32,
+) -> Result<Micronutrients, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let micros = sqlx::query_as!(
+        Micronutrients,
+        r#"
+        SELECT fiber, sugar, saturated_fat, sodium_mg, vitamin_c_mg, calcium_mg, iron_mg, potassium_mg
+        FROM food_logs
+        WHERE id = $1 AND chat_id = $2
+        "#,
+        log_id,
+        chat_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(micros.unwrap_or_default())
+}
+
+/// Происхождение записи дневника (исходный ввод, ответ модели, модель).
+pub async fn get_food_log_provenance(
+    chat_id: i64,
+    log_id: i32,
+) -> Result<Option<FoodProvenance>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let provenance = sqlx::query_as!(
+        FoodProvenance,
+        r#"
+        SELECT source, input_text, telegram_file_id, transcript, suggestion, model, transcription_model
+        FROM food_logs
+        WHERE id = $1 AND chat_id = $2
+        "#,
+        log_id,
+        chat_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(provenance)
+}
+
+/// Переносит запись на другое время и обновляет тип приёма пищи.
+pub async fn update_food_log_time(
+    chat_id: i64,
+    log_id: i32,
+    eaten_at: DateTime<Utc>,
+    meal_type: &str,
+) -> Result<bool, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let result = sqlx::query!(
+        "UPDATE food_logs SET created_at = $1, meal_type = $2 WHERE id = $3 AND chat_id = $4",
+        eaten_at,
+        meal_type,
+        log_id,
+        chat_id
+    )
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
 }
 
-/// Получает суммарные калории по дням за последние 7 суток.
-pub async fn get_weekly_calories(chat_id: i64) -> Result<Vec<(NaiveDate, f32)>, sqlx::Error> {
+/// Меняет тип приёма пищи у записи. Возвращает `false`, если запись не найдена.
+pub async fn update_food_log_meal_type(
+    chat_id: i64,
+    log_id: i32,
+    meal_type: &str,
+) -> Result<bool, sqlx::Error> {
     let Some(pool) = DB_POOL.get() else {
         return Err(sqlx::Error::PoolTimedOut);
     };
 
     let result = sqlx::query!(
+        "UPDATE food_logs SET meal_type = $1 WHERE id = $2 AND chat_id = $3",
+        meal_type,
+        log_id,
+        chat_id
+    )
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Получает итоги по приёмам пищи за указанный день.
+pub async fn get_meal_summary(
+    chat_id: i64,
+    date: NaiveDate,
+) -> Result<Vec<MealTotals>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let meals = sqlx::query_as!(
+        MealTotals,
         r#"
         SELECT
-            DATE(created_at) as date,
-            SUM(calories) as total_calories
+            meal_type,
+            COALESCE(SUM(calories), 0) as "calories!",
+            COALESCE(SUM(proteins), 0) as "proteins!",
+            COALESCE(SUM(fats), 0) as "fats!",
+            COALESCE(SUM(carbs), 0) as "carbs!"
         FROM food_logs
-        WHERE chat_id = $1 AND created_at > now() - interval '7 days'
-        GROUP BY date
-        ORDER BY date
+        WHERE chat_id = $1 AND user_local_date(chat_id, created_at) = $2
+        GROUP BY meal_type
         "#,
-        chat_id
+        chat_id,
+        date
     )
         .fetch_all(pool)
         .await?;
 
-    Ok(result
-        .into_iter()
-        .map(|r| (r.date.unwrap(), r.total_calories.unwrap_or(0.0)))
-        .collect())
+    Ok(meals)
 }
 
-/// Удаляет записи за текущие сутки.
-pub async fn reset_today_logs(chat_id: i64) -> Result<(), sqlx::Error> {
+/// Суммирует КБЖУ за период с `from` по `to` включительно.
+pub async fn get_period_totals(
+    chat_id: i64,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<(f32, f32, f32, f32), sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let result = sqlx::query!(
+        r#"
+        SELECT
+            COALESCE(SUM(calories), 0) as "calories!",
+            COALESCE(SUM(proteins), 0) as "proteins!",
+            COALESCE(SUM(fats), 0) as "fats!",
+            COALESCE(SUM(carbs), 0) as "carbs!"
+        FROM food_logs
+        WHERE chat_id = $1 AND user_local_date(chat_id, created_at) BETWEEN $2 AND $3
+        "#,
+        chat_id,
+        from,
+        to
+    )
+        .fetch_one(pool)
+        .await?;
+
+    Ok((result.calories, result.proteins, result.fats, result.carbs))
+}
+
+/// Суммирует микронутриенты за день. Поле остаётся `None`, если ни в одной записи нет оценки.
+pub async fn get_daily_micronutrients(
+    chat_id: i64,
+    date: NaiveDate,
+) -> Result<Micronutrients, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let micros = sqlx::query_as!(
+        Micronutrients,
+        r#"
+        SELECT
+            SUM(fiber) as fiber,
+            SUM(sugar) as sugar,
+            SUM(saturated_fat) as saturated_fat,
+            SUM(sodium_mg) as sodium_mg,
+            SUM(vitamin_c_mg) as vitamin_c_mg,
+            SUM(calcium_mg) as calcium_mg,
+            SUM(iron_mg) as iron_mg,
+            SUM(potassium_mg) as potassium_mg
+        FROM food_logs
+        WHERE chat_id = $1 AND user_local_date(chat_id, created_at) = $2
+        "#,
+        chat_id,
+        date
+    )
+        .fetch_one(pool)
+        .await?;
+
+    Ok(micros)
+}
+
+/// Получает итоги КБЖУ по дням за период с `from` по `to` включительно (дни без записей пропускаются).
+pub async fn get_daily_totals_range(
+    chat_id: i64,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<DailyTotals>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let days = sqlx::query_as!(
+        DailyTotals,
+        r#"
+        SELECT
+            user_local_date(chat_id, created_at) as "date!",
+            COUNT(*) as "entries!",
+            COALESCE(SUM(calories), 0) as "calories!",
+            COALESCE(SUM(proteins), 0) as "proteins!",
+            COALESCE(SUM(fats), 0) as "fats!",
+            COALESCE(SUM(carbs), 0) as "carbs!"
+        FROM food_logs
+        WHERE chat_id = $1 AND user_local_date(chat_id, created_at) BETWEEN $2 AND $3
+        GROUP BY 1
+        ORDER BY 1
+        "#,
+        chat_id,
+        from,
+        to
+    )
+        .fetch_all(pool)
+        .await?;
+
+    Ok(days)
+}
+
+/// Добавляет запись о выпитой воде.
+pub async fn add_water_log(chat_id: i64, amount_ml: i32) -> Result<(), sqlx::Error> {
     let Some(pool) = DB_POOL.get() else {
         return Err(sqlx::Error::PoolTimedOut);
     };
 
     sqlx::query!(
-        "DELETE FROM food_logs WHERE chat_id = $1 AND created_at::date = CURRENT_DATE",
+        "INSERT INTO water_logs (chat_id, amount_ml) VALUES ($1, $2)",
+        chat_id,
+        amount_ml
+    )
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Удаляет последнюю запись о воде за день.
+pub async fn delete_last_water_log(chat_id: i64, date: NaiveDate) -> Result<bool, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM water_logs
+        WHERE id = (
+            SELECT id FROM water_logs
+            WHERE chat_id = $1 AND user_local_date(chat_id, created_at) = $2
+            ORDER BY created_at DESC, id DESC
+            LIMIT 1
+        )
+        "#,
+        chat_id,
+        date
+    )
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Получает количество выпитой воды (мл) за день.
+pub async fn get_daily_water(chat_id: i64, date: NaiveDate) -> Result<i64, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let total = sqlx::query_scalar!(
+        r#"
+        SELECT COALESCE(SUM(amount_ml), 0) as "total!"
+        FROM water_logs
+        WHERE chat_id = $1 AND user_local_date(chat_id, created_at) = $2
+        "#,
+        chat_id,
+        date
+    )
+        .fetch_one(pool)
+        .await?;
+
+    Ok(total)
+}
+
+/// Получает количество воды (мл) по дням за последние 7 суток.
+pub async fn get_weekly_water(chat_id: i64) -> Result<Vec<(NaiveDate, i64)>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let result = sqlx::query!(
+        r#"
+        SELECT
+            user_local_date(chat_id, created_at) as "date!",
+            SUM(amount_ml) as "total!"
+        FROM water_logs
+        WHERE chat_id = $1 AND user_local_date(chat_id, created_at) > user_local_date($1, now()) - 7
+        GROUP BY 1
+        ORDER BY 1
+        "#,
+        chat_id
+    )
+        .fetch_all(pool)
+        .await?;
+
+    Ok(result.into_iter().map(|r| (r.date, r.total)).collect())
+}
+
+/// Добавляет запись об активности.
+pub async fn add_activity_log(chat_id: i64, entry: &NewActivityLog) -> Result<i32, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO activity_logs (chat_id, activity, description, duration_min, distance_km, calories)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING id
+        "#,
+        chat_id,
+        entry.activity,
+        entry.description,
+        entry.duration_min,
+        entry.distance_km,
+        entry.calories
+    )
+        .fetch_one(pool)
+        .await?;
+
+    Ok(id)
+}
+
+/// Получает активности за день.
+pub async fn get_activity_logs_by_date(
+    chat_id: i64,
+    date: NaiveDate,
+) -> Result<Vec<ActivityLog>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let logs = sqlx::query_as!(
+        ActivityLog,
+        r#"
+        SELECT id, chat_id, activity, description, duration_min, distance_km, calories, created_at
+        FROM activity_logs
+        WHERE chat_id = $1 AND user_local_date(chat_id, created_at) = $2
+        ORDER BY created_at
+        "#,
+        chat_id,
+        date
+    )
+        .fetch_all(pool)
+        .await?;
+
+    Ok(logs)
+}
+
+/// Получает сожжённые за день калории.
+pub async fn get_daily_burned(chat_id: i64, date: NaiveDate) -> Result<f32, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let burned = sqlx::query_scalar!(
+        r#"
+        SELECT COALESCE(SUM(calories), 0) as "burned!"
+        FROM activity_logs
+        WHERE chat_id = $1 AND user_local_date(chat_id, created_at) = $2
+        "#,
+        chat_id,
+        date
+    )
+        .fetch_one(pool)
+        .await?;
+
+    Ok(burned)
+}
+
+/// Получает сожжённые калории по дням за период с `from` по `to` включительно.
+pub async fn get_burned_by_day(
+    chat_id: i64,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<Vec<(NaiveDate, f32)>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let result = sqlx::query!(
+        r#"
+        SELECT
+            user_local_date(chat_id, created_at) as "date!",
+            SUM(calories) as "burned!"
+        FROM activity_logs
+        WHERE chat_id = $1 AND user_local_date(chat_id, created_at) BETWEEN $2 AND $3
+        GROUP BY 1
+        ORDER BY 1
+        "#,
+        chat_id,
+        from,
+        to
+    )
+        .fetch_all(pool)
+        .await?;
+
+    Ok(result.into_iter().map(|r| (r.date, r.burned)).collect())
+}
+
+/// Начинает голодание. Возвращает `None`, если у пользователя уже идёт другое.
+pub async fn start_fast(
+    chat_id: i64,
+    protocol: &str,
+    target_hours: f32,
+    started_at: DateTime<Utc>,
+) -> Result<Option<i32>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO fasts (chat_id, protocol, target_hours, started_at)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (chat_id) WHERE ended_at IS NULL DO NOTHING
+        RETURNING id
+        "#,
+        chat_id,
+        protocol,
+        target_hours,
+        started_at
+    )
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(id)
+}
+
+/// Получает текущее (незавершённое) голодание.
+pub async fn get_active_fast(chat_id: i64) -> Result<Option<Fast>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let fast = sqlx::query_as!(
+        Fast,
+        r#"
+        SELECT id, chat_id, protocol, target_hours, started_at, ended_at, notified
+        FROM fasts
+        WHERE chat_id = $1 AND ended_at IS NULL
+        "#,
+        chat_id
+    )
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(fast)
+}
+
+/// Завершает текущее голодание, если оно началось раньше `ended_at`.
+/// Возвращает завершённую сессию или `None`, если завершать нечего.
+pub async fn end_fast(chat_id: i64, ended_at: DateTime<Utc>) -> Result<Option<Fast>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let fast = sqlx::query_as!(
+        Fast,
+        r#"
+        UPDATE fasts
+        SET ended_at = $2
+        WHERE chat_id = $1 AND ended_at IS NULL AND started_at < $2
+        RETURNING id, chat_id, protocol, target_hours, started_at, ended_at, notified
+        "#,
+        chat_id,
+        ended_at
+    )
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(fast)
+}
+
+/// Получает завершённые голодания за последние `days` дней (от новых к старым).
+pub async fn get_finished_fasts(chat_id: i64, days: i32) -> Result<Vec<Fast>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let fasts = sqlx::query_as!(
+        Fast,
+        r#"
+        SELECT id, chat_id, protocol, target_hours, started_at, ended_at, notified
+        FROM fasts
+        WHERE chat_id = $1
+          AND ended_at IS NOT NULL
+          AND started_at > now() - make_interval(days => $2)
+        ORDER BY started_at DESC
+        "#,
+        chat_id,
+        days
+    )
+        .fetch_all(pool)
+        .await?;
+
+    Ok(fasts)
+}
+
+/// Получает идущие голодания, которые достигли цели, но о которых ещё не сообщили.
+pub async fn get_due_fasts() -> Result<Vec<Fast>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let fasts = sqlx::query_as!(
+        Fast,
+        r#"
+        SELECT id, chat_id, protocol, target_hours, started_at, ended_at, notified
+        FROM fasts
+        WHERE ended_at IS NULL
+          AND NOT notified
+          AND started_at + make_interval(secs => target_hours * 3600) <= now()
+        "#
+    )
+        .fetch_all(pool)
+        .await?;
+
+    Ok(fasts)
+}
+
+/// Отмечает, что уведомление о достижении цели отправлено.
+pub async fn mark_fast_notified(fast_id: i32) -> Result<(), sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    sqlx::query!("UPDATE fasts SET notified = TRUE WHERE id = $1", fast_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Получает выбранный пользователем протокол голодания (например, `16:8`).
+pub async fn get_fasting_protocol(chat_id: i64) -> Result<Option<String>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let protocol = sqlx::query_scalar!(
+        "SELECT fasting_protocol FROM users WHERE chat_id = $1",
+        chat_id
+    )
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(protocol.flatten())
+}
+
+/// Сохраняет протокол голодания по умолчанию.
+pub async fn set_fasting_protocol(chat_id: i64, protocol: &str) -> Result<(), sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    sqlx::query!(
+        "UPDATE users SET fasting_protocol = $1 WHERE chat_id = $2",
+        protocol,
+        chat_id
+    )
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Получает настройки отчётов пользователя.
+pub async fn get_report_settings(chat_id: i64) -> Result<Option<ReportSettings>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let settings = sqlx::query_as!(
+        ReportSettings,
+        r#"
+        SELECT chat_id, weekly, monthly, send_time, last_weekly_sent, last_monthly_sent
+        FROM report_settings
+        WHERE chat_id = $1
+        "#,
+        chat_id
+    )
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(settings)
+}
+
+/// Получает всех пользователей, подписанных хотя бы на один отчёт.
+pub async fn get_enabled_report_settings() -> Result<Vec<ReportSettings>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let settings = sqlx::query_as!(
+        ReportSettings,
+        r#"
+        SELECT chat_id, weekly, monthly, send_time, last_weekly_sent, last_monthly_sent
+        FROM report_settings
+        WHERE weekly OR monthly
+        "#
+    )
+        .fetch_all(pool)
+        .await?;
+
+    Ok(settings)
+}
+
+/// Сохраняет подписки и время отправки (даты последних отчётов не меняются).
+pub async fn save_report_settings(settings: &ReportSettings) -> Result<(), sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    sqlx::query!(
+        r#"
+        INSERT INTO report_settings (chat_id, weekly, monthly, send_time)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (chat_id) DO UPDATE
+        SET weekly = EXCLUDED.weekly,
+            monthly = EXCLUDED.monthly,
+            send_time = EXCLUDED.send_time
+        "#,
+        settings.chat_id,
+        settings.weekly,
+        settings.monthly,
+        settings.send_time
+    )
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Запоминает дату (местную), за которую уже отправлен недельный или месячный отчёт.
+pub async fn mark_report_sent(chat_id: i64, weekly: bool, date: NaiveDate) -> Result<(), sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    sqlx::query!(
+        r#"
+        UPDATE report_settings
+        SET last_weekly_sent = CASE WHEN $2 THEN $3 ELSE last_weekly_sent END,
+            last_monthly_sent = CASE WHEN $2 THEN last_monthly_sent ELSE $3 END
+        WHERE chat_id = $1
+        "#,
+        chat_id,
+        weekly,
+        date
+    )
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Создаёт или меняет напоминание для приёма пищи. Возвращает id.
+pub async fn upsert_meal_reminder(
+    chat_id: i64,
+    meal_type: &str,
+    remind_at: NaiveTime,
+    only_if_not_logged: bool,
+) -> Result<i32, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO meal_reminders (chat_id, meal_type, remind_at, only_if_not_logged)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (chat_id, meal_type) DO UPDATE
+        SET remind_at = EXCLUDED.remind_at,
+            only_if_not_logged = EXCLUDED.only_if_not_logged,
+            snoozed_until = NULL
+        RETURNING id
+        "#,
+        chat_id,
+        meal_type,
+        remind_at,
+        only_if_not_logged
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(id)
+}
+
+/// Получает напоминания пользователя.
+pub async fn get_meal_reminders(chat_id: i64) -> Result<Vec<MealReminder>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let reminders = sqlx::query_as!(
+        MealReminder,
+        r#"
+        SELECT id, chat_id, meal_type, remind_at, only_if_not_logged, last_sent, snoozed_until
+        FROM meal_reminders
+        WHERE chat_id = $1
+        ORDER BY remind_at
+        "#,
+        chat_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(reminders)
+}
+
+/// Получает все напоминания — для планировщика.
+pub async fn get_all_meal_reminders() -> Result<Vec<MealReminder>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let reminders = sqlx::query_as!(
+        MealReminder,
+        r#"
+        SELECT id, chat_id, meal_type, remind_at, only_if_not_logged, last_sent, snoozed_until
+        FROM meal_reminders
+        ORDER BY chat_id
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(reminders)
+}
+
+/// Удаляет напоминание. Возвращает `false`, если его не было.
+pub async fn delete_meal_reminder(chat_id: i64, reminder_id: i32) -> Result<bool, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let result = sqlx::query!(
+        "DELETE FROM meal_reminders WHERE id = $1 AND chat_id = $2",
+        reminder_id,
+        chat_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Отмечает напоминание обработанным за местную дату `date` и сбрасывает отложенную отправку.
+pub async fn mark_reminder_sent(reminder_id: i32, date: NaiveDate) -> Result<(), sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    sqlx::query!(
+        "UPDATE meal_reminders SET last_sent = $2, snoozed_until = NULL WHERE id = $1",
+        reminder_id,
+        date
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Снимает отсрочку напоминания, не трогая дату последней отправки.
+pub async fn clear_reminder_snooze(reminder_id: i32) -> Result<(), sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    sqlx::query!(
+        "UPDATE meal_reminders SET snoozed_until = NULL WHERE id = $1",
+        reminder_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Откладывает напоминание до `until`. Возвращает `false`, если напоминание не найдено.
+pub async fn snooze_meal_reminder(
+    chat_id: i64,
+    reminder_id: i32,
+    until: DateTime<Utc>,
+) -> Result<bool, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let result = sqlx::query!(
+        "UPDATE meal_reminders SET snoozed_until = $3 WHERE id = $1 AND chat_id = $2",
+        reminder_id,
+        chat_id,
+        until
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Получает тихие часы пользователя (местное время начала и конца).
+pub async fn get_quiet_hours(chat_id: i64) -> Result<Option<(NaiveTime, NaiveTime)>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let row = sqlx::query!(
+        "SELECT quiet_hours_start, quiet_hours_end FROM users WHERE chat_id = $1",
+        chat_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.and_then(|r| r.quiet_hours_start.zip(r.quiet_hours_end)))
+}
+
+/// Сохраняет тихие часы; `None` отключает их.
+pub async fn set_quiet_hours(
+    chat_id: i64,
+    quiet_hours: Option<(NaiveTime, NaiveTime)>,
+) -> Result<(), sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let (start, end) = quiet_hours.unzip();
+    sqlx::query!(
+        "UPDATE users SET quiet_hours_start = $1, quiet_hours_end = $2 WHERE chat_id = $3",
+        start,
+        end,
+        chat_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Проверяет, записан ли приём пищи `meal_type` начиная с момента `since`.
+pub async fn has_meal_logged_since(
+    chat_id: i64,
+    meal_type: &str,
+    since: DateTime<Utc>,
+) -> Result<bool, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let exists = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM food_logs
+            WHERE chat_id = $1 AND meal_type = $2 AND created_at >= $3
+        ) as "exists!"
+        "#,
+        chat_id,
+        meal_type,
+        since
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(exists)
+}
+
+/// Получает настройки ежедневных советов; `None`, если пользователь не зарегистрирован.
+pub async fn get_tip_settings(chat_id: i64) -> Result<Option<TipSettings>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let settings = sqlx::query_as!(
+        TipSettings,
+        r#"
+        SELECT chat_id, tips_enabled as enabled, tip_time as send_time, last_tip_sent as last_sent
+        FROM users
+        WHERE chat_id = $1
+        "#,
+        chat_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(settings)
+}
+
+/// Получает пользователей, которым можно отправить совет: советы не отключены
+/// и за последние `active_days` дней была хотя бы одна запись о еде.
+pub async fn get_tip_recipients(active_days: i32) -> Result<Vec<TipSettings>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let recipients = sqlx::query_as!(
+        TipSettings,
+        r#"
+        SELECT u.chat_id, u.tips_enabled as enabled, u.tip_time as send_time, u.last_tip_sent as last_sent
+        FROM users u
+        WHERE u.tips_enabled
+          AND EXISTS (
+              SELECT 1 FROM food_logs f
+              WHERE f.chat_id = u.chat_id
+                AND f.created_at > now() - make_interval(days => $1)
+          )
+        "#,
+        active_days
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(recipients)
+}
+
+/// Включает или отключает ежедневные советы.
+pub async fn set_tips_enabled(chat_id: i64, enabled: bool) -> Result<(), sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    sqlx::query!(
+        "UPDATE users SET tips_enabled = $1 WHERE chat_id = $2",
+        enabled,
+        chat_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Сохраняет местное время отправки советов.
+pub async fn set_tip_time(chat_id: i64, send_time: NaiveTime) -> Result<(), sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    sqlx::query!(
+        "UPDATE users SET tip_time = $1 WHERE chat_id = $2",
+        send_time,
+        chat_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Отмечает, что совет за местную дату `date` отправлен.
+pub async fn mark_tip_day_sent(chat_id: i64, date: NaiveDate) -> Result<(), sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    sqlx::query!(
+        "UPDATE users SET last_tip_sent = $1 WHERE chat_id = $2",
+        date,
+        chat_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Получает, когда пользователю последний раз показывался каждый совет.
+pub async fn get_tip_history(chat_id: i64) -> Result<Vec<(String, DateTime<Utc>)>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let rows = sqlx::query!(
+        "SELECT tip_id, sent_at FROM tip_history WHERE chat_id = $1",
+        chat_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(|r| (r.tip_id, r.sent_at)).collect())
+}
+
+/// Запоминает показ совета.
+pub async fn record_tip_sent(chat_id: i64, tip_id: &str) -> Result<(), sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    sqlx::query!(
+        r#"
+        INSERT INTO tip_history (chat_id, tip_id, sent_at)
+        VALUES ($1, $2, now())
+        ON CONFLICT (chat_id, tip_id) DO UPDATE SET sent_at = EXCLUDED.sent_at
+        "#,
+        chat_id,
+        tip_id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Получает количество записей о приёмах пищи за всё время.
+pub async fn count_food_logs(chat_id: i64) -> Result<i64, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let count = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) as "count!" FROM food_logs WHERE chat_id = $1"#,
+        chat_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count)
+}
+
+/// Получает значки пользователя в порядке получения.
+pub async fn get_badges(chat_id: i64) -> Result<Vec<UserBadge>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let badges = sqlx::query_as!(
+        UserBadge,
+        r#"
+        SELECT chat_id, badge, awarded_at
+        FROM user_badges
+        WHERE chat_id = $1
+        ORDER BY awarded_at, badge
+        "#,
+        chat_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(badges)
+}
+
+/// Есть ли у пользователя хотя бы один значок.
+pub async fn has_badges(chat_id: i64) -> Result<bool, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let exists = sqlx::query_scalar!(
+        r#"SELECT EXISTS (SELECT 1 FROM user_badges WHERE chat_id = $1) as "exists!""#,
+        chat_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(exists)
+}
+
+/// Выдаёт значки; возвращает только те, которых у пользователя ещё не было.
+pub async fn award_badges(chat_id: i64, badges: &[String]) -> Result<Vec<String>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let awarded = sqlx::query_scalar!(
+        r#"
+        INSERT INTO user_badges (chat_id, badge)
+        SELECT $1, badge FROM UNNEST($2::text[]) AS badge
+        ON CONFLICT (chat_id, badge) DO NOTHING
+        RETURNING badge
+        "#,
+        chat_id,
+        badges
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(awarded)
+}
+
+/// Получает количество дней, за которые есть записи о приёмах пищи.
+pub async fn count_history_days(chat_id: i64) -> Result<i64, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let count = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(DISTINCT user_local_date(chat_id, created_at)) as "count!"
+        FROM food_logs
+        WHERE chat_id = $1
+        "#,
+        chat_id
+    )
+        .fetch_one(pool)
+        .await?;
+
+    Ok(count)
+}
+
+/// Получает итоги по дням (от новых к старым) для страницы истории.
+pub async fn get_history_days(
+    chat_id: i64,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<DailyTotals>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let days = sqlx::query_as!(
+        DailyTotals,
+        r#"
+        SELECT
+            user_local_date(chat_id, created_at) as "date!",
+            COUNT(*) as "entries!",
+            COALESCE(SUM(calories), 0) as "calories!",
+            COALESCE(SUM(proteins), 0) as "proteins!",
+            COALESCE(SUM(fats), 0) as "fats!",
+            COALESCE(SUM(carbs), 0) as "carbs!"
+        FROM food_logs
+        WHERE chat_id = $1
+        GROUP BY 1
+        ORDER BY 1 DESC
+        LIMIT $2 OFFSET $3
+        "#,
+        chat_id,
+        limit,
+        offset
+    )
+        .fetch_all(pool)
+        .await?;
+
+    Ok(days)
+}
+
+/// Получает все записи о еде за указанный день.
+pub async fn get_food_logs_by_date(
+    chat_id: i64,
+    date: NaiveDate,
+) -> Result<Vec<FoodLog>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let logs = sqlx::query_as!(
+        FoodLog,
+        r#"
+        SELECT id, chat_id, food_name, calories, proteins, fats, carbs, meal_type, created_at
+        FROM food_logs
+        WHERE chat_id = $1 AND user_local_date(chat_id, created_at) = $2
+        ORDER BY created_at
+        "#,
+        chat_id,
+        date
+    )
+        .fetch_all(pool)
+        .await?;
+
+    Ok(logs)
+}
+
+/// Получает записи о еде за период `[from, to)` — для экспорта дневника.
+pub async fn get_food_logs_between(
+    chat_id: i64,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<FoodLog>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let logs = sqlx::query_as!(
+        FoodLog,
+        r#"
+        SELECT id, chat_id, food_name, calories, proteins, fats, carbs, meal_type, created_at
+        FROM food_logs
+        WHERE chat_id = $1 AND created_at >= $2 AND created_at < $3
+        ORDER BY created_at
+        "#,
+        chat_id,
+        from,
+        to
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(logs)
+}
+
+/// Получает измерения веса за период `[from, to)`.
+pub async fn get_weight_logs_between(
+    chat_id: i64,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<WeightLog>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let logs = sqlx::query_as!(
+        WeightLog,
+        r#"
+        SELECT weight_kg, created_at
+        FROM weight_logs
+        WHERE chat_id = $1 AND created_at >= $2 AND created_at < $3
+        ORDER BY created_at
+        "#,
+        chat_id,
+        from,
+        to
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(logs)
+}
+
+/// Получает тренировки за период `[from, to)`.
+pub async fn get_activity_logs_between(
+    chat_id: i64,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<ActivityLog>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let logs = sqlx::query_as!(
+        ActivityLog,
+        r#"
+        SELECT id, chat_id, activity, description, duration_min, distance_km, calories, created_at
+        FROM activity_logs
+        WHERE chat_id = $1 AND created_at >= $2 AND created_at < $3
+        ORDER BY created_at
+        "#,
+        chat_id,
+        from,
+        to
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(logs)
+}
+
+/// Все данные, хранящиеся о пользователе, одним JSON-документом (для `/mydata`).
+pub async fn get_account_data(chat_id: i64) -> Result<String, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let data = sqlx::query_scalar!(
+        r#"
+        SELECT json_build_object(
+            'chat_id', $1::BIGINT,
+            'exported_at', now(),
+            'user', (SELECT row_to_json(u) FROM users u WHERE u.chat_id = $1),
+            'food_logs', (SELECT COALESCE(json_agg(t ORDER BY t.created_at), '[]') FROM food_logs t WHERE t.chat_id = $1),
+            'water_logs', (SELECT COALESCE(json_agg(t ORDER BY t.created_at), '[]') FROM water_logs t WHERE t.chat_id = $1),
+            'weight_logs', (SELECT COALESCE(json_agg(t ORDER BY t.created_at), '[]') FROM weight_logs t WHERE t.chat_id = $1),
+            'activity_logs', (SELECT COALESCE(json_agg(t ORDER BY t.created_at), '[]') FROM activity_logs t WHERE t.chat_id = $1),
+            'fasts', (SELECT COALESCE(json_agg(t ORDER BY t.started_at), '[]') FROM fasts t WHERE t.chat_id = $1),
+            'favorite_meals', (SELECT COALESCE(json_agg(t ORDER BY t.id), '[]') FROM favorite_meals t WHERE t.chat_id = $1),
+            'recipes', (
+                SELECT COALESCE(json_agg(r ORDER BY r.id), '[]')
+                FROM (
+                    SELECT recipes.*, (
+                        SELECT COALESCE(json_agg(i ORDER BY i.id), '[]')
+                        FROM recipe_ingredients i
+                        WHERE i.recipe_id = recipes.id
+                    ) AS ingredients
+                    FROM recipes
+                    WHERE recipes.chat_id = $1
+                ) r
+            ),
+            'meal_reminders', (SELECT COALESCE(json_agg(t ORDER BY t.id), '[]') FROM meal_reminders t WHERE t.chat_id = $1),
+            'report_settings', (SELECT row_to_json(t) FROM report_settings t WHERE t.chat_id = $1),
+            'tip_history', (SELECT COALESCE(json_agg(t ORDER BY t.sent_at), '[]') FROM tip_history t WHERE t.chat_id = $1),
+            'badges', (SELECT COALESCE(json_agg(t ORDER BY t.awarded_at), '[]') FROM user_badges t WHERE t.chat_id = $1),
+            'payments', (
+                SELECT COALESCE(json_agg(p ORDER BY p.created_at), '[]')
+                FROM payments p
+                JOIN users u ON u.id = p.user_id
+                WHERE u.chat_id = $1
+            )
+        )::TEXT AS "data!"
+        "#,
+        chat_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(data)
+}
+
+/// Удаляет все данные пользователя одним запросом и записывает это в `account_deletions`
+/// с причиной `reason` (`user_request` или `retention`).
+/// Платежи остаются для бухгалтерии, их связь с пользователем обнуляется.
+/// Возвращает число удалённых строк и сохранённых платежей.
+pub async fn delete_account(chat_id: i64, reason: &str) -> Result<(i64, i32), sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let result = sqlx::query!(
+        r#"
+        WITH
+            account AS (SELECT id FROM users WHERE chat_id = $1),
+            kept AS (
+                SELECT COALESCE(array_agg(id ORDER BY id), '{}') AS ids
+                FROM payments
+                WHERE user_id IN (SELECT id FROM account)
+            ),
+            food AS (DELETE FROM food_logs WHERE chat_id = $1 RETURNING 1),
+            water AS (DELETE FROM water_logs WHERE chat_id = $1 RETURNING 1),
+            weight AS (DELETE FROM weight_logs WHERE chat_id = $1 RETURNING 1),
+            activity AS (DELETE FROM activity_logs WHERE chat_id = $1 RETURNING 1),
+            fasting AS (DELETE FROM fasts WHERE chat_id = $1 RETURNING 1),
+            favorites AS (DELETE FROM favorite_meals WHERE chat_id = $1 RETURNING 1),
+            user_recipes AS (DELETE FROM recipes WHERE chat_id = $1 RETURNING 1),
+            reminders AS (DELETE FROM meal_reminders WHERE chat_id = $1 RETURNING 1),
+            reports AS (DELETE FROM report_settings WHERE chat_id = $1 RETURNING 1),
+            tips AS (DELETE FROM tip_history WHERE chat_id = $1 RETURNING 1),
+            badges AS (DELETE FROM user_badges WHERE chat_id = $1 RETURNING 1),
+            profile AS (DELETE FROM users WHERE chat_id = $1 RETURNING 1)
+        INSERT INTO account_deletions (chat_id, user_id, erased_rows, retained_payment_ids, reason)
+        SELECT
+            $1,
+            (SELECT id FROM account),
+            (SELECT COUNT(*) FROM food) + (SELECT COUNT(*) FROM water)
+                + (SELECT COUNT(*) FROM weight) + (SELECT COUNT(*) FROM activity)
+                + (SELECT COUNT(*) FROM fasting) + (SELECT COUNT(*) FROM favorites)
+                + (SELECT COUNT(*) FROM user_recipes) + (SELECT COUNT(*) FROM reminders)
+                + (SELECT COUNT(*) FROM reports) + (SELECT COUNT(*) FROM tips)
+                + (SELECT COUNT(*) FROM badges) + (SELECT COUNT(*) FROM profile),
+            (SELECT ids FROM kept),
+            $2
+        RETURNING erased_rows, cardinality(retained_payment_ids) AS "retained!"
+        "#,
+        chat_id,
+        reason
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok((result.erased_rows, result.retained))
+}
+
+/// Аккаунты без записей в дневнике и изменений профиля с `since`; действующая
+/// или недавно закончившаяся подписка защищает аккаунт от удаления.
+pub async fn get_inactive_accounts(since: DateTime<Utc>) -> Result<Vec<i64>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let chat_ids = sqlx::query_scalar!(
+        r#"
+        SELECT u.chat_id
+        FROM users u
+        WHERE COALESCE(u.updated_at, u.created_at) < $1
+          AND (u.subscription_ends_at IS NULL OR u.subscription_ends_at < $1)
+          AND NOT EXISTS (SELECT 1 FROM food_logs t WHERE t.chat_id = u.chat_id AND t.created_at >= $1)
+          AND NOT EXISTS (SELECT 1 FROM water_logs t WHERE t.chat_id = u.chat_id AND t.created_at >= $1)
+          AND NOT EXISTS (SELECT 1 FROM weight_logs t WHERE t.chat_id = u.chat_id AND t.created_at >= $1)
+          AND NOT EXISTS (SELECT 1 FROM activity_logs t WHERE t.chat_id = u.chat_id AND t.created_at >= $1)
+          AND NOT EXISTS (SELECT 1 FROM fasts t WHERE t.chat_id = u.chat_id AND t.started_at >= $1)
+        ORDER BY u.chat_id
+        "#,
+        since
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(chat_ids)
+}
+
+/// Удаляет записи о еде старше `before`; при `dry_run` только считает их.
+pub async fn purge_food_logs(before: DateTime<Utc>, dry_run: bool) -> Result<u64, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    if dry_run {
+        let count = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM food_logs WHERE created_at < $1"#,
+            before
+        )
+        .fetch_one(pool)
+        .await?;
+        return Ok(count as u64);
+    }
+
+    let result = sqlx::query!("DELETE FROM food_logs WHERE created_at < $1", before)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Стирает исходный ввод и ответ модели у записей дневника старше `before`
+/// (сами записи, источник и модель остаются); при `dry_run` только считает такие записи.
+pub async fn purge_food_log_inputs(before: DateTime<Utc>, dry_run: bool) -> Result<u64, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    if dry_run {
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "count!"
+            FROM food_logs
+            WHERE created_at < $1
+              AND num_nonnulls(input_text, telegram_file_id, transcript, suggestion) > 0
+            "#,
+            before
+        )
+        .fetch_one(pool)
+        .await?;
+        return Ok(count as u64);
+    }
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE food_logs
+        SET input_text = NULL, telegram_file_id = NULL, transcript = NULL, suggestion = NULL
+        WHERE created_at < $1
+          AND num_nonnulls(input_text, telegram_file_id, transcript, suggestion) > 0
+        "#,
+        before
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Удаляет историю отправленных советов старше `before`; при `dry_run` только считает её.
+pub async fn purge_tip_history(before: DateTime<Utc>, dry_run: bool) -> Result<u64, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    if dry_run {
+        let count = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM tip_history WHERE sent_at < $1"#,
+            before
+        )
+        .fetch_one(pool)
+        .await?;
+        return Ok(count as u64);
+    }
+
+    let result = sqlx::query!("DELETE FROM tip_history WHERE sent_at < $1", before)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Добавляет запись из лога в избранное (повторное добавление обновляет КБЖУ).
+/// Возвращает id избранного или `None`, если запись не найдена.
+pub async fn add_favorite_from_log(chat_id: i64, log_id: i32) -> Result<Option<i32>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO favorite_meals (
+            chat_id, name, calories, proteins, fats, carbs,
+            fiber, sugar, saturated_fat, sodium_mg, vitamin_c_mg, calcium_mg, iron_mg, potassium_mg
+        )
+        SELECT
+            chat_id, food_name, calories, proteins, fats, carbs,
+            fiber, sugar, saturated_fat, sodium_mg, vitamin_c_mg, calcium_mg, iron_mg, potassium_mg
+        FROM food_logs
+        WHERE id = $1 AND chat_id = $2
+        ON CONFLICT (chat_id, name) DO UPDATE
+            SET calories = EXCLUDED.calories,
+                proteins = EXCLUDED.proteins,
+                fats = EXCLUDED.fats,
+                carbs = EXCLUDED.carbs,
+                fiber = EXCLUDED.fiber,
+                sugar = EXCLUDED.sugar,
+                saturated_fat = EXCLUDED.saturated_fat,
+                sodium_mg = EXCLUDED.sodium_mg,
+                vitamin_c_mg = EXCLUDED.vitamin_c_mg,
+                calcium_mg = EXCLUDED.calcium_mg,
+                iron_mg = EXCLUDED.iron_mg,
+                potassium_mg = EXCLUDED.potassium_mg
+        RETURNING id
+        "#,
+        log_id,
+        chat_id
+    )
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(id)
+}
+
+/// Получает избранные блюда пользователя.
+pub async fn get_favorites(chat_id: i64) -> Result<Vec<FavoriteMeal>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let favorites = sqlx::query_as!(
+        FavoriteMeal,
+        r#"
+        SELECT id, chat_id, name, calories, proteins, fats, carbs, created_at
+        FROM favorite_meals
+        WHERE chat_id = $1
+        ORDER BY name
+        "#,
+        chat_id
+    )
+        .fetch_all(pool)
+        .await?;
+
+    Ok(favorites)
+}
+
+/// Получает избранное блюдо по id.
+pub async fn get_favorite(chat_id: i64, favorite_id: i32) -> Result<Option<FavoriteMeal>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let favorite = sqlx::query_as!(
+        FavoriteMeal,
+        r#"
+        SELECT id, chat_id, name, calories, proteins, fats, carbs, created_at
+        FROM favorite_meals
+        WHERE id = $1 AND chat_id = $2
+        "#,
+        favorite_id,
+        chat_id
+    )
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(favorite)
+}
+
+/// Микронутриенты избранного блюда — копируются при повторной записи.
+pub async fn get_favorite_micronutrients(
+    chat_id: i64,
+    favorite_id: i32,
+) -> Result<Micronutrients, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let micros = sqlx::query_as!(
+        Micronutrients,
+        r#"
+        SELECT fiber, sugar, saturated_fat, sodium_mg, vitamin_c_mg, calcium_mg, iron_mg, potassium_mg
+        FROM favorite_meals
+        WHERE id = $1 AND chat_id = $2
+        "#,
+        favorite_id,
+        chat_id
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(micros.unwrap_or_default())
+}
+
+/// Удаляет блюдо из избранного.
+pub async fn delete_favorite(chat_id: i64, favorite_id: i32) -> Result<(), sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    sqlx::query!(
+        "DELETE FROM favorite_meals WHERE id = $1 AND chat_id = $2",
+        favorite_id,
+        chat_id
+    )
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Получает блюда, которые пользователь записывал хотя бы дважды за последние 60 дней.
+/// Одинаковыми считаются записи с совпадающим названием (без учёта регистра и пробелов по краям):
+/// оценки модели для одного и того же блюда каждый раз немного разные. КБЖУ и `id` берутся
+/// из последней записи группы.
+pub async fn get_frequent_meals(chat_id: i64, limit: i64) -> Result<Vec<FrequentMeal>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let meals = sqlx::query_as!(
+        FrequentMeal,
+        r#"
+        WITH groups AS (
+            SELECT
+                (array_agg(id ORDER BY created_at DESC, id DESC))[1] AS latest_id,
+                COUNT(*) AS times,
+                MAX(created_at) AS last_logged
+            FROM food_logs
+            WHERE chat_id = $1 AND created_at > now() - interval '60 days'
+            GROUP BY lower(trim(food_name))
+            HAVING COUNT(*) >= 2
+        )
+        SELECT
+            f.id,
+            f.food_name,
+            f.calories,
+            g.times as "times!"
+        FROM groups g
+        JOIN food_logs f ON f.id = g.latest_id
+        ORDER BY g.times DESC, g.last_logged DESC
+        LIMIT $2
+        "#,
+        chat_id,
+        limit
+    )
+        .fetch_all(pool)
+        .await?;
+
+    Ok(meals)
+}
+
+/// Добавляет продукт в локальную базу или обновляет КБЖУ существующего. Возвращает id.
+pub async fn upsert_food(
+    source: &str,
+    external_id: Option<&str>,
+    name: &str,
+    per_100g: (f32, f32, f32, f32),
+) -> Result<i32, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let (calories, proteins, fats, carbs) = per_100g;
+    let id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO foods (source, external_id, name, calories, proteins, fats, carbs)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (source, name) DO UPDATE
+            SET external_id = EXCLUDED.external_id,
+                calories = EXCLUDED.calories,
+                proteins = EXCLUDED.proteins,
+                fats = EXCLUDED.fats,
+                carbs = EXCLUDED.carbs
+        RETURNING id
+        "#,
+        source,
+        external_id,
+        name,
+        calories,
+        proteins,
+        fats,
+        carbs
+    )
+        .fetch_one(pool)
+        .await?;
+
+    Ok(id)
+}
+
+/// Добавляет альтернативное название продукта (синоним или перевод).
+pub async fn add_food_alias(food_id: i32, lang: Option<&str>, alias: &str) -> Result<(), sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    sqlx::query!(
+        r#"
+        INSERT INTO food_aliases (food_id, lang, alias)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (food_id, alias) DO NOTHING
+        "#,
+        food_id,
+        lang,
+        alias
+    )
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Получает все продукты локальной базы.
+pub async fn get_all_foods() -> Result<Vec<Food>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let foods = sqlx::query_as!(
+        Food,
+        "SELECT id, source, external_id, name, calories, proteins, fats, carbs FROM foods"
+    )
+        .fetch_all(pool)
+        .await?;
+
+    Ok(foods)
+}
+
+/// Получает все альтернативные названия продуктов.
+pub async fn get_all_food_aliases() -> Result<Vec<FoodAlias>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let aliases = sqlx::query_as!(FoodAlias, "SELECT food_id, alias FROM food_aliases")
+        .fetch_all(pool)
+        .await?;
+
+    Ok(aliases)
+}
+
+/// Добавляет или обновляет продукты по штрихкоду одной транзакцией. Возвращает их количество.
+pub async fn upsert_products(products: &[Product], source: &str) -> Result<u64, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let mut tx = pool.begin().await?;
+    for product in products {
+        sqlx::query!(
+            r#"
+            INSERT INTO products (barcode, name, brand, serving_size_g, calories, proteins, fats, carbs, source, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, now())
+            ON CONFLICT (barcode) DO UPDATE
+                SET name = EXCLUDED.name,
+                    brand = EXCLUDED.brand,
+                    serving_size_g = EXCLUDED.serving_size_g,
+                    calories = EXCLUDED.calories,
+                    proteins = EXCLUDED.proteins,
+                    fats = EXCLUDED.fats,
+                    carbs = EXCLUDED.carbs,
+                    source = EXCLUDED.source,
+                    updated_at = now()
+            "#,
+            product.barcode,
+            product.name,
+            product.brand,
+            product.serving_size_g,
+            product.calories,
+            product.proteins,
+            product.fats,
+            product.carbs,
+            source
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+
+    Ok(products.len() as u64)
+}
+
+/// Получает продукт по штрихкоду.
+pub async fn get_product(barcode: &str) -> Result<Option<Product>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let product = sqlx::query_as!(
+        Product,
+        r#"
+        SELECT barcode, name, brand, serving_size_g, calories, proteins, fats, carbs
+        FROM products
+        WHERE barcode = $1
+        "#,
+        barcode
+    )
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(product)
+}
+
+/// Сохраняет рецепт вместе с ингредиентами; рецепт с тем же названием заменяется.
+pub async fn save_recipe(chat_id: i64, recipe: &NewRecipe) -> Result<i32, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let mut totals = [0.0f32; 4];
+    for ingredient in &recipe.ingredients {
+        totals[0] += ingredient.calories;
+        totals[1] += ingredient.proteins;
+        totals[2] += ingredient.fats;
+        totals[3] += ingredient.carbs;
+    }
+
+    let mut tx = pool.begin().await?;
+
+    let id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO recipes (chat_id, name, cooked_weight_g, servings, calories, proteins, fats, carbs)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (chat_id, name) DO UPDATE
+            SET cooked_weight_g = EXCLUDED.cooked_weight_g,
+                servings = EXCLUDED.servings,
+                calories = EXCLUDED.calories,
+                proteins = EXCLUDED.proteins,
+                fats = EXCLUDED.fats,
+                carbs = EXCLUDED.carbs,
+                created_at = now()
+        RETURNING id
+        "#,
+        chat_id,
+        recipe.name,
+        recipe.cooked_weight_g,
+        recipe.servings,
+        totals[0],
+        totals[1],
+        totals[2],
+        totals[3]
+    )
+        .fetch_one(&mut *tx)
+        .await?;
+
+    sqlx::query!("DELETE FROM recipe_ingredients WHERE recipe_id = $1", id)
+        .execute(&mut *tx)
+        .await?;
+
+    for ingredient in &recipe.ingredients {
+        sqlx::query!(
+            r#"
+            INSERT INTO recipe_ingredients (recipe_id, description, calories, proteins, fats, carbs)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+            id,
+            ingredient.description,
+            ingredient.calories,
+            ingredient.proteins,
+            ingredient.fats,
+            ingredient.carbs
+        )
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(id)
+}
+
+/// Получает рецепты пользователя.
+pub async fn get_recipes(chat_id: i64) -> Result<Vec<Recipe>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let recipes = sqlx::query_as!(
+        Recipe,
+        r#"
+        SELECT id, chat_id, name, cooked_weight_g, servings, calories, proteins, fats, carbs, created_at
+        FROM recipes
+        WHERE chat_id = $1
+        ORDER BY name
+        "#,
+        chat_id
+    )
+        .fetch_all(pool)
+        .await?;
+
+    Ok(recipes)
+}
+
+/// Получает рецепт по id.
+pub async fn get_recipe(chat_id: i64, recipe_id: i32) -> Result<Option<Recipe>, sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    let recipe = sqlx::query_as!(
+        Recipe,
+        r#"
+        SELECT id, chat_id, name, cooked_weight_g, servings, calories, proteins, fats, carbs, created_at
+        FROM recipes
+        WHERE id = $1 AND chat_id = $2
+        "#,
+        recipe_id,
+        chat_id
+    )
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(recipe)
+}
+
+/// Удаляет рецепт (ингредиенты удаляются каскадно).
+pub async fn delete_recipe(chat_id: i64, recipe_id: i32) -> Result<(), sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    sqlx::query!(
+        "DELETE FROM recipes WHERE id = $1 AND chat_id = $2",
+        recipe_id,
+        chat_id
+    )
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Удаляет записи за текущий день по местному времени пользователя.
+pub async fn reset_today_logs(chat_id: i64) -> Result<(), sqlx::Error> {
+    let Some(pool) = DB_POOL.get() else {
+        return Err(sqlx::Error::PoolTimedOut);
+    };
+
+    sqlx::query!(
+        r#"
+        DELETE FROM food_logs
+        WHERE chat_id = $1 AND user_local_date(chat_id, created_at) = user_local_date($1, now())
+        "#,
         chat_id
     )
         .execute(pool)
@@ -208,6 +2267,7 @@ pub async fn reset_today_logs(chat_id: i64) -> Result<(), sqlx::Error> {
 }
 
 /// Проверяет, активна ли подписка
+#[allow(dead_code)]
 pub async fn is_subscription_active(chat_id: i64) -> Result<bool, sqlx::Error> {
     let Some(pool) = DB_POOL.get() else {
         return Err(sqlx::Error::PoolTimedOut);
@@ -228,6 +2288,7 @@ pub async fn is_subscription_active(chat_id: i64) -> Result<bool, sqlx::Error> {
 }
 
 /// Продлевает подписку на заданное количество месяцев
+#[allow(dead_code)]
 pub async fn extend_subscription(chat_id: i64, months: i32) -> Result<(), sqlx::Error> {
     let Some(pool) = DB_POOL.get() else {
         return Err(sqlx::Error::PoolTimedOut);
@@ -1,6 +1,7 @@
 #[derive(Clone)]
 pub struct Messages {
     pub welcome: String,
+    #[allow(dead_code)]
     pub help: String,
     pub help_detailed: String,
     pub unknown: String,
@@ -8,6 +9,7 @@ pub struct Messages {
     pub week_empty: String,
     pub graph_error: String,
     pub error: String,
+    pub history_empty: String,
 }
 
 impl Messages {
@@ -24,14 +26,35 @@ Welcome to the Nutrition Bot\! Track your meals and monitor your nutrition with
 • `/start` Register and choose your language\.
 • `/help` Show this help message\.
 • `/stats` View today’s nutrition summary \(calories, protein, fats, carbs\)\.
+• `/stats full` Add fibre, sugar, sodium and vitamins compared with your daily norms\.
+• `/stats chart` Add a protein/fat/carb donut chart vs\. your targets, e\.g\. `/stats chart 7` for the last week\.
 • `/reset` Clear today’s food logs\.
 • `/week` See a chart of your weekly calorie intake\.
+• `/month` Calorie chart for the last 30 days; `/chart 2026\-09\-01 2026\-09\-30` for any period\.
+• `/water` Track water with \+250/\+500 ml buttons, or `/water 300`\.
+• `/weight 82\.4` Log your weight and see the trend chart\.
+• `/exercise` Today’s activities; log one like `/exercise run 5 km 30 min` or just send "yoga 1 h"\.
+• `/fast start` Start an intermittent fast \(16:8 by default, e\.g\. `/fast start 18:6`\), `/fast stop` to finish, `/fast` for history and streak\.
+• `/history` Browse your logged meals day by day\.
+• `/digest weekly on` Get a weekly \(or `monthly`\) progress report with a chart; `/digest time 20:00` sets the time\.
+• `/remind lunch 14:00` Remind me if lunch isn't logged by 14:00; `/remind quiet 22:00\-08:00` sets quiet hours\.
+• `/tips off` Turn off the daily tip; `/tips time 10:00` sets the time, `/tips now` sends one right away\.
+• `/achievements` Your streaks and badges\.
+• `/export` Download your diary as CSV \(`/export json`, `/export 30` for the last 30 days\)\.
+• `/import` Move your history from MyFitnessPal, FatSecret or Cronometer: just send their CSV export\.
+• `/report 30` PDF report for your dietitian: profile, targets, daily table and charts\.
+• `/mydata` Download everything the bot stores about you\.
+• `/deleteme` Delete your account and all your data\.
+• `/favorites` Re\-log favorite and frequent meals with one tap\.
+• `/recipe new` Save a home recipe, then log it as "150 g of my lasagna" or via `/recipes`\.
+• `/log 2026\-10\-18 19:30 pasta` Log a meal for an earlier date and time\.
+• `/timezone` Set your time zone, e\.g\. `/timezone Europe/London`\.
 • `/subscribe` Subscribe for full access \(299 RUB\/month\)\.
 • `/status` Check your subscription status\.
 • `/cancel` Learn how to cancel your subscription\.
 
 *How to Log Food:*
-• *Text*: Send a message like "I ate 100g chicken and rice" to log a meal\.
+• *Text*: Send a message like "I ate 100g chicken and rice" to log a meal\. Add "yesterday for dinner" to log it for an earlier time\.
 • *Photo*: Send a food photo to analyze its contents\.
 • *Voice*: Send a voice message describing your meal\.
 
@@ -42,6 +65,7 @@ Welcome to the Nutrition Bot\! Track your meals and monitor your nutrition with
                 week_empty: "No data for the last 7 days.".into(),
                 graph_error: "❌ Error drawing the chart.".into(),
                 error: "❌ An error occurred.".into(),
+                history_empty: "📭 You have no logged meals yet.".into(),
             },
             "ru" => Messages {
                 welcome: "Добро пожаловать!".into(),
@@ -54,14 +78,35 @@ Welcome to the Nutrition Bot\! Track your meals and monitor your nutrition with
 • `/start` Зарегистрируйтесь и выберите язык\.
 • `/help` Показать это сообщение с помощью\.
 • `/stats` Посмотреть сводку питания за сегодня \(калории, белки, жиры, углеводы\)\.
+• `/stats full` Клетчатка, сахар, натрий и витамины в сравнении с нормой\.
+• `/stats chart` Диаграмма БЖУ в сравнении с нормой, например `/stats chart 7` за неделю\.
 • `/reset` Очистить логи еды за сегодня\.
 • `/week` Посмотреть график калорий за неделю\.
+• `/month` График калорий за 30 дней; `/chart 2026\-09\-01 2026\-09\-30` — за любой период\.
+• `/water` Учёт воды: кнопки \+250/\+500 мл или `/water 300`\.
+• `/weight 82\.4` Записать вес и посмотреть график динамики\.
+• `/exercise` Активности за сегодня; запишите тренировку: `/exercise бег 5 км 30 мин` или просто «йога 1 ч»\.
+• `/fast start` Начать интервальное голодание \(по умолчанию 16:8, например `/fast start 18:6`\), `/fast stop` — завершить, `/fast` — история и серия\.
+• `/history` Просмотреть историю питания по дням\.
+• `/digest weekly on` Еженедельный \(или `monthly` — ежемесячный\) отчёт с графиком; `/digest time 20:00` — время отправки\.
+• `/remind lunch 14:00` Напомнить, если обед не записан к 14:00; `/remind quiet 22:00\-08:00` — тихие часы\.
+• `/tips off` Отключить совет дня; `/tips time 10:00` — время отправки, `/tips now` — совет прямо сейчас\.
+• `/achievements` Ваши серии и достижения\.
+• `/export` Скачать дневник в CSV \(`/export json`, `/export 30` — за 30 дней\)\.
+• `/import` Перенести историю из MyFitnessPal, FatSecret или Cronometer: просто отправьте их CSV\-выгрузку\.
+• `/report 30` PDF\-отчёт для диетолога: профиль, нормы, таблица по дням и графики\.
+• `/mydata` Скачать все данные, которые бот хранит о вас\.
+• `/deleteme` Удалить аккаунт и все ваши данные\.
+• `/favorites` Избранные и частые блюда — запись в одно нажатие\.
+• `/recipe new` Сохранить свой рецепт и записывать его как «150 г моей лазаньи» или через `/recipes`\.
+• `/log 2026\-10\-18 19:30 паста` Записать еду задним числом\.
+• `/timezone` Указать часовой пояс, например `/timezone Europe/Moscow`\.
 • `/subscribe` Оформить подписку для полного доступа \(299 ₽\/мес\)\.
 • `/status` Проверить статус подписки\.
 • `/cancel` Узнать, как отменить подписку\.
 
 *Как записывать еду:*
-• *Текст*: Отправьте сообщение, например, "Я съел 100г курицы и риса"\.
+• *Текст*: Отправьте сообщение, например, "Я съел 100г курицы и риса"\. Добавьте "вчера на ужин", чтобы записать еду задним числом\.
 • *Фото*: Отправьте фото еды для анализа\.
 • *Голос*: Отправьте голосовое сообщение с описанием еды\.
 
@@ -72,6 +117,7 @@ Welcome to the Nutrition Bot\! Track your meals and monitor your nutrition with
                 week_empty: "Нет данных за последние 7 дней.".into(),
                 graph_error: "❌ Ошибка при построении графика.".into(),
                 error: "❌ Произошла ошибка.".into(),
+                history_empty: "📭 У вас пока нет записей о еде.".into(),
             },
             "th" => Messages {
                 welcome: "ยินดีต้อนรับสู่บอทคำนวณแคลอรี่ของคุณ!".into(),
@@ -84,8 +130,29 @@ Welcome to the Nutrition Bot\! Track your meals and monitor your nutrition with
 • `/start` ลงทะเบียนและเลือกภาษา
 • `/help` แสดงข้อความช่วยเหลือนี้
 • `/stats` ดูสรุปโภชนาการของวันนี้ \(แคลอรี่, โปรตีน, ไขมัน, คาร์โบไฮเดรต\)
+• `/stats full` ใยอาหาร น้ำตาล โซเดียม และวิตามินเทียบกับปริมาณแนะนำ
+• `/stats chart` แผนภูมิโดนัทสัดส่วนโปรตีน ไขมัน คาร์บเทียบกับเป้าหมาย เช่น `/stats chart 7` สำหรับสัปดาห์ล่าสุด
 • `/reset` ล้างบันทึกอาหารของวันนี้
 • `/week` ดูกราฟแคลอรี่รายสัปดาห์
+• `/month` กราฟแคลอรี่ 30 วัน หรือ `/chart 2026\-09\-01 2026\-09\-30` สำหรับช่วงเวลาใดก็ได้
+• `/water` บันทึกการดื่มน้ำด้วยปุ่ม \+250/\+500 มล\. หรือ `/water 300`
+• `/weight 82\.4` บันทึกน้ำหนักและดูกราฟแนวโน้ม
+• `/exercise` กิจกรรมของวันนี้ บันทึกได้ด้วย `/exercise วิ่ง 5 กม 30 นาที` หรือส่ง "โยคะ 1 ชั่วโมง"
+• `/fast start` เริ่มการอดอาหารแบบ IF \(ค่าเริ่มต้น 16:8 เช่น `/fast start 18:6`\) `/fast stop` หยุด `/fast` ดูประวัติและสถิติต่อเนื่อง
+• `/history` ดูประวัติมื้ออาหารรายวัน
+• `/digest weekly on` รับรายงานความคืบหน้ารายสัปดาห์ \(หรือ `monthly`\) พร้อมกราฟ `/digest time 20:00` ตั้งเวลาส่ง
+• `/remind lunch 14:00` เตือนถ้ายังไม่ได้บันทึกมื้อกลางวันภายใน 14:00 `/remind quiet 22:00\-08:00` ตั้งช่วงเวลาเงียบ
+• `/tips off` ปิดเคล็ดลับประจำวัน `/tips time 10:00` ตั้งเวลาส่ง `/tips now` รับเคล็ดลับทันที
+• `/achievements` สถิติต่อเนื่องและเหรียญรางวัลของคุณ
+• `/export` ดาวน์โหลดบันทึกเป็น CSV \(`/export json`, `/export 30` สำหรับ 30 วันล่าสุด\)
+• `/import` ย้ายประวัติจาก MyFitnessPal, FatSecret หรือ Cronometer: แค่ส่งไฟล์ CSV ที่ส่งออกมา
+• `/report 30` รายงาน PDF สำหรับนักโภชนาการ: โปรไฟล์ เป้าหมาย ตารางรายวัน และกราฟ
+• `/mydata` ดาวน์โหลดข้อมูลทั้งหมดที่บอทเก็บเกี่ยวกับคุณ
+• `/deleteme` ลบบัญชีและข้อมูลทั้งหมดของคุณ
+• `/favorites` บันทึกเมนูโปรดและเมนูที่กินบ่อยได้ในคลิกเดียว
+• `/recipe new` บันทึกสูตรอาหารของคุณ แล้วบันทึกการกินผ่าน `/recipes`
+• `/log 2026\-10\-18 19:30 ข้าวผัด` บันทึกอาหารย้อนหลัง
+• `/timezone` ตั้งค่าเขตเวลา เช่น `/timezone Asia/Bangkok`
 • `/subscribe` สมัครสมาชิกเพื่อใช้งานเต็มรูปแบบ \(299 รูเบิล\/เดือน\)
 • `/status` ตรวจสอบสถานะการสมัครสมาชิก
 • `/cancel` เรียนรู้วิธียกเลิกการสมัครสมาชิก
@@ -102,6 +169,7 @@ Welcome to the Nutrition Bot\! Track your meals and monitor your nutrition with
                 week_empty: "ไม่มีข้อมูลในช่วง 7 วันที่ผ่านมา.".into(),
                 graph_error: "❌ เกิดข้อผิดพลาดในการสร้างกราฟ.".into(),
                 error: "❌ เกิดข้อผิดพลาด.".into(),
+                history_empty: "📭 ยังไม่มีบันทึกมื้ออาหาร.".into(),
             },
             "zh" => Messages {
                 welcome: "欢迎使用您的卡路里助手！".into(),
@@ -114,8 +182,29 @@ Welcome to the Nutrition Bot\! Track your meals and monitor your nutrition with
 • `/start` 注册并选择语言\.
 • `/help` 显示此帮助信息\.
 • `/stats` 查看今日营养总结\(卡路里、蛋白质、脂肪、碳水化合物\)\.
+• `/stats full` 膳食纤维、糖、钠和维生素与每日参考量对比\.
+• `/stats chart` 蛋白质/脂肪/碳水环形图与目标对比，例如 `/stats chart 7` 查看最近一周\.
 • `/reset` 清除今日的饮食记录\.
 • `/week` 查看每周卡路里摄入图表\.
+• `/month` 最近30天卡路里图表；`/chart 2026\-09\-01 2026\-09\-30` 查看任意时间段\.
+• `/water` 记录饮水：点击 \+250/\+500 毫升或发送 `/water 300`\.
+• `/weight 82\.4` 记录体重并查看趋势图\.
+• `/exercise` 今日运动；记录运动：`/exercise 跑步 5 公里 30 分钟` 或直接发送 «瑜伽 1 小时»\.
+• `/fast start` 开始间歇性断食\(默认 16:8，例如 `/fast start 18:6`\)，`/fast stop` 结束，`/fast` 查看历史和连续天数\.
+• `/history` 按天浏览饮食记录\.
+• `/digest weekly on` 每周\(或 `monthly` 每月\)接收带图表的进度报告；`/digest time 20:00` 设置发送时间\.
+• `/remind lunch 14:00` 如果14:00前未记录午餐则提醒；`/remind quiet 22:00\-08:00` 设置免打扰时段\.
+• `/tips off` 关闭每日小贴士；`/tips time 10:00` 设置发送时间，`/tips now` 立即获取一条\.
+• `/achievements` 您的连续记录和成就徽章\.
+• `/export` 下载 CSV 格式的日记\(`/export json`，`/export 30` 最近30天\)\.
+• `/import` 从 MyFitnessPal、FatSecret 或 Cronometer 迁移历史：直接发送它们导出的 CSV 文件\.
+• `/report 30` 给营养师的 PDF 报告：个人资料、目标、每日明细和图表\.
+• `/mydata` 下载机器人保存的关于您的全部数据\.
+• `/deleteme` 删除账户及全部数据\.
+• `/favorites` 一键记录收藏和常吃的餐\.
+• `/recipe new` 保存自己的食谱，之后发送 «150克 千层面» 或通过 `/recipes` 记录\.
+• `/log 2026\-10\-18 19:30 米饭` 补记之前的饮食\.
+• `/timezone` 设置时区，例如 `/timezone Asia/Shanghai`\.
 • `/subscribe` 订阅以获得完整功能\(299卢布\/月\)\.
 • `/status` 检查订阅状态\.
 • `/cancel` 了解如何取消订阅\.
@@ -132,6 +221,7 @@ Welcome to the Nutrition Bot\! Track your meals and monitor your nutrition with
                 week_empty: "过去 7 天没有记录。".into(),
                 graph_error: "❌ 绘图时出错。".into(),
                 error: "❌ 发生错误。".into(),
+                history_empty: "📭 您还没有任何饮食记录。".into(),
             },
             _ => Messages {
                 welcome: "Welcome!".into(),
@@ -144,14 +234,35 @@ Welcome to the Nutrition Bot\! Track your meals and monitor your nutrition with
 • `/start` Register and choose your language\.
 • `/help` Show this help message\.
 • `/stats` View today’s nutrition summary \(calories, protein, fats, carbs\)\.
+• `/stats full` Add fibre, sugar, sodium and vitamins compared with your daily norms\.
+• `/stats chart` Add a protein/fat/carb donut chart vs\. your targets, e\.g\. `/stats chart 7` for the last week\.
 • `/reset` Clear today’s food logs\.
 • `/week` See a chart of your weekly calorie intake\.
+• `/month` Calorie chart for the last 30 days; `/chart 2026\-09\-01 2026\-09\-30` for any period\.
+• `/water` Track water with \+250/\+500 ml buttons, or `/water 300`\.
+• `/weight 82\.4` Log your weight and see the trend chart\.
+• `/exercise` Today’s activities; log one like `/exercise run 5 km 30 min` or just send "yoga 1 h"\.
+• `/fast start` Start an intermittent fast \(16:8 by default, e\.g\. `/fast start 18:6`\), `/fast stop` to finish, `/fast` for history and streak\.
+• `/history` Browse your logged meals day by day\.
+• `/digest weekly on` Get a weekly \(or `monthly`\) progress report with a chart; `/digest time 20:00` sets the time\.
+• `/remind lunch 14:00` Remind me if lunch isn't logged by 14:00; `/remind quiet 22:00\-08:00` sets quiet hours\.
+• `/tips off` Turn off the daily tip; `/tips time 10:00` sets the time, `/tips now` sends one right away\.
+• `/achievements` Your streaks and badges\.
+• `/export` Download your diary as CSV \(`/export json`, `/export 30` for the last 30 days\)\.
+• `/import` Move your history from MyFitnessPal, FatSecret or Cronometer: just send their CSV export\.
+• `/report 30` PDF report for your dietitian: profile, targets, daily table and charts\.
+• `/mydata` Download everything the bot stores about you\.
+• `/deleteme` Delete your account and all your data\.
+• `/favorites` Re\-log favorite and frequent meals with one tap\.
+• `/recipe new` Save a home recipe, then log it as "150 g of my lasagna" or via `/recipes`\.
+• `/log 2026\-10\-18 19:30 pasta` Log a meal for an earlier date and time\.
+• `/timezone` Set your time zone, e\.g\. `/timezone Europe/London`\.
 • `/subscribe` Subscribe for full access \(299 RUB\/month\)\.
 • `/status` Check your subscription status\.
 • `/cancel` Learn how to cancel your subscription\.
 
 *How to Log Food:*
-• *Text*: Send a message like "I ate 100g chicken and rice" to log a meal\.
+• *Text*: Send a message like "I ate 100g chicken and rice" to log a meal\. Add "yesterday for dinner" to log it for an earlier time\.
 • *Photo*: Send a food photo to analyze its contents\.
 • *Voice*: Send a voice message describing your meal\.
 
@@ -162,7 +273,8 @@ Welcome to the Nutrition Bot\! Track your meals and monitor your nutrition with
                 week_empty: "No data for last week.".into(),
                 graph_error: "❌ Chart error.".into(),
                 error: "❌ An error occurred.".into(),
+                history_empty: "📭 No logged meals yet.".into(),
             },
         }
     }
-}
\ No newline at end of file
+}
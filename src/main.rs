@@ -6,6 +6,7 @@ use teloxide::{
 };
 use actix_web::{App, HttpServer};
 
+mod admin;
 mod db;
 mod locales;
 mod services;
@@ -27,9 +28,22 @@ async fn main() -> std::io::Result<()> {
         .expect("❌ Не удалось подключиться к базе данных");
     crate::db::queries::set_pool(pool);
 
+    // Administrative commands (e.g. `import-foods`) run instead of the bot
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        if let Err(e) = admin::run(&args).await {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     // Initialize the Telegram bot
     let bot = Bot::from_env();
 
+    // Background jobs (fasting notifications, scheduled reports, meal reminders, daily tips, data retention)
+    telegram::scheduler::spawn(bot.clone());
+
     // Set up the dispatcher schema
     let schema = dptree::entry()
         .branch(
@@ -0,0 +1,209 @@
+use crate::db::models::DailyTotals;
+use crate::services::report::logging_streak;
+use chrono::{Duration, NaiveDate};
+use std::collections::BTreeMap;
+
+/// Показатели пользователя, по которым выдаются значки.
+#[derive(Debug, Clone, Default)]
+pub struct Progress {
+    pub meals_logged: i64,
+    /// Дней подряд с записями, включая сегодня.
+    pub logging_streak: u32,
+    pub best_logging_streak: u32,
+    /// Завершённых дней подряд в пределах нормы калорий (до вчерашнего включительно).
+    pub under_target_streak: u32,
+    pub best_under_target_streak: u32,
+    pub has_target: bool,
+}
+
+impl Progress {
+    /// Считает серии по дневным итогам: `today` — текущий день, он ещё не завершён
+    /// и в серию дней в пределах нормы не входит.
+    pub fn build(
+        meals_logged: i64,
+        totals: &[DailyTotals],
+        burned: &BTreeMap<NaiveDate, f32>,
+        target: Option<f32>,
+        today: NaiveDate,
+    ) -> Self {
+        let logged: Vec<NaiveDate> = totals
+            .iter()
+            .filter(|d| d.entries > 0)
+            .map(|d| d.date)
+            .collect();
+        let under_target: Vec<NaiveDate> = match target.filter(|t| *t > 0.0) {
+            Some(target) => totals
+                .iter()
+                .filter(|d| d.date < today && d.calories > 0.0)
+                .filter(|d| d.calories - burned.get(&d.date).copied().unwrap_or(0.0) <= target)
+                .map(|d| d.date)
+                .collect(),
+            None => Vec::new(),
+        };
+
+        Progress {
+            meals_logged,
+            logging_streak: logging_streak(&logged, today),
+            best_logging_streak: longest_streak(&logged),
+            under_target_streak: logging_streak(&under_target, today - Duration::days(1)),
+            best_under_target_streak: longest_streak(&under_target),
+            has_target: target.is_some(),
+        }
+    }
+}
+
+/// Самая длинная серия последовательных дат; `dates` отсортированы по возрастанию.
+pub fn longest_streak(dates: &[NaiveDate]) -> u32 {
+    let mut best = 0;
+    let mut current = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for &date in dates {
+        current = match previous {
+            Some(prev) if date - prev == Duration::days(1) => current + 1,
+            Some(prev) if date == prev => current,
+            _ => 1,
+        };
+        best = best.max(current);
+        previous = Some(date);
+    }
+    best
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Badge {
+    FirstMeal,
+    Meals100,
+    Meals500,
+    Meals1000,
+    Streak3,
+    Streak7,
+    Streak30,
+    Streak100,
+    UnderTarget7,
+    UnderTarget30,
+}
+
+impl Badge {
+    pub const ALL: [Badge; 10] = [
+        Badge::FirstMeal,
+        Badge::Meals100,
+        Badge::Meals500,
+        Badge::Meals1000,
+        Badge::Streak3,
+        Badge::Streak7,
+        Badge::Streak30,
+        Badge::Streak100,
+        Badge::UnderTarget7,
+        Badge::UnderTarget30,
+    ];
+
+    /// Ключ значка в `user_badges`.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Badge::FirstMeal => "first_meal",
+            Badge::Meals100 => "meals_100",
+            Badge::Meals500 => "meals_500",
+            Badge::Meals1000 => "meals_1000",
+            Badge::Streak3 => "streak_3",
+            Badge::Streak7 => "streak_7",
+            Badge::Streak30 => "streak_30",
+            Badge::Streak100 => "streak_100",
+            Badge::UnderTarget7 => "under_target_7",
+            Badge::UnderTarget30 => "under_target_30",
+        }
+    }
+
+    pub fn parse(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|badge| badge.key() == key)
+    }
+
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            Badge::FirstMeal => "🍽",
+            Badge::Meals100 => "💯",
+            Badge::Meals500 => "🥈",
+            Badge::Meals1000 => "🥇",
+            Badge::Streak3 => "🔥",
+            Badge::Streak7 => "📅",
+            Badge::Streak30 => "🗓",
+            Badge::Streak100 => "👑",
+            Badge::UnderTarget7 => "🎯",
+            Badge::UnderTarget30 => "🏹",
+        }
+    }
+
+    /// Текущее значение показателя и порог для получения значка.
+    pub fn progress(&self, progress: &Progress) -> (i64, i64) {
+        match self {
+            Badge::FirstMeal => (progress.meals_logged, 1),
+            Badge::Meals100 => (progress.meals_logged, 100),
+            Badge::Meals500 => (progress.meals_logged, 500),
+            Badge::Meals1000 => (progress.meals_logged, 1000),
+            Badge::Streak3 => (progress.best_logging_streak as i64, 3),
+            Badge::Streak7 => (progress.best_logging_streak as i64, 7),
+            Badge::Streak30 => (progress.best_logging_streak as i64, 30),
+            Badge::Streak100 => (progress.best_logging_streak as i64, 100),
+            Badge::UnderTarget7 => (progress.best_under_target_streak as i64, 7),
+            Badge::UnderTarget30 => (progress.best_under_target_streak as i64, 30),
+        }
+    }
+
+    pub fn is_earned(&self, progress: &Progress) -> bool {
+        let (value, goal) = self.progress(progress);
+        value >= goal
+    }
+
+    pub fn title(&self, lang: &str) -> String {
+        let (_, goal) = self.progress(&Progress::default());
+        match (self, lang) {
+            (Badge::FirstMeal, "ru") => "Первая запись".to_string(),
+            (Badge::FirstMeal, "th") => "บันทึกแรก".to_string(),
+            (Badge::FirstMeal, "zh") => "第一条记录".to_string(),
+            (Badge::FirstMeal, _) => "First meal logged".to_string(),
+            (Badge::Meals100 | Badge::Meals500 | Badge::Meals1000, "ru") => {
+                format!("Записей о еде: {}", goal)
+            }
+            (Badge::Meals100 | Badge::Meals500 | Badge::Meals1000, "th") => {
+                format!("บันทึกอาหาร {} รายการ", goal)
+            }
+            (Badge::Meals100 | Badge::Meals500 | Badge::Meals1000, "zh") => {
+                format!("记录{}餐", goal)
+            }
+            (Badge::Meals100 | Badge::Meals500 | Badge::Meals1000, _) => {
+                format!("{} meals logged", goal)
+            }
+            (Badge::Streak3 | Badge::Streak7 | Badge::Streak30 | Badge::Streak100, "ru") => {
+                format!("Дней подряд с записями: {}", goal)
+            }
+            (Badge::Streak3 | Badge::Streak7 | Badge::Streak30 | Badge::Streak100, "th") => {
+                format!("บันทึกต่อเนื่อง {} วัน", goal)
+            }
+            (Badge::Streak3 | Badge::Streak7 | Badge::Streak30 | Badge::Streak100, "zh") => {
+                format!("连续记录{}天", goal)
+            }
+            (Badge::Streak3 | Badge::Streak7 | Badge::Streak30 | Badge::Streak100, _) => {
+                format!("{}-day logging streak", goal)
+            }
+            (Badge::UnderTarget7 | Badge::UnderTarget30, "ru") => {
+                format!("Дней подряд в пределах нормы: {}", goal)
+            }
+            (Badge::UnderTarget7 | Badge::UnderTarget30, "th") => {
+                format!("อยู่ในเป้าหมายแคลอรี {} วันติดต่อกัน", goal)
+            }
+            (Badge::UnderTarget7 | Badge::UnderTarget30, "zh") => {
+                format!("连续{}天不超热量目标", goal)
+            }
+            (Badge::UnderTarget7 | Badge::UnderTarget30, _) => {
+                format!("{} days in a row under target", goal)
+            }
+        }
+    }
+}
+
+/// Значки, заработанные по текущим показателям.
+pub fn earned_badges(progress: &Progress) -> Vec<Badge> {
+    Badge::ALL
+        .into_iter()
+        .filter(|badge| badge.is_earned(progress))
+        .collect()
+}
@@ -0,0 +1,324 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Вес для расчёта, если в профиле он не указан.
+const DEFAULT_WEIGHT_KG: f64 = 70.0;
+
+static DURATION_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(\d+(?:[.,]\d+)?)\s*(час\w*|ч\b|hours?\b|hrs?\b|h\b|мин\w*|minutes?\b|mins?\b|小时|分钟|ชั่วโมง|นาที)").unwrap()
+});
+
+/// Слова, которые могут стоять перед видом активности («я сегодня бегал», «went swimming»).
+const LEADING_WORDS: &[&str] = &[
+    "i", "went", "did", "today", "yesterday", "this", "morning", "evening", "a", "an", "some", "to",
+    "я", "сегодня", "вчера", "утром", "днем", "днём", "вечером", "на", "в", "ходил", "ходила",
+    "сходил", "сходила", "был", "была", "немного",
+];
+
+static DISTANCE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(\d+(?:[.,]\d+)?)\s*(км|km|公里|กม)").unwrap()
+});
+
+/// Вид активности: ключевые слова, MET (Compendium of Physical Activities) и типичная
+/// скорость (км/ч) для оценки длительности по дистанции.
+pub struct ActivityKind {
+    pub key: &'static str,
+    pub emoji: &'static str,
+    keywords: &'static [&'static str],
+    met: f64,
+    speed_kmh: Option<f64>,
+}
+
+/// Ключевые слова на латинице и кириллице — целые словоформы, без поиска по префиксу
+/// («йогурт» не йога, «плавленый» не плавание).
+/// Порядок важен: более конкретные виды («เดินป่า» — поход) проверяются раньше общих («เดิน»).
+const ACTIVITIES: &[ActivityKind] = &[
+    ActivityKind {
+        key: "running",
+        emoji: "🏃",
+        keywords: &[
+            "run", "runs", "running", "jog", "jogged", "jogging", "бег", "бегал", "бегала", "побегал",
+            "побегала", "пробежка", "пробежку", "пробежал", "пробежала", "跑步", "วิ่ง",
+        ],
+        met: 9.8,
+        speed_kmh: Some(10.0),
+    },
+    ActivityKind {
+        key: "hiking",
+        emoji: "🥾",
+        keywords: &["hike", "hiked", "hiking", "поход", "походе", "трекинг", "徒步", "เดินป่า"],
+        met: 6.0,
+        speed_kmh: Some(4.0),
+    },
+    ActivityKind {
+        key: "walking",
+        emoji: "🚶",
+        keywords: &[
+            "walk", "walked", "walking", "ходьба", "ходьбу", "прогулка", "прогулку", "гулял", "гуляла",
+            "шел", "шёл", "шла", "散步", "步行", "เดิน",
+        ],
+        met: 3.5,
+        speed_kmh: Some(5.0),
+    },
+    ActivityKind {
+        key: "cycling",
+        emoji: "🚴",
+        keywords: &[
+            "cycling", "cycled", "bike", "biked", "biking", "велосипед", "велосипеде", "велик", "велике",
+            "велотренажер", "велотренажёр", "骑行", "自行车", "ปั่นจักรยาน",
+        ],
+        met: 7.5,
+        speed_kmh: Some(18.0),
+    },
+    ActivityKind {
+        key: "swimming",
+        emoji: "🏊",
+        keywords: &[
+            "swim", "swam", "swimming", "плавание", "плавал", "плавала", "проплыл", "проплыла",
+            "бассейн", "бассейне", "游泳", "ว่ายน้ำ",
+        ],
+        met: 6.0,
+        speed_kmh: Some(2.5),
+    },
+    ActivityKind {
+        key: "skiing",
+        emoji: "⛷",
+        keywords: &["ski", "skied", "skiing", "лыжи", "лыжах", "滑雪", "สกี"],
+        met: 7.0,
+        speed_kmh: Some(10.0),
+    },
+    ActivityKind {
+        key: "strength",
+        emoji: "🏋",
+        keywords: &[
+            "gym", "weights", "weightlifting", "lifting", "strength", "зал", "зале", "качалка",
+            "качалке", "силовая", "силовую", "тренажеры", "тренажёры", "тренажерах", "тренажёрах",
+            "штанга", "штангу", "健身", "举重", "ยกน้ำหนัก", "ฟิตเนส",
+        ],
+        met: 5.0,
+        speed_kmh: None,
+    },
+    ActivityKind {
+        key: "hiit",
+        emoji: "🔥",
+        keywords: &["hiit", "crossfit", "кроссфит", "интервальная", "интервальную", "интервалы", "间歇", "ครอสฟิต"],
+        met: 8.0,
+        speed_kmh: None,
+    },
+    ActivityKind {
+        key: "yoga",
+        emoji: "🧘",
+        keywords: &[
+            "yoga", "pilates", "stretching", "йога", "йогу", "йогой", "пилатес", "растяжка", "растяжку",
+            "瑜伽", "普拉提", "โยคะ",
+        ],
+        met: 2.5,
+        speed_kmh: None,
+    },
+    ActivityKind {
+        key: "football",
+        emoji: "⚽",
+        keywords: &["football", "soccer", "футбол", "футбола", "足球", "ฟุตบอล"],
+        met: 7.0,
+        speed_kmh: None,
+    },
+    ActivityKind {
+        key: "tennis",
+        emoji: "🎾",
+        keywords: &["tennis", "теннис", "网球", "เทนนิส"],
+        met: 7.3,
+        speed_kmh: None,
+    },
+    ActivityKind {
+        key: "dancing",
+        emoji: "💃",
+        keywords: &["dance", "danced", "dancing", "танцы", "танцевал", "танцевала", "跳舞", "เต้น"],
+        met: 5.0,
+        speed_kmh: None,
+    },
+    ActivityKind {
+        key: "jump_rope",
+        emoji: "🪢",
+        keywords: &["jump rope", "skipping", "скакалка", "скакалку", "скакалкой", "跳绳", "กระโดดเชือก"],
+        met: 11.0,
+        speed_kmh: None,
+    },
+];
+
+/// Распознанная активность из сообщения.
+pub struct ParsedActivity {
+    pub kind: &'static ActivityKind,
+    pub duration_min: f32,
+    pub distance_km: Option<f32>,
+}
+
+impl ActivityKind {
+    pub fn label(&self, lang: &str) -> &'static str {
+        match (self.key, lang) {
+            ("running", "ru") => "Бег",
+            ("walking", "ru") => "Ходьба",
+            ("cycling", "ru") => "Велосипед",
+            ("swimming", "ru") => "Плавание",
+            ("hiking", "ru") => "Поход",
+            ("skiing", "ru") => "Лыжи",
+            ("strength", "ru") => "Силовая тренировка",
+            ("hiit", "ru") => "Интервальная тренировка",
+            ("yoga", "ru") => "Йога",
+            ("football", "ru") => "Футбол",
+            ("tennis", "ru") => "Теннис",
+            ("dancing", "ru") => "Танцы",
+            ("jump_rope", "ru") => "Скакалка",
+            ("running", "zh") => "跑步",
+            ("walking", "zh") => "步行",
+            ("cycling", "zh") => "骑行",
+            ("swimming", "zh") => "游泳",
+            ("hiking", "zh") => "徒步",
+            ("skiing", "zh") => "滑雪",
+            ("strength", "zh") => "力量训练",
+            ("hiit", "zh") => "间歇训练",
+            ("yoga", "zh") => "瑜伽",
+            ("football", "zh") => "足球",
+            ("tennis", "zh") => "网球",
+            ("dancing", "zh") => "跳舞",
+            ("jump_rope", "zh") => "跳绳",
+            ("running", "th") => "วิ่ง",
+            ("walking", "th") => "เดิน",
+            ("cycling", "th") => "ปั่นจักรยาน",
+            ("swimming", "th") => "ว่ายน้ำ",
+            ("hiking", "th") => "เดินป่า",
+            ("skiing", "th") => "สกี",
+            ("strength", "th") => "เวทเทรนนิ่ง",
+            ("hiit", "th") => "HIIT",
+            ("yoga", "th") => "โยคะ",
+            ("football", "th") => "ฟุตบอล",
+            ("tennis", "th") => "เทนนิส",
+            ("dancing", "th") => "เต้น",
+            ("jump_rope", "th") => "กระโดดเชือก",
+            ("running", _) => "Running",
+            ("walking", _) => "Walking",
+            ("cycling", _) => "Cycling",
+            ("swimming", _) => "Swimming",
+            ("hiking", _) => "Hiking",
+            ("skiing", _) => "Skiing",
+            ("strength", _) => "Strength training",
+            ("hiit", _) => "HIIT",
+            ("yoga", _) => "Yoga",
+            ("football", _) => "Football",
+            ("tennis", _) => "Tennis",
+            ("dancing", _) => "Dancing",
+            ("jump_rope", _) => "Jump rope",
+            _ => "Activity",
+        }
+    }
+
+    /// Ищет вид активности по ключу, сохранённому в `activity_logs.activity`.
+    pub fn find(key: &str) -> Option<&'static ActivityKind> {
+        ACTIVITIES.iter().find(|a| a.key == key)
+    }
+}
+
+/// Распознаёт активность в сообщении вроде «бег 5 км 30 мин» или «gym 1 h».
+/// Вид активности должен начинать сообщение (после слов вроде «я», «вчера»), а в тайском
+/// и китайском — стоять до первого числа; нужна также длительность или дистанция.
+/// Иначе сообщение считается едой («йогурт 200 г, 1 час назад»).
+pub fn parse_activity(text: &str) -> Option<ParsedActivity> {
+    let lower = text.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .skip_while(|w| LEADING_WORDS.contains(w))
+        .collect();
+    let before_number = &lower[..lower.find(|c: char| c.is_ascii_digit()).unwrap_or(lower.len())];
+
+    let kind = ACTIVITIES.iter().find(|activity| {
+        activity.keywords.iter().any(|keyword| {
+            if keyword.chars().all(|c| c == ' ' || is_spaced_script(c)) {
+                let keyword: Vec<&str> = keyword.split(' ').collect();
+                words.starts_with(&keyword)
+            } else {
+                before_number.contains(keyword)
+            }
+        })
+    })?;
+
+    let distance_km = DISTANCE_RE
+        .captures(&lower)
+        .and_then(|cap| cap[1].replace(',', ".").parse::<f32>().ok())
+        .filter(|km| *km > 0.0);
+
+    let text_without_distance = DISTANCE_RE.replace_all(&lower, " ");
+    let mut duration_min: f32 = DURATION_RE
+        .captures_iter(&text_without_distance)
+        .filter_map(|cap| {
+            let amount = cap[1].replace(',', ".").parse::<f32>().ok()?;
+            let unit = &cap[2];
+            let is_hours = unit.starts_with('ч')
+                || unit.starts_with('h')
+                || unit == "小时"
+                || unit == "ชั่วโมง";
+            Some(if is_hours { amount * 60.0 } else { amount })
+        })
+        .sum();
+
+    if duration_min <= 0.0 {
+        let speed = kind.speed_kmh?;
+        duration_min = (distance_km? as f64 / speed * 60.0) as f32;
+    }
+    if !(1.0..=24.0 * 60.0).contains(&duration_min) {
+        return None;
+    }
+
+    Some(ParsedActivity {
+        kind,
+        duration_min,
+        distance_km,
+    })
+}
+
+/// Латиница и кириллица — языки, где слова разделены пробелами.
+fn is_spaced_script(c: char) -> bool {
+    c.is_ascii_alphabetic() || ('а'..='я').contains(&c) || c == 'ё'
+}
+
+/// Сожжённые калории: MET × вес (кг) × длительность (ч).
+pub fn calories_burned(activity: &ParsedActivity, weight_kg: Option<f64>) -> f32 {
+    let weight = weight_kg.filter(|w| *w > 0.0).unwrap_or(DEFAULT_WEIGHT_KG);
+    (activity.kind.met * weight * activity.duration_min as f64 / 60.0) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(text: &str) -> Option<(&'static str, f32)> {
+        parse_activity(text).map(|a| (a.kind.key, a.duration_min))
+    }
+
+    #[test]
+    fn recognizes_activities_at_the_start_of_the_message() {
+        assert_eq!(parsed("бег 5 км 30 мин"), Some(("running", 30.0)));
+        assert_eq!(parsed("я сегодня плавал 1 час"), Some(("swimming", 60.0)));
+        assert_eq!(parsed("went swimming 45 min"), Some(("swimming", 45.0)));
+        assert_eq!(parsed("gym 1 h"), Some(("strength", 60.0)));
+        assert_eq!(parsed("йога 40 минут"), Some(("yoga", 40.0)));
+        assert_eq!(parsed("jump rope 10 min"), Some(("jump_rope", 10.0)));
+        assert_eq!(parsed("跑步 30 分钟"), Some(("running", 30.0)));
+        assert_eq!(parsed("วิ่ง 5 กม"), Some(("running", 30.0)));
+    }
+
+    #[test]
+    fn food_phrases_are_not_activities() {
+        assert!(parsed("йогурт 200 г, 1 час назад").is_none());
+        assert!(parsed("плавленый сыр 30 мин").is_none());
+        assert!(parsed("ranch salad 1 h ago").is_none());
+        assert!(parsed("бутерброд после бега 20 мин назад").is_none());
+        assert!(parsed("200 g yogurt, 1 hour ago").is_none());
+        assert!(parsed("ข้าวผัด 1 จาน หลังวิ่ง 30 นาที").is_none());
+    }
+
+    #[test]
+    fn needs_duration_or_distance() {
+        assert!(parsed("бег").is_none());
+        assert_eq!(parsed("walk 5 km"), Some(("walking", 60.0)));
+    }
+}
@@ -0,0 +1,269 @@
+use image::{DynamicImage, GrayImage};
+
+/// Ширины (в модулях) четырёх полос цифры в наборе L (и R) для EAN-13.
+const L_PATTERNS: [[u8; 4]; 10] = [
+    [3, 2, 1, 1],
+    [2, 2, 2, 1],
+    [2, 1, 2, 2],
+    [1, 4, 1, 1],
+    [1, 1, 3, 2],
+    [1, 2, 3, 1],
+    [1, 1, 1, 4],
+    [1, 3, 1, 2],
+    [1, 2, 1, 3],
+    [3, 1, 1, 2],
+];
+
+/// Чётность левых шести цифр, кодирующая первую цифру (`true` — набор G).
+const FIRST_DIGIT_PARITY: [[bool; 6]; 10] = [
+    [false, false, false, false, false, false],
+    [false, false, true, false, true, true],
+    [false, false, true, true, false, true],
+    [false, false, true, true, true, false],
+    [false, true, false, false, true, true],
+    [false, true, true, false, false, true],
+    [false, true, true, true, false, false],
+    [false, true, false, true, false, true],
+    [false, true, false, true, true, false],
+    [false, true, true, false, true, false],
+];
+
+/// Число полос штрихкода EAN-13: 3 + 6·4 + 5 + 6·4 + 3.
+const EAN13_RUNS: usize = 59;
+
+/// Сколько строк (и столбцов) изображения просматривается при поиске кода.
+const SCAN_LINES: u32 = 24;
+
+/// Ищет на фотографии штрихкод EAN-13 или UPC-A (UPC-A возвращается как EAN-13 с ведущим нулём).
+pub fn detect_barcode(image_bytes: &[u8]) -> Option<String> {
+    let image = image::load_from_memory(image_bytes).ok()?;
+    let gray = downscale(image).to_luma8();
+
+    scan(&gray).or_else(|| scan(&image::imageops::rotate90(&gray)))
+}
+
+fn downscale(image: DynamicImage) -> DynamicImage {
+    if image.width() > 1600 || image.height() > 1600 {
+        image.resize(1600, 1600, image::imageops::FilterType::Triangle)
+    } else {
+        image
+    }
+}
+
+/// Просматривает горизонтальные строки в средней части изображения.
+fn scan(image: &GrayImage) -> Option<String> {
+    let (width, height) = image.dimensions();
+    if width < EAN13_RUNS as u32 * 2 || height == 0 {
+        return None;
+    }
+
+    (0..SCAN_LINES)
+        .map(|i| height / 5 + i * (height * 3 / 5) / SCAN_LINES)
+        .find_map(|y| {
+            let row: Vec<u8> = (0..width).map(|x| image.get_pixel(x, y)[0]).collect();
+            let runs = to_runs(&row);
+            decode_runs(&runs).or_else(|| {
+                let reversed: Vec<(bool, u32)> = runs.iter().rev().copied().collect();
+                decode_runs(&reversed)
+            })
+        })
+}
+
+/// Бинаризует строку пикселей и превращает её в последовательность полос `(тёмная, ширина)`.
+fn to_runs(row: &[u8]) -> Vec<(bool, u32)> {
+    let (min, max) = row
+        .iter()
+        .fold((u8::MAX, u8::MIN), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+    if max - min < 40 {
+        return Vec::new();
+    }
+    let threshold = (min as u16 + max as u16) / 2;
+
+    let mut runs: Vec<(bool, u32)> = Vec::new();
+    for &pixel in row {
+        let dark = (pixel as u16) < threshold;
+        match runs.last_mut() {
+            Some((color, width)) if *color == dark => *width += 1,
+            _ => runs.push((dark, 1)),
+        }
+    }
+    runs
+}
+
+fn decode_runs(runs: &[(bool, u32)]) -> Option<String> {
+    if runs.len() < EAN13_RUNS {
+        return None;
+    }
+
+    (0..=runs.len() - EAN13_RUNS)
+        .filter(|&i| runs[i].0)
+        .find_map(|i| decode_ean13(&runs[i..i + EAN13_RUNS]))
+}
+
+/// Декодирует 59 полос, начинающихся с тёмной полосы стартового ограничителя.
+fn decode_ean13(runs: &[(bool, u32)]) -> Option<String> {
+    let widths: Vec<f32> = runs.iter().map(|&(_, w)| w as f32).collect();
+    let module = widths.iter().sum::<f32>() / 95.0;
+    if module < 1.0 {
+        return None;
+    }
+
+    let is_guard = |range: &[f32]| range.iter().all(|w| (w / module - 1.0).abs() < 0.7);
+    if !is_guard(&widths[0..3]) || !is_guard(&widths[27..32]) || !is_guard(&widths[56..59]) {
+        return None;
+    }
+
+    let mut digits = Vec::with_capacity(13);
+    let mut parity = [false; 6];
+    for (n, chunk) in widths[3..27].chunks(4).enumerate() {
+        let (digit, is_g) = match_digit(chunk, true)?;
+        digits.push(digit);
+        parity[n] = is_g;
+    }
+    for chunk in widths[32..56].chunks(4) {
+        let (digit, _) = match_digit(chunk, false)?;
+        digits.push(digit);
+    }
+
+    let first = FIRST_DIGIT_PARITY.iter().position(|p| *p == parity)? as u8;
+    digits.insert(0, first);
+
+    if !checksum_ok(&digits) {
+        return None;
+    }
+    Some(digits.iter().map(|d| char::from(b'0' + d)).collect())
+}
+
+/// Подбирает цифру по четырём ширинам полос; для левой половины учитывает набор G.
+fn match_digit(widths: &[f32], left: bool) -> Option<(u8, bool)> {
+    let total: f32 = widths.iter().sum();
+    let scaled: Vec<f32> = widths.iter().map(|w| w * 7.0 / total).collect();
+
+    let error = |pattern: [u8; 4]| -> f32 {
+        scaled
+            .iter()
+            .zip(pattern)
+            .map(|(s, p)| (s - p as f32).powi(2))
+            .sum()
+    };
+
+    let mut best: Option<(u8, bool, f32)> = None;
+    for (digit, pattern) in L_PATTERNS.iter().enumerate() {
+        let mut candidates = vec![(false, *pattern)];
+        if left {
+            let mut g = *pattern;
+            g.reverse();
+            candidates.push((true, g));
+        }
+        for (is_g, p) in candidates {
+            let e = error(p);
+            if best.is_none_or(|(_, _, b)| e < b) {
+                best = Some((digit as u8, is_g, e));
+            }
+        }
+    }
+
+    best.filter(|&(_, _, e)| e < 1.5).map(|(d, g, _)| (d, g))
+}
+
+fn checksum_ok(digits: &[u8]) -> bool {
+    let sum: u32 = digits[..12]
+        .iter()
+        .enumerate()
+        .map(|(i, &d)| d as u32 * if i % 2 == 0 { 1 } else { 3 })
+        .sum();
+    (10 - sum % 10) % 10 == digits[12] as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageFormat, Luma};
+    use std::io::Cursor;
+
+    const MODULE: u32 = 3;
+    const QUIET: u32 = 12;
+
+    fn digits(code: &str) -> Vec<u8> {
+        code.bytes().map(|b| b - b'0').collect()
+    }
+
+    /// Полосы кода EAN-13 в модулях, начиная с тёмной полосы стартового ограничителя.
+    fn ean13_runs(code: &str) -> Vec<(bool, u32)> {
+        let digits = digits(code);
+        let parity = FIRST_DIGIT_PARITY[digits[0] as usize];
+        let mut runs = vec![(true, 1), (false, 1), (true, 1)];
+        for (n, &d) in digits[1..7].iter().enumerate() {
+            let mut widths = L_PATTERNS[d as usize];
+            if parity[n] {
+                widths.reverse();
+            }
+            runs.extend(widths.iter().enumerate().map(|(i, &w)| (i % 2 == 1, w as u32)));
+        }
+        runs.extend([(false, 1), (true, 1), (false, 1), (true, 1), (false, 1)]);
+        for &d in &digits[7..] {
+            let widths = L_PATTERNS[d as usize];
+            runs.extend(widths.iter().enumerate().map(|(i, &w)| (i % 2 == 0, w as u32)));
+        }
+        runs.extend([(true, 1), (false, 1), (true, 1)]);
+        runs
+    }
+
+    fn render(code: &str) -> GrayImage {
+        let runs = ean13_runs(code);
+        let width = (95 + 2 * QUIET) * MODULE;
+        let mut image = GrayImage::from_pixel(width, 80, Luma([255]));
+        let mut x = QUIET * MODULE;
+        for (dark, modules) in runs {
+            for _ in 0..modules * MODULE {
+                if dark {
+                    for y in 0..image.height() {
+                        image.put_pixel(x, y, Luma([20]));
+                    }
+                }
+                x += 1;
+            }
+        }
+        image
+    }
+
+    fn png(image: &GrayImage) -> Vec<u8> {
+        let mut bytes = Cursor::new(Vec::new());
+        image.write_to(&mut bytes, ImageFormat::Png).unwrap();
+        bytes.into_inner()
+    }
+
+    #[test]
+    fn decodes_rendered_barcode() {
+        for code in ["4006381333931", "4607001771234", "0012345678905"] {
+            assert_eq!(ean13_runs(code).len(), EAN13_RUNS);
+            let image = render(code);
+            assert_eq!(detect_barcode(&png(&image)).as_deref(), Some(code));
+            let mirrored = image::imageops::flip_horizontal(&image);
+            assert_eq!(detect_barcode(&png(&mirrored)).as_deref(), Some(code));
+            let rotated = image::imageops::rotate90(&image);
+            assert_eq!(detect_barcode(&png(&rotated)).as_deref(), Some(code));
+        }
+    }
+
+    #[test]
+    fn rejects_wrong_check_digit() {
+        let mut runs = ean13_runs("4006381333931");
+        // Последняя цифра 1 → 2: код остаётся читаемым, но не сходится контрольная сумма.
+        let last = EAN13_RUNS - 7;
+        for (i, &w) in L_PATTERNS[2].iter().enumerate() {
+            runs[last + i].1 = w as u32;
+        }
+        assert_eq!(decode_runs(&runs), None);
+        assert_eq!(detect_barcode(&png(&GrayImage::from_pixel(400, 80, Luma([255])))), None);
+    }
+
+    #[test]
+    fn checksum() {
+        assert!(checksum_ok(&digits("4006381333931")));
+        assert!(checksum_ok(&digits("4607001771234")));
+        assert!(checksum_ok(&digits("0012345678905")));
+        assert!(!checksum_ok(&digits("4006381333932")));
+        assert!(!checksum_ok(&digits("4607001771230")));
+    }
+}
@@ -1,79 +1,504 @@
-use image::{ImageBuffer, Rgb, RgbImage};
+use crate::services::profile::KCAL_PER_GRAM;
+use chrono::NaiveDate;
+use image::{ImageBuffer, ImageFormat, Rgb, RgbImage};
+use once_cell::sync::Lazy;
 use rusttype::{Font, Scale, point, PositionedGlyph};
+use std::io::Cursor;
 
-pub fn draw_weekly_calories_chart(
-    data: &[(String, f32)],
-    output_path: &str,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+/// Шрифт разбирается один раз при первом построении графика.
+static FONT: Lazy<Option<Font<'static>>> =
+    Lazy::new(|| Font::try_from_bytes(include_bytes!("../../assets/DejaVuSans.ttf")));
+
+/// Язык подписей на графиках. В шрифте нет тайских и китайских глифов,
+/// поэтому для этих языков подписи рисуются по-английски.
+pub fn label_lang(lang: &str) -> &str {
+    match lang {
+        "th" | "zh" => "en",
+        _ => lang,
+    }
+}
+
+/// Итоги одного дня для столбчатого графика калорий.
+pub struct DayBar {
+    pub label: String,
+    pub calories: f32,
+    pub proteins: f32,
+    pub fats: f32,
+    pub carbs: f32,
+    pub burned: f32,
+}
+
+/// Подписи графика калорий на языке пользователя.
+pub struct CalorieLegend<'a> {
+    pub unit: &'a str,
+    pub eaten: &'a str,
+    pub burned: &'a str,
+    pub target: &'a str,
+    /// Белки, жиры, углеводы.
+    pub macros: [&'a str; 3],
+}
+
+/// График калорий за произвольный период.
+pub struct CalorieChart<'a> {
+    pub title: &'a str,
+    pub days: &'a [DayBar],
+    /// Суточная норма — рисуется горизонтальной линией.
+    pub target: Option<f32>,
+    pub legend: CalorieLegend<'a>,
+}
+
+/// Строит график калорий по дням: столбцы делятся на белки, жиры и углеводы (по энергии),
+/// рядом — сожжённые калории, поверх — линия суточной нормы. Ширина столбцов и частота
+/// подписей подбираются по длине периода.
+pub fn draw_calorie_chart(
+    chart: &CalorieChart,
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
     let width: u32 = 800;
     let height: u32 = 400;
-    let margin: u32 = 50;
-    let spacing: u32 = 20;
-    let bar_width: u32 = 40;
+    let (left, right, top, bottom) = (60u32, 20u32, 70u32, 40u32);
+    let plot_width = (width - left - right) as f32;
+    let plot_height = (height - top - bottom) as f32;
+    let days = chart.days;
+    if days.is_empty() {
+        return Err("No calorie data".into());
+    }
+
+    let macro_colors = [Rgb([220, 80, 80]), Rgb([240, 180, 40]), Rgb([100, 149, 237])];
+    let eaten_color = Rgb([100, 149, 237]);
+    let burned_color = Rgb([120, 120, 120]);
+    let target_color = Rgb([40, 160, 90]);
+    let has_burned = days.iter().any(|d| d.burned > 0.0);
+    let has_macros = days.iter().any(|d| d.proteins + d.fats + d.carbs > 0.0);
 
-    // Calculate the maximum value for scaling the bars
-    let max_val = data
+    // Scale so that the tallest bar and the target line fit with some headroom
+    let max_val = days
         .iter()
-        .map(|(_, v)| *v)
+        .flat_map(|d| [d.calories, d.burned])
+        .chain(chart.target)
         .fold(0.0f32, f32::max)
-        .max(1.0); // Avoid division by zero
+        .max(1.0)
+        * 1.1;
+    let to_height = |value: f32| (value / max_val * plot_height).round() as u32;
 
     let mut img: RgbImage = ImageBuffer::from_pixel(width, height, Rgb([255, 255, 255]));
+    let font = load_font()?;
+    let scale = Scale::uniform(16.0);
 
-    // Load embedded font with better error handling
-    let font_data = include_bytes!("../../assets/DejaVuSans.ttf");
-    let font = Font::try_from_vec(font_data.to_vec())
-        .ok_or_else(|| {
-            log::error!("Failed to load font from ../../assets/DejaVuSans.ttf");
-            "Failed to load font"
-        })?;
-    let scale = Scale::uniform(18.0);
+    // Draw Y-axis labels and grid lines
+    let num_grid_lines = 5;
+    for i in 0..=num_grid_lines {
+        let y = top + i * (height - top - bottom) / num_grid_lines;
+        let value = max_val * (1.0 - i as f32 / num_grid_lines as f32);
+        for x in left..width - right {
+            img.put_pixel(x, y, Rgb([220, 220, 220]));
+        }
+        let label = format!("{:.0}", value);
+        let x = (left as f32 - 6.0 - text_width(font, &label, scale)).max(0.0);
+        draw_text(&mut img, &label, x as u32, y.saturating_sub(9), scale, font, Rgb([0, 0, 0]));
+    }
+    draw_text(&mut img, chart.legend.unit, 5, top - 26, scale, font, Rgb([90, 90, 90]));
+
+    // Bar width follows the number of days
+    let slot = plot_width / days.len() as f32;
+    let (eaten_width, burned_width) = if has_burned {
+        ((slot * 0.55).max(1.0), (slot * 0.25).max(1.0))
+    } else {
+        ((slot * 0.7).max(1.0), 0.0)
+    };
+    let bar_start = (slot - eaten_width - burned_width) / 2.0;
+
+    // Show every n-th date so the labels don't overlap
+    let label_width = text_width(font, "00.00", scale) + 10.0;
+    let label_step = (label_width / slot).ceil().max(1.0) as usize;
+
+    let baseline = height - bottom;
+    for (i, day) in days.iter().enumerate() {
+        let x0 = left as f32 + i as f32 * slot + bar_start;
+        let bar_height = to_height(day.calories);
+
+        // Stacked macro segments proportional to their energy share
+        let energy: Vec<f32> = [day.proteins, day.fats, day.carbs]
+            .iter()
+            .zip(KCAL_PER_GRAM)
+            .map(|(g, k)| g.max(0.0) * k)
+            .collect();
+        let total_energy: f32 = energy.iter().sum();
+        let segments: Vec<(u32, Rgb<u8>)> = if has_macros && total_energy > 0.0 {
+            let mut cumulative = 0.0;
+            let mut drawn = 0;
+            energy
+                .iter()
+                .zip(macro_colors)
+                .map(|(e, color)| {
+                    cumulative += e / total_energy;
+                    let top_of_segment = (cumulative * bar_height as f32).round() as u32;
+                    let segment = top_of_segment - drawn;
+                    drawn = top_of_segment;
+                    (segment, color)
+                })
+                .collect()
+        } else {
+            vec![(bar_height, eaten_color)]
+        };
+        let mut y = baseline;
+        for (segment, color) in segments {
+            fill_rect(&mut img, x0, y - segment, eaten_width, segment, color);
+            y -= segment;
+        }
+
+        if day.burned > 0.0 {
+            let burned_height = to_height(day.burned);
+            fill_rect(
+                &mut img,
+                x0 + eaten_width,
+                baseline - burned_height,
+                burned_width,
+                burned_height,
+                burned_color,
+            );
+        }
+
+        if i % label_step == 0 {
+            let center = left as f32 + (i as f32 + 0.5) * slot;
+            let x = (center - text_width(font, &day.label, scale) / 2.0).max(0.0);
+            draw_text(&mut img, &day.label, x as u32, baseline + 10, scale, font, Rgb([0, 0, 0]));
+        }
+    }
+
+    // Daily target: dashed horizontal line
+    if let Some(target) = chart.target.filter(|t| *t > 0.0) {
+        let y = baseline - to_height(target);
+        for x in (left..width - right).filter(|x| (x / 8) % 2 == 0) {
+            for dy in 0..2 {
+                img.put_pixel(x, y + dy, target_color);
+            }
+        }
+        let label = format!("{} {:.0}", chart.legend.target, target);
+        let x = width - right - text_width(font, &label, scale) as u32 - 4;
+        draw_text(&mut img, &label, x, y.saturating_sub(20), scale, font, target_color);
+    }
+
+    // Legend under the title
+    let mut legend: Vec<(&str, Rgb<u8>)> = if has_macros {
+        chart.legend.macros.iter().copied().zip(macro_colors).collect()
+    } else {
+        vec![(chart.legend.eaten, eaten_color)]
+    };
+    if has_burned {
+        legend.push((chart.legend.burned, burned_color));
+    }
+    let mut x = left;
+    for (label, color) in legend {
+        fill_rect(&mut img, x as f32, 44, 12.0, 12, color);
+        draw_text(&mut img, label, x + 18, 40, scale, font, Rgb([0, 0, 0]));
+        x += 18 + text_width(font, label, scale) as u32 + 24;
+    }
+
+    // Draw title
+    let title_scale = Scale::uniform(24.0);
+    let title_x = (width as f32 - text_width(font, chart.title, title_scale)) / 2.0;
+    draw_text(&mut img, chart.title, title_x.max(0.0) as u32, 8, title_scale, font, Rgb([0, 0, 0]));
+
+    encode_png(&img)
+}
+
+/// Закрашивает прямоугольник; `x` и ширина дробные, чтобы узкие столбцы не слипались.
+fn fill_rect(image: &mut RgbImage, x: f32, y: u32, width: f32, height: u32, color: Rgb<u8>) {
+    let (x_start, x_end) = (x.round() as u32, (x + width).round().max(x.round() + 1.0) as u32);
+    for px in x_start..x_end.min(image.width()) {
+        for py in y..(y + height).min(image.height()) {
+            image.put_pixel(px, py, color);
+        }
+    }
+}
+
+/// Подписи графика веса на языке пользователя.
+pub struct WeightLegend<'a> {
+    pub title: &'a str,
+    /// Подпись отдельных измерений («Вес, кг»).
+    pub weight: &'a str,
+    /// Подпись скользящей средней.
+    pub average: String,
+}
+
+/// Строит график веса: отдельные измерения и сглаженная скользящая средняя за `window_days` дней.
+pub fn draw_weight_chart(
+    data: &[(NaiveDate, f32)],
+    window_days: i64,
+    legend: &WeightLegend,
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    let width: u32 = 800;
+    let height: u32 = 400;
+    let margin: u32 = 60;
+
+    let (Some(first), Some(last)) = (data.first(), data.last()) else {
+        return Err("No weight data".into());
+    };
+    let average = moving_average(data, window_days);
+
+    // Vertical range with some padding so the line doesn't touch the edges
+    let (min_val, max_val) = data
+        .iter()
+        .map(|(_, v)| *v)
+        .chain(average.iter().copied())
+        .fold((f32::MAX, f32::MIN), |(lo, hi), v| (lo.min(v), hi.max(v)));
+    let padding = ((max_val - min_val) * 0.1).max(0.5);
+    let (min_val, max_val) = (min_val - padding, max_val + padding);
+
+    let span_days = (last.0 - first.0).num_days().max(1) as f32;
+    let plot_width = (width - 2 * margin) as f32;
+    let plot_height = (height - 2 * margin) as f32;
+    let to_x = |date: NaiveDate| {
+        if data.len() == 1 {
+            margin as f32 + plot_width / 2.0
+        } else {
+            margin as f32 + (date - first.0).num_days() as f32 / span_days * plot_width
+        }
+    };
+    let to_y = |value: f32| margin as f32 + (max_val - value) / (max_val - min_val) * plot_height;
+
+    let mut img: RgbImage = ImageBuffer::from_pixel(width, height, Rgb([255, 255, 255]));
+    let font = load_font()?;
+    let scale = Scale::uniform(16.0);
 
     // Draw Y-axis labels and grid lines
     let num_grid_lines = 5;
     for i in 0..=num_grid_lines {
         let y = margin + (i * (height - 2 * margin) / num_grid_lines);
-        let calorie_value = max_val * (1.0 - i as f32 / num_grid_lines as f32);
-
-        // Draw grid line
-        for x in margin..width {
-            img.put_pixel(x, y, Rgb([200, 200, 200]));
+        let value = max_val - (max_val - min_val) * i as f32 / num_grid_lines as f32;
+        for x in margin..width - margin / 2 {
+            img.put_pixel(x, y, Rgb([220, 220, 220]));
         }
+        draw_text(&mut img, &format!("{:.1}", value), 5, y - 9, scale, font, Rgb([0, 0, 0]));
+    }
+
+    // Raw measurements: thin line with dots
+    let raw_color = Rgb([150, 180, 230]);
+    for pair in data.windows(2) {
+        draw_line(
+            &mut img,
+            (to_x(pair[0].0), to_y(pair[0].1)),
+            (to_x(pair[1].0), to_y(pair[1].1)),
+            1,
+            raw_color,
+        );
+    }
+    for (date, value) in data {
+        draw_dot(&mut img, to_x(*date), to_y(*value), 3, Rgb([100, 149, 237]));
+    }
+
+    // Smoothed trend
+    let trend_color = Rgb([230, 120, 40]);
+    for (pair, avg) in data.windows(2).zip(average.windows(2)) {
+        draw_line(
+            &mut img,
+            (to_x(pair[0].0), to_y(avg[0])),
+            (to_x(pair[1].0), to_y(avg[1])),
+            3,
+            trend_color,
+        );
+    }
+
+    // X-axis labels: first, middle and last dates
+    let mut label_dates = vec![first.0, last.0];
+    if data.len() > 2 {
+        label_dates.push(data[data.len() / 2].0);
+    }
+    for date in label_dates {
+        let label = date.format("%d.%m").to_string();
+        let x = (to_x(date) as u32).saturating_sub(label.len() as u32 * 4);
+        draw_text(&mut img, &label, x, height - margin + 10, scale, font, Rgb([0, 0, 0]));
+    }
+
+    // Legend
+    draw_dot(&mut img, (margin + 10) as f32, (height - 18) as f32, 4, Rgb([100, 149, 237]));
+    draw_text(&mut img, legend.weight, margin + 20, height - 27, scale, font, Rgb([0, 0, 0]));
+    let line_x = margin + 20 + text_width(font, legend.weight, scale) as u32 + 24;
+    draw_line(
+        &mut img,
+        (line_x as f32, (height - 18) as f32),
+        ((line_x + 25) as f32, (height - 18) as f32),
+        3,
+        trend_color,
+    );
+    draw_text(&mut img, &legend.average, line_x + 35, height - 27, scale, font, Rgb([0, 0, 0]));
+
+    // Draw title
+    let title_scale = Scale::uniform(26.0);
+    let title_x = ((width as f32 - text_width(font, legend.title, title_scale)) / 2.0).max(0.0);
+    draw_text(&mut img, legend.title, title_x as u32, 15, title_scale, font, Rgb([0, 0, 0]));
+
+    encode_png(&img)
+}
+
+/// Данные для кольцевой диаграммы БЖУ; подписи передаются уже на языке пользователя.
+pub struct MacroDonut<'a> {
+    pub title: &'a str,
+    /// Белки, жиры, углеводы.
+    pub labels: [&'a str; 3],
+    /// Съедено граммов (в среднем за день, если период длиннее суток).
+    pub grams: [f32; 3],
+    /// Рекомендуемая доля энергии, %.
+    pub target_percent: [f32; 3],
+    /// Норма в граммах, если известна норма калорий.
+    pub target_grams: Option<[f32; 3]>,
+    pub target_label: &'a str,
+}
+
+/// Строит кольцевую диаграмму распределения энергии между белками, жирами и углеводами.
+/// Внешнее тонкое кольцо показывает рекомендуемое распределение.
+pub fn draw_macro_donut_chart(
+    chart: &MacroDonut,
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    let width: u32 = 800;
+    let height: u32 = 400;
+    let (cx, cy) = (220.0f32, 215.0f32);
+    let (inner_radius, outer_radius) = (85.0f32, 150.0f32);
+    let (ring_inner, ring_outer) = (158.0f32, 170.0f32);
+    let colors = [Rgb([220, 80, 80]), Rgb([240, 180, 40]), Rgb([100, 149, 237])];
+    let light_colors = [Rgb([240, 175, 175]), Rgb([250, 220, 150]), Rgb([185, 205, 245])];
 
-        // Draw Y-axis label
-        let label = format!("{:.0}", calorie_value);
-        draw_text(&mut img, &label, 5, y - 10, scale, &font, Rgb([0, 0, 0]));
+    let energy: Vec<f32> = chart
+        .grams
+        .iter()
+        .zip(KCAL_PER_GRAM)
+        .map(|(g, k)| g.max(0.0) * k)
+        .collect();
+    let total: f32 = energy.iter().sum();
+    if total <= 0.0 {
+        return Err("No macro data".into());
     }
+    let shares: Vec<f32> = energy.iter().map(|e| e / total).collect();
+    let target_total: f32 = chart.target_percent.iter().sum::<f32>().max(1.0);
+    let target_shares: Vec<f32> = chart.target_percent.iter().map(|p| p / target_total).collect();
 
-    // Draw bars and X-axis labels
-    for (i, (label, value)) in data.iter().enumerate() {
-        let i = i as u32;
-        let bar_height = ((value / max_val) * (height - 2 * margin) as f32) as u32;
-        let x = margin + i * (bar_width + spacing);
-        let y = height - bar_height - margin;
+    let mut img: RgbImage = ImageBuffer::from_pixel(width, height, Rgb([255, 255, 255]));
+    let font = load_font()?;
 
-        // Draw bar
-        for dx in 0..bar_width {
-            for dy in 0..bar_height {
-                img.put_pixel(x + dx, y + dy, Rgb([100, 149, 237]));
+    // Доля окружности по часовой стрелке от «12 часов»
+    let segment_at = |shares: &[f32], fraction: f32| {
+        let mut cumulative = 0.0;
+        for (i, share) in shares.iter().enumerate() {
+            cumulative += share;
+            if fraction < cumulative {
+                return i;
             }
         }
+        shares.len() - 1
+    };
+    for y in 0..height {
+        for x in 0..width / 2 {
+            let (dx, dy) = (x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+            let distance = (dx * dx + dy * dy).sqrt();
+            let fraction = (dx.atan2(-dy) / std::f32::consts::TAU).rem_euclid(1.0);
+            if (inner_radius..=outer_radius).contains(&distance) {
+                img.put_pixel(x, y, colors[segment_at(&shares, fraction)]);
+            } else if (ring_inner..=ring_outer).contains(&distance) {
+                img.put_pixel(x, y, light_colors[segment_at(&target_shares, fraction)]);
+            }
+        }
+    }
+
+    // Total energy in the middle of the donut
+    let total_label = format!("{:.0}", total);
+    let total_scale = Scale::uniform(36.0);
+    let total_x = cx - text_width(font, &total_label, total_scale) / 2.0;
+    draw_text(&mut img, &total_label, total_x as u32, cy as u32 - 28, total_scale, font, Rgb([0, 0, 0]));
+    let kcal_scale = Scale::uniform(18.0);
+    let kcal_x = cx - text_width(font, "kcal", kcal_scale) / 2.0;
+    draw_text(&mut img, "kcal", kcal_x as u32, cy as u32 + 10, kcal_scale, font, Rgb([90, 90, 90]));
 
-        // Draw label (day name) below the bar
-        let label_x = x + (bar_width / 2) - (label.len() as u32 * 5); // Center the label
-        let label_y = height - margin + 15; // Move label below the bar
-        draw_text(&mut img, label, label_x, label_y, scale, &font, Rgb([0, 0, 0]));
+    // Legend: actual grams and share vs. target
+    let scale = Scale::uniform(20.0);
+    let small_scale = Scale::uniform(16.0);
+    let legend_x = 430;
+    for (i, label) in chart.labels.iter().enumerate() {
+        let y = 110 + i as u32 * 80;
+        for dx in 0..18 {
+            for dy in 0..18 {
+                img.put_pixel(legend_x + dx, y + dy + 2, colors[i]);
+            }
+        }
+        let line = format!("{}: {:.0} g · {:.0}%", label, chart.grams[i], shares[i] * 100.0);
+        draw_text(&mut img, &line, legend_x + 28, y, scale, font, Rgb([0, 0, 0]));
+        let target = match chart.target_grams {
+            Some(grams) => format!("{}: {:.0} g · {:.0}%", chart.target_label, grams[i], chart.target_percent[i]),
+            None => format!("{}: {:.0}%", chart.target_label, chart.target_percent[i]),
+        };
+        draw_text(&mut img, &target, legend_x + 28, y + 28, small_scale, font, Rgb([110, 110, 110]));
     }
 
     // Draw title
-    let title = "Weekly Calorie Intake";
     let title_scale = Scale::uniform(26.0);
-    let title_x = (width / 2) - (title.len() as u32 * 6); // Center the title
-    draw_text(&mut img, title, title_x, 20, title_scale, &font, Rgb([0, 0, 0]));
+    let title_x = (width as f32 - text_width(font, chart.title, title_scale)) / 2.0;
+    draw_text(&mut img, chart.title, title_x.max(0.0) as u32, 12, title_scale, font, Rgb([0, 0, 0]));
+
+    encode_png(&img)
+}
+
+/// Скользящая средняя: для каждой точки — среднее измерений за последние `window_days` дней.
+pub fn moving_average(data: &[(NaiveDate, f32)], window_days: i64) -> Vec<f32> {
+    data.iter()
+        .enumerate()
+        .map(|(i, (date, _))| {
+            let window: Vec<f32> = data[..=i]
+                .iter()
+                .filter(|(d, _)| (*date - *d).num_days() < window_days)
+                .map(|(_, v)| *v)
+                .collect();
+            window.iter().sum::<f32>() / window.len() as f32
+        })
+        .collect()
+}
 
-    // Save the image
-    img.save(output_path)?;
-    Ok(())
+/// Ширина строки в пикселях для центрирования.
+fn text_width(font: &Font, text: &str, scale: Scale) -> f32 {
+    font.layout(text, scale, point(0.0, 0.0))
+        .filter_map(|g| g.pixel_bounding_box().map(|bb| bb.max.x as f32))
+        .fold(0.0, f32::max)
+}
+
+fn load_font() -> Result<&'static Font<'static>, Box<dyn std::error::Error + Send + Sync>> {
+    FONT.as_ref().ok_or_else(|| {
+        log::error!("Failed to load font from ../../assets/DejaVuSans.ttf");
+        "Failed to load font".into()
+    })
+}
+
+/// Кодирует изображение в PNG.
+fn encode_png(img: &RgbImage) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut bytes = Vec::new();
+    img.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)?;
+    Ok(bytes)
+}
+
+/// Рисует отрезок заданной толщины.
+fn draw_line(image: &mut RgbImage, from: (f32, f32), to: (f32, f32), thickness: i32, color: Rgb<u8>) {
+    let steps = (to.0 - from.0).abs().max((to.1 - from.1).abs()).ceil().max(1.0) as i32;
+    for step in 0..=steps {
+        let t = step as f32 / steps as f32;
+        let x = from.0 + (to.0 - from.0) * t;
+        let y = from.1 + (to.1 - from.1) * t;
+        draw_dot(image, x, y, thickness / 2, color);
+    }
+}
+
+/// Рисует закрашенный круг радиусом `radius` пикселей.
+fn draw_dot(image: &mut RgbImage, cx: f32, cy: f32, radius: i32, color: Rgb<u8>) {
+    let (cx, cy) = (cx.round() as i32, cy.round() as i32);
+    for dy in -radius..=radius {
+        for dx in -radius..=radius {
+            let (x, y) = (cx + dx, cy + dy);
+            if dx * dx + dy * dy <= radius * radius
+                && x >= 0
+                && y >= 0
+                && x < image.width() as i32
+                && y < image.height() as i32
+            {
+                image.put_pixel(x as u32, y as u32, color);
+            }
+        }
+    }
 }
 
 fn draw_text(
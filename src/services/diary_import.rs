@@ -0,0 +1,477 @@
+use crate::db::models::{FoodProvenance, Micronutrients, NewFoodLog};
+use crate::services::log_time::local_to_utc;
+use crate::services::meal::MealType;
+use crate::services::nutrition::InputSource;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use chrono_tz::Tz;
+use std::collections::HashMap;
+
+/// Больше строк за один импорт не принимаем.
+pub const MAX_ROWS: usize = 50_000;
+
+const DATE_FORMATS: [&str; 7] = [
+    "%Y-%m-%d",
+    "%m/%d/%Y",
+    "%d.%m.%Y",
+    "%Y/%m/%d",
+    "%A, %B %d, %Y",
+    "%B %d, %Y",
+    "%d %B %Y",
+];
+const DATETIME_FORMATS: [&str; 3] = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%m/%d/%Y %H:%M"];
+const TIME_FORMATS: [&str; 4] = ["%H:%M", "%H:%M:%S", "%I:%M %p", "%I:%M:%S %p"];
+
+/// Приложение, из которого выгружен файл (определяется по заголовку).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Source {
+    MyFitnessPal,
+    FatSecret,
+    Cronometer,
+    Other,
+}
+
+impl Source {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Source::MyFitnessPal => "MyFitnessPal",
+            Source::FatSecret => "FatSecret",
+            Source::Cronometer => "Cronometer",
+            Source::Other => "CSV",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ImportError {
+    /// В заголовке нет даты или калорий.
+    UnknownFormat,
+    TooManyRows,
+    Csv(csv::Error),
+}
+
+impl From<csv::Error> for ImportError {
+    fn from(e: csv::Error) -> Self {
+        ImportError::Csv(e)
+    }
+}
+
+impl ImportError {
+    pub fn message(&self, lang: &str) -> String {
+        match (self, lang) {
+            (ImportError::UnknownFormat, "ru") => "📄 Не удалось распознать файл: нужны столбцы с датой и калориями. Поддерживаются выгрузки MyFitnessPal, FatSecret и Cronometer.".to_string(),
+            (ImportError::UnknownFormat, "th") => "📄 ไม่รู้จักรูปแบบไฟล์: ต้องมีคอลัมน์วันที่และแคลอรี รองรับไฟล์ส่งออกจาก MyFitnessPal, FatSecret และ Cronometer".to_string(),
+            (ImportError::UnknownFormat, "zh") => "📄 无法识别文件：需要包含日期和热量列。支持 MyFitnessPal、FatSecret 和 Cronometer 的导出文件。".to_string(),
+            (ImportError::UnknownFormat, _) => "📄 Couldn't recognise the file: it needs date and calorie columns. Exports from MyFitnessPal, FatSecret and Cronometer are supported.".to_string(),
+            (ImportError::TooManyRows, "ru") => format!("📄 Слишком большой файл: не более {} строк за раз.", MAX_ROWS),
+            (ImportError::TooManyRows, "th") => format!("📄 ไฟล์ใหญ่เกินไป: ไม่เกิน {} แถวต่อครั้ง", MAX_ROWS),
+            (ImportError::TooManyRows, "zh") => format!("📄 文件过大：每次最多 {} 行。", MAX_ROWS),
+            (ImportError::TooManyRows, _) => format!("📄 The file is too large: at most {} rows at a time.", MAX_ROWS),
+            (ImportError::Csv(e), "ru") => format!("📄 Не удалось прочитать CSV: {}", e),
+            (ImportError::Csv(e), "th") => format!("📄 อ่าน CSV ไม่ได้: {}", e),
+            (ImportError::Csv(e), "zh") => format!("📄 无法读取 CSV：{}", e),
+            (ImportError::Csv(e), _) => format!("📄 Couldn't read the CSV: {}", e),
+        }
+    }
+}
+
+/// Результат разбора файла: записи для дневника и строки, которые не удалось прочитать.
+#[derive(Debug)]
+pub struct ParsedImport {
+    pub source: Source,
+    pub entries: Vec<NewFoodLog>,
+    /// Номер строки файла и причина.
+    pub skipped: Vec<(usize, String)>,
+}
+
+impl ParsedImport {
+    pub fn date_range(&self) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let first = self.entries.iter().map(|e| e.eaten_at).min()?;
+        let last = self.entries.iter().map(|e| e.eaten_at).max()?;
+        Some((first, last))
+    }
+
+    /// Убирает записи, которые уже есть в дневнике (то же время, название и калории).
+    /// Возвращает количество убранных.
+    pub fn remove_existing(&mut self, existing: &[(DateTime<Utc>, String, Option<f32>)]) -> usize {
+        let mut counts: HashMap<(i64, String, i64), usize> = HashMap::new();
+        for (at, name, calories) in existing {
+            *counts.entry(dedupe_key(*at, name, *calories)).or_default() += 1;
+        }
+        let before = self.entries.len();
+        self.entries.retain(|e| {
+            match counts.get_mut(&dedupe_key(e.eaten_at, &e.food_name, e.calories)) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    false
+                }
+                _ => true,
+            }
+        });
+        before - self.entries.len()
+    }
+}
+
+fn dedupe_key(at: DateTime<Utc>, name: &str, calories: Option<f32>) -> (i64, String, i64) {
+    (
+        at.timestamp() / 60,
+        name.trim().to_lowercase(),
+        calories.unwrap_or(0.0).round() as i64,
+    )
+}
+
+/// Колонки выгрузки, найденные по заголовку.
+struct Columns {
+    date: usize,
+    calories: usize,
+    time: Option<usize>,
+    meal: Option<usize>,
+    name: Option<usize>,
+    proteins: Option<usize>,
+    fats: Option<usize>,
+    carbs: Option<usize>,
+    fiber: Option<usize>,
+    sugar: Option<usize>,
+    saturated_fat: Option<usize>,
+    sodium: Option<usize>,
+    potassium: Option<usize>,
+}
+
+impl Columns {
+    /// Сопоставляет заголовки MyFitnessPal («Date, Meal, Calories, Fat (g)…»),
+    /// Cronometer («Day, Time, Group, Food Name, Energy (kcal)…») и FatSecret («Date, Meal, Food, Cals, Fat, Carbs, Prot…»).
+    fn detect(headers: &csv::StringRecord) -> Option<(Self, Source)> {
+        let headers: Vec<String> = headers.iter().map(|h| h.trim().to_lowercase()).collect();
+        let find = |exact: &[&str], prefixes: &[&str]| {
+            headers
+                .iter()
+                .position(|h| exact.contains(&h.as_str()))
+                .or_else(|| headers.iter().position(|h| prefixes.iter().any(|p| h.starts_with(p))))
+        };
+        let has = |header: &str| headers.iter().any(|h| h == header);
+
+        let columns = Columns {
+            date: find(&["date", "day", "дата"], &["date"])?,
+            calories: find(&["calories", "energy (kcal)", "cals", "kcal"], &["calorie", "energy", "cals", "kcal", "калор"])?,
+            time: find(&["time", "время"], &[]),
+            meal: find(&["meal", "group", "meal type"], &["meal"]),
+            name: find(&["food name", "food", "name", "description", "item", "название", "продукт"], &["food name"]),
+            proteins: find(&["protein", "prot"], &["protein", "белк"]),
+            fats: find(&["fat", "total fat"], &["fat (", "fat(", "total fat", "жир"]),
+            carbs: find(&["carbs", "carbohydrates", "carbh"], &["carbs (", "carbohydrate", "углев"]),
+            fiber: find(&["fiber", "fibre"], &["fiber", "fibre"]),
+            sugar: find(&["sugar", "sugars"], &["sugar"]),
+            saturated_fat: find(&["sat fat", "saturated fat"], &["saturated"]),
+            sodium: find(&["sodium (mg)", "sodium"], &["sodium"]),
+            potassium: find(&["potassium (mg)", "potassium"], &["potassium"]),
+        };
+
+        let source = if has("food name") && has("energy (kcal)") {
+            Source::Cronometer
+        } else if has("cals") || has("prot") || has("carbh") {
+            Source::FatSecret
+        } else if columns.name.is_none() && columns.meal.is_some() && has("protein (g)") {
+            Source::MyFitnessPal
+        } else {
+            Source::Other
+        };
+        Some((columns, source))
+    }
+}
+
+/// Разбирает CSV-выгрузку дневника. Время без явного значения берётся типичным для приёма пищи,
+/// даты и время считаются местными для пояса `tz`. Записи без названия (итоги приёмов пищи
+/// в MyFitnessPal) называются по приёму пищи и источнику.
+pub fn parse_csv(
+    content: &str,
+    tz: Tz,
+    now: DateTime<Utc>,
+    lang: &str,
+) -> Result<ParsedImport, ImportError> {
+    let content = content.trim_start_matches('\u{feff}');
+    let header_line = content.lines().next().unwrap_or_default();
+    let delimiter = if header_line.matches(';').count() > header_line.matches(',').count() {
+        b';'
+    } else {
+        b','
+    };
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_reader(content.as_bytes());
+    let (columns, source) = Columns::detect(reader.headers()?).ok_or(ImportError::UnknownFormat)?;
+
+    let mut parsed = ParsedImport {
+        source,
+        entries: Vec::new(),
+        skipped: Vec::new(),
+    };
+    for (i, record) in reader.records().enumerate() {
+        let line = i + 2;
+        if i >= MAX_ROWS {
+            return Err(ImportError::TooManyRows);
+        }
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                parsed.skipped.push((line, e.to_string()));
+                continue;
+            }
+        };
+        if record.iter().all(|field| field.trim().is_empty()) {
+            continue;
+        }
+
+        let field = |idx: Option<usize>| idx.and_then(|i| record.get(i)).map(str::trim).filter(|v| !v.is_empty());
+        let number = |idx: Option<usize>| {
+            field(idx).and_then(|v| v.replace(',', ".").replace(' ', "").parse::<f32>().ok())
+        };
+
+        let Some(date_value) = field(Some(columns.date)) else {
+            parsed.skipped.push((line, "empty date".into()));
+            continue;
+        };
+        let Some((date, datetime_time)) = parse_date(date_value) else {
+            parsed.skipped.push((line, format!("invalid date '{}'", date_value)));
+            continue;
+        };
+        let Some(calories) = number(Some(columns.calories)) else {
+            parsed.skipped.push((line, "invalid calories".into()));
+            continue;
+        };
+
+        let time = datetime_time.or_else(|| field(columns.time).and_then(parse_time));
+        let meal = field(columns.meal)
+            .and_then(parse_meal)
+            .or_else(|| time.map(MealType::from_local_time))
+            .unwrap_or(MealType::Snack);
+        let name = match field(columns.name) {
+            Some(name) => name.to_string(),
+            None => format!("{} ({})", meal.label(lang), source.label()),
+        };
+        let Some(eaten_at) = local_to_utc(date, time.unwrap_or_else(|| meal.default_time()), tz) else {
+            parsed.skipped.push((line, format!("invalid local time on {}", date)));
+            continue;
+        };
+        if eaten_at > now {
+            parsed.skipped.push((line, format!("date in the future: {}", date)));
+            continue;
+        }
+
+        parsed.entries.push(NewFoodLog {
+            food_name: name,
+            calories: Some(calories),
+            proteins: number(columns.proteins),
+            fats: number(columns.fats),
+            carbs: number(columns.carbs),
+            meal_type: meal.as_str().to_string(),
+            eaten_at,
+            micros: Micronutrients {
+                fiber: number(columns.fiber),
+                sugar: number(columns.sugar),
+                saturated_fat: number(columns.saturated_fat),
+                sodium_mg: number(columns.sodium),
+                potassium_mg: number(columns.potassium),
+                ..Default::default()
+            },
+            // Исходная строка CSV — чтобы запись можно было сверить с выгрузкой.
+            provenance: FoodProvenance {
+                input_text: Some(record.iter().collect::<Vec<_>>().join(&(delimiter as char).to_string())),
+                ..InputSource::Import.provenance()
+            },
+        });
+    }
+
+    Ok(parsed)
+}
+
+/// Дата или дата со временем.
+fn parse_date(value: &str) -> Option<(NaiveDate, Option<NaiveTime>)> {
+    DATETIME_FORMATS
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(value, f).ok())
+        .map(|dt| (dt.date(), Some(dt.time())))
+        .or_else(|| {
+            DATE_FORMATS
+                .iter()
+                .find_map(|f| NaiveDate::parse_from_str(value, f).ok())
+                .map(|date| (date, None))
+        })
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    let value = value.to_uppercase();
+    TIME_FORMATS
+        .iter()
+        .find_map(|f| NaiveTime::parse_from_str(&value, f).ok())
+}
+
+/// Название приёма пищи из выгрузки («Breakfast», «Snacks», «Supper», «Обед»).
+fn parse_meal(value: &str) -> Option<MealType> {
+    MealType::from_name(value).or_else(|| {
+        let value = value.to_lowercase();
+        if value.contains("breakfast") || value.contains("завтрак") {
+            Some(MealType::Breakfast)
+        } else if value.contains("lunch") || value.contains("обед") {
+            Some(MealType::Lunch)
+        } else if value.contains("dinner") || value.contains("supper") || value.contains("ужин") {
+            Some(MealType::Dinner)
+        } else if value.contains("snack") || value.contains("перекус") {
+            Some(MealType::Snack)
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap()
+    }
+
+    fn parse(content: &str) -> ParsedImport {
+        parse_csv(content, Tz::UTC, now(), "en").unwrap()
+    }
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    #[test]
+    fn myfitnesspal_export() {
+        let parsed = parse(
+            "Date,Meal,Calories,Fat (g),Carbohydrates (g),Protein (g),Sodium (mg)\n\
+             2026-03-01,Breakfast,420,12.5,55,20,300\n\
+             2026-03-01,Dinner,650,25,60,40,800\n",
+        );
+        assert_eq!(parsed.source, Source::MyFitnessPal);
+        assert_eq!(parsed.entries.len(), 2);
+        let breakfast = &parsed.entries[0];
+        assert_eq!(breakfast.food_name, "Breakfast (MyFitnessPal)");
+        assert_eq!(breakfast.meal_type, "breakfast");
+        assert_eq!(breakfast.eaten_at, at(2026, 3, 1, 8, 0));
+        assert_eq!(breakfast.calories, Some(420.0));
+        assert_eq!(breakfast.fats, Some(12.5));
+        assert_eq!(breakfast.carbs, Some(55.0));
+        assert_eq!(breakfast.proteins, Some(20.0));
+        assert_eq!(breakfast.micros.sodium_mg, Some(300.0));
+        assert_eq!(parsed.entries[1].eaten_at, at(2026, 3, 1, 19, 0));
+    }
+
+    #[test]
+    fn fatsecret_export() {
+        let parsed = parse(
+            "Date,Meal,Food,Cals,Fat,Carbh,Prot\n\
+             03/02/2026,Lunch,Chicken soup,310,9,28,24\n",
+        );
+        assert_eq!(parsed.source, Source::FatSecret);
+        let soup = &parsed.entries[0];
+        assert_eq!(soup.food_name, "Chicken soup");
+        assert_eq!(soup.meal_type, "lunch");
+        assert_eq!(soup.eaten_at, at(2026, 3, 2, 13, 0));
+        assert_eq!(soup.calories, Some(310.0));
+        assert_eq!(soup.fats, Some(9.0));
+        assert_eq!(soup.carbs, Some(28.0));
+        assert_eq!(soup.proteins, Some(24.0));
+    }
+
+    #[test]
+    fn cronometer_export() {
+        let parsed = parse(
+            "Day,Time,Group,Food Name,Amount,Energy (kcal),Protein (g),Fiber (g)\n\
+             2026-03-03,7:45 AM,Breakfast,\"Oats, rolled\",50 g,190,6.5,5\n",
+        );
+        assert_eq!(parsed.source, Source::Cronometer);
+        let oats = &parsed.entries[0];
+        assert_eq!(oats.food_name, "Oats, rolled");
+        assert_eq!(oats.eaten_at, at(2026, 3, 3, 7, 45));
+        assert_eq!(oats.calories, Some(190.0));
+        assert_eq!(oats.proteins, Some(6.5));
+        assert_eq!(oats.micros.fiber, Some(5.0));
+    }
+
+    #[test]
+    fn other_export_with_semicolons_and_local_time() {
+        let content = "Дата;Время;Название;Калории\n04.03.2026;18:30;Гречка;250,5\n";
+        let parsed = parse_csv(content, chrono_tz::Europe::Moscow, now(), "ru").unwrap();
+        assert_eq!(parsed.source, Source::Other);
+        let buckwheat = &parsed.entries[0];
+        assert_eq!(buckwheat.food_name, "Гречка");
+        assert_eq!(buckwheat.calories, Some(250.5));
+        assert_eq!(buckwheat.eaten_at, at(2026, 3, 4, 15, 30));
+    }
+
+    #[test]
+    fn unknown_header() {
+        assert!(matches!(
+            parse_csv("Food,Weight\nApple,150\n", Tz::UTC, now(), "en"),
+            Err(ImportError::UnknownFormat)
+        ));
+    }
+
+    #[test]
+    fn malformed_rows_are_skipped() {
+        let parsed = parse(
+            "Date,Food,Calories\n\
+             2026-03-05,Apple,80\n\
+             ,Pear,60\n\
+             yesterday,Plum,30\n\
+             2026-03-05,Bread,\n\
+             2026-04-01,Cake,400\n\
+             ,,\n\
+             2026-03-06,Kefir,1 200\n",
+        );
+        let names: Vec<_> = parsed.entries.iter().map(|e| e.food_name.as_str()).collect();
+        assert_eq!(names, ["Apple", "Kefir"]);
+        assert_eq!(parsed.entries[1].calories, Some(1200.0));
+        let lines: Vec<_> = parsed.skipped.iter().map(|(line, _)| *line).collect();
+        assert_eq!(lines, [3, 4, 5, 6]);
+        assert_eq!(parsed.skipped[1].1, "invalid date 'yesterday'");
+        assert!(parsed.skipped[3].1.starts_with("date in the future"));
+    }
+
+    #[test]
+    fn too_many_rows() {
+        let row = "2026-03-01,Apple,80\n";
+        let limit = format!("Date,Food,Calories\n{}", row.repeat(MAX_ROWS));
+        assert_eq!(parse(&limit).entries.len(), MAX_ROWS);
+        let over = format!("{}{}", limit, row);
+        assert!(matches!(
+            parse_csv(&over, Tz::UTC, now(), "en"),
+            Err(ImportError::TooManyRows)
+        ));
+    }
+
+    #[test]
+    fn removes_entries_already_in_diary() {
+        let mut parsed = parse(
+            "Date,Time,Food,Calories\n\
+             2026-03-07,12:00,Apple,80\n\
+             2026-03-07,12:00,Apple,80\n\
+             2026-03-07,12:00,Pear,60\n\
+             2026-03-07,13:00,Apple,80\n",
+        );
+        let existing = vec![
+            (at(2026, 3, 7, 12, 0) + chrono::Duration::seconds(30), " apple ".to_string(), Some(80.4)),
+            (at(2026, 3, 7, 12, 0), "Pear".to_string(), Some(75.0)),
+        ];
+        assert_eq!(parsed.remove_existing(&existing), 1);
+        let kept: Vec<_> = parsed
+            .entries
+            .iter()
+            .map(|e| (e.food_name.as_str(), e.eaten_at))
+            .collect();
+        assert_eq!(
+            kept,
+            [
+                ("Apple", at(2026, 3, 7, 12, 0)),
+                ("Pear", at(2026, 3, 7, 12, 0)),
+                ("Apple", at(2026, 3, 7, 13, 0)),
+            ]
+        );
+    }
+}
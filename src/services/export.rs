@@ -0,0 +1,223 @@
+use crate::db::models::{ActivityLog, FoodLog, WeightLog};
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+use std::error::Error;
+
+/// Метка порядка байтов: без неё Excel открывает UTF-8 CSV с кириллицей как кракозябры.
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "csv" => Some(ExportFormat::Csv),
+            "json" => Some(ExportFormat::Json),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+/// Дневник пользователя за период; даты и время — в его часовом поясе.
+#[derive(Debug, Serialize)]
+pub struct Diary {
+    pub timezone: String,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub food: Vec<FoodEntry>,
+    pub weights: Vec<WeightEntry>,
+    pub activities: Vec<ActivityEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FoodEntry {
+    pub date: String,
+    pub time: String,
+    pub meal_type: String,
+    pub name: String,
+    pub calories: Option<f32>,
+    pub proteins: Option<f32>,
+    pub fats: Option<f32>,
+    pub carbs: Option<f32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WeightEntry {
+    pub date: String,
+    pub time: String,
+    pub weight_kg: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ActivityEntry {
+    pub date: String,
+    pub time: String,
+    pub activity: String,
+    pub description: String,
+    pub duration_min: f32,
+    pub distance_km: Option<f32>,
+    pub calories_burned: f32,
+}
+
+/// Строка общего CSV: запись о еде, взвешивание или тренировка.
+#[derive(Serialize)]
+struct CsvRow<'a> {
+    date: &'a str,
+    time: &'a str,
+    record: &'static str,
+    meal_type: Option<&'a str>,
+    activity: Option<&'a str>,
+    name: &'a str,
+    calories: Option<f32>,
+    proteins: Option<f32>,
+    fats: Option<f32>,
+    carbs: Option<f32>,
+    weight_kg: Option<f64>,
+    duration_min: Option<f32>,
+    distance_km: Option<f32>,
+    calories_burned: Option<f32>,
+}
+
+impl Diary {
+    pub fn new(
+        tz: Tz,
+        range: Option<(NaiveDate, NaiveDate)>,
+        foods: &[FoodLog],
+        weights: &[WeightLog],
+        activities: &[ActivityLog],
+    ) -> Self {
+        Diary {
+            timezone: tz.name().to_string(),
+            from: range.map(|(from, _)| from),
+            to: range.map(|(_, to)| to),
+            food: foods
+                .iter()
+                .map(|log| {
+                    let (date, time) = local(log.created_at, tz);
+                    FoodEntry {
+                        date,
+                        time,
+                        meal_type: log.meal_type.clone(),
+                        name: log.food_name.clone(),
+                        calories: log.calories,
+                        proteins: log.proteins,
+                        fats: log.fats,
+                        carbs: log.carbs,
+                    }
+                })
+                .collect(),
+            weights: weights
+                .iter()
+                .map(|log| {
+                    let (date, time) = local(log.created_at, tz);
+                    WeightEntry {
+                        date,
+                        time,
+                        weight_kg: log.weight_kg,
+                    }
+                })
+                .collect(),
+            activities: activities
+                .iter()
+                .map(|log| {
+                    let (date, time) = local(log.created_at, tz);
+                    ActivityEntry {
+                        date,
+                        time,
+                        activity: log.activity.clone(),
+                        description: log.description.clone(),
+                        duration_min: log.duration_min,
+                        distance_km: log.distance_km,
+                        calories_burned: log.calories,
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.food.is_empty() && self.weights.is_empty() && self.activities.is_empty()
+    }
+
+    pub fn to_bytes(&self, format: ExportFormat) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+        match format {
+            ExportFormat::Csv => self.to_csv(),
+            ExportFormat::Json => Ok(serde_json::to_vec_pretty(self)?),
+        }
+    }
+
+    /// Один CSV на все записи, отсортированные по времени; столбец `record` — тип записи.
+    fn to_csv(&self) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+        let empty = CsvRow {
+            date: "",
+            time: "",
+            record: "",
+            meal_type: None,
+            activity: None,
+            name: "",
+            calories: None,
+            proteins: None,
+            fats: None,
+            carbs: None,
+            weight_kg: None,
+            duration_min: None,
+            distance_km: None,
+            calories_burned: None,
+        };
+        let mut rows: Vec<CsvRow> = Vec::new();
+        rows.extend(self.food.iter().map(|e| CsvRow {
+            date: &e.date,
+            time: &e.time,
+            record: "food",
+            meal_type: Some(&e.meal_type),
+            name: &e.name,
+            calories: e.calories,
+            proteins: e.proteins,
+            fats: e.fats,
+            carbs: e.carbs,
+            ..empty
+        }));
+        rows.extend(self.weights.iter().map(|e| CsvRow {
+            date: &e.date,
+            time: &e.time,
+            record: "weight",
+            weight_kg: Some(e.weight_kg),
+            ..empty
+        }));
+        rows.extend(self.activities.iter().map(|e| CsvRow {
+            date: &e.date,
+            time: &e.time,
+            record: "activity",
+            activity: Some(&e.activity),
+            name: &e.description,
+            duration_min: Some(e.duration_min),
+            distance_km: e.distance_km,
+            calories_burned: Some(e.calories_burned),
+            ..empty
+        }));
+        rows.sort_by(|a, b| (a.date, a.time).cmp(&(b.date, b.time)));
+
+        let mut writer = csv::Writer::from_writer(UTF8_BOM.to_vec());
+        for row in &rows {
+            writer.serialize(row)?;
+        }
+        Ok(writer.into_inner()?)
+    }
+}
+
+fn local(at: DateTime<Utc>, tz: Tz) -> (String, String) {
+    let local = at.with_timezone(&tz);
+    (local.format("%Y-%m-%d").to_string(), local.format("%H:%M").to_string())
+}
@@ -0,0 +1,99 @@
+use crate::db::models::Fast;
+use chrono::{Duration, NaiveDate};
+use chrono_tz::Tz;
+use std::collections::HashSet;
+
+/// Протокол по умолчанию, если пользователь его не выбирал.
+pub const DEFAULT_PROTOCOL: &str = "16:8";
+
+/// Самое длинное голодание, которое можно задать протоколом, часов.
+const MAX_TARGET_HOURS: f32 = 72.0;
+
+/// Протокол голодания: название (`16:8`, `omad`, `36h`) и целевая длительность.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Protocol {
+    pub name: String,
+    pub target_hours: f32,
+}
+
+impl Protocol {
+    /// Понимает `16:8`, `16/8`, `omad` (23:1) и длительность в часах — `18`, `36h`.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim().to_lowercase();
+        if text == "omad" {
+            return Some(Protocol {
+                name: "omad".into(),
+                target_hours: 23.0,
+            });
+        }
+
+        if let Some((fast, eat)) = text.split_once([':', '/']) {
+            let fast: u32 = fast.trim().parse().ok()?;
+            let eat: u32 = eat.trim().parse().ok()?;
+            if fast == 0 || fast + eat != 24 {
+                return None;
+            }
+            return Some(Protocol {
+                name: format!("{}:{}", fast, eat),
+                target_hours: fast as f32,
+            });
+        }
+
+        let hours = text
+            .trim_end_matches(|c: char| c.is_alphabetic() || c.is_whitespace())
+            .replace(',', ".")
+            .parse::<f32>()
+            .ok()
+            .filter(|h| (1.0..=MAX_TARGET_HOURS).contains(h))?;
+        Some(Protocol {
+            name: format!("{}h", hours),
+            target_hours: hours,
+        })
+    }
+}
+
+/// Целевая длительность голодания.
+pub fn target(fast: &Fast) -> Duration {
+    Duration::minutes((fast.target_hours * 60.0).round() as i64)
+}
+
+/// Голодание завершено и продлилось не меньше цели.
+pub fn is_completed(fast: &Fast) -> bool {
+    fast.ended_at
+        .is_some_and(|ended_at| ended_at - fast.started_at >= target(fast))
+}
+
+/// Серия дней подряд, в каждый из которых завершилось успешное голодание.
+/// Если сегодня голодание ещё не завершено, серия считается со вчерашнего дня.
+/// Дни считаются в часовом поясе пользователя `tz`, `today` — его местная дата.
+pub fn streak(fasts: &[Fast], today: NaiveDate, tz: Tz) -> u32 {
+    let days: HashSet<NaiveDate> = fasts
+        .iter()
+        .filter(|f| is_completed(f))
+        .filter_map(|f| f.ended_at.map(|e| e.with_timezone(&tz).date_naive()))
+        .collect();
+
+    let mut day = if days.contains(&today) {
+        today
+    } else {
+        today - Duration::days(1)
+    };
+    let mut streak = 0;
+    while days.contains(&day) {
+        streak += 1;
+        day -= Duration::days(1);
+    }
+    streak
+}
+
+/// Длительность вида «15 ч 20 мин».
+pub fn format_duration(duration: Duration, lang: &str) -> String {
+    let minutes = duration.num_minutes().max(0);
+    let (h, m) = match lang {
+        "ru" => ("ч", "мин"),
+        "th" => ("ชม.", "นาที"),
+        "zh" => ("小时", "分钟"),
+        _ => ("h", "min"),
+    };
+    format!("{} {} {} {}", minutes / 60, h, minutes % 60, m)
+}
@@ -0,0 +1,430 @@
+use crate::db::models::{Food, Micronutrients};
+use crate::db::queries;
+use crate::services::log_time::extract_time_hint;
+use crate::services::nutrition::{self, FoodSummary, NutritionError};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// Минимальная похожесть названия (Jaro–Winkler), при которой продукт считается найденным.
+pub(crate) const MATCH_THRESHOLD: f64 = 0.9;
+
+/// Как часто перечитывать базу продуктов (импорт выполняется отдельным процессом).
+const INDEX_TTL: Duration = Duration::from_secs(600);
+
+static QUANTITY_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(\d+(?:[.,]\d+)?)\s*(килограмм\w*|кг|kg|грамм\w*|гр|г|grams?|gr|g|миллилитр\w*|мл|ml|литр\w*|л|l)\b").unwrap()
+});
+
+static SEPARATOR_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\s*(?:[,;+\n]|\s+и\s+|\s+and\s+)\s*").unwrap()
+});
+
+/// Слова, которые не относятся к названию продукта («я съел вчера на ужин ...»).
+const STOPWORDS: &[&str] = &[
+    "я", "съел", "съела", "съели", "ел", "ела", "выпил", "выпила", "вчера", "сегодня", "позавчера",
+    "на", "в", "около", "завтрак", "обед", "ужин", "перекус", "i", "ate", "had", "have", "drank",
+    "for", "breakfast", "lunch", "dinner", "snack", "yesterday", "today", "of", "at", "some", "a",
+    "an", "the",
+];
+
+/// Окончания, которые отбрасываются при сравнении («гречки» → «гречк», «eggs» → «egg»).
+const ENDINGS: &[&str] = &[
+    "ами", "ями", "ого", "его", "ому", "ему", "ыми", "ими", "ой", "ей", "ий", "ый", "ая", "яя",
+    "ое", "ее", "ом", "ем", "ах", "ях", "ов", "ев", "ам", "ям", "ую", "юю", "es", "а", "я", "ы",
+    "и", "у", "ю", "е", "о", "s",
+];
+
+/// Индекс названий продуктов для нечёткого поиска.
+pub struct FoodIndex {
+    foods: HashMap<i32, Food>,
+    keys: Vec<(i32, String)>,
+}
+
+/// Загруженный индекс и момент загрузки.
+type CachedIndex = Option<(Instant, Arc<FoodIndex>)>;
+
+static FOOD_INDEX: Lazy<RwLock<CachedIndex>> = Lazy::new(|| RwLock::new(None));
+
+impl FoodIndex {
+    pub async fn load() -> Result<Self, sqlx::Error> {
+        let foods = queries::get_all_foods().await?;
+        let aliases = queries::get_all_food_aliases().await?;
+
+        let mut keys: Vec<(i32, String)> = foods.iter().map(|f| (f.id, match_key(&f.name))).collect();
+        keys.extend(aliases.iter().map(|a| (a.food_id, match_key(&a.alias))));
+        keys.retain(|(_, key)| !key.is_empty());
+
+        Ok(FoodIndex {
+            foods: foods.into_iter().map(|f| (f.id, f)).collect(),
+            keys,
+        })
+    }
+
+    /// Ищет продукт по названию на любом из языков базы.
+    pub fn find(&self, phrase: &str) -> Option<&Food> {
+        let key = match_key(phrase);
+        if key.is_empty() {
+            return None;
+        }
+
+        let (food_id, score) = self
+            .keys
+            .iter()
+            .map(|(id, candidate)| {
+                let score = if *candidate == key {
+                    1.0
+                } else {
+                    strsim::jaro_winkler(&key, candidate)
+                };
+                (*id, score)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))?;
+
+        if score >= MATCH_THRESHOLD {
+            self.foods.get(&food_id)
+        } else {
+            None
+        }
+    }
+}
+
+/// Возвращает индекс продуктов, перечитывая его из БД не чаще раза в `INDEX_TTL`.
+pub async fn food_index() -> Result<Arc<FoodIndex>, sqlx::Error> {
+    if let Some((loaded_at, index)) = FOOD_INDEX.read().unwrap().as_ref()
+        && loaded_at.elapsed() < INDEX_TTL
+    {
+        return Ok(index.clone());
+    }
+
+    let index = Arc::new(FoodIndex::load().await?);
+    *FOOD_INDEX.write().unwrap() = Some((Instant::now(), index.clone()));
+    Ok(index)
+}
+
+/// Ключ для сравнения названий: нижний регистр, без знаков препинания и окончаний.
+pub(crate) fn match_key(text: &str) -> String {
+    normalize(text)
+        .split_whitespace()
+        .map(stem)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .replace('ё', "е")
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn stem(word: &str) -> &str {
+    if word.chars().count() <= 4 {
+        return word;
+    }
+    ENDINGS
+        .iter()
+        .find_map(|ending| word.strip_suffix(ending))
+        .unwrap_or(word)
+}
+
+/// Делит описание на позиции по запятым, «;», «+», переводам строк и союзам «и»/«and».
+/// Запятая между цифрами — десятичная («1,5 кг»), по ней не делим.
+pub(crate) fn split_segments(text: &str) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    for separator in SEPARATOR_RE.find_iter(text) {
+        let is_decimal_comma = separator.as_str() == ","
+            && text[..separator.start()].ends_with(|c: char| c.is_ascii_digit())
+            && text[separator.end()..].starts_with(|c: char| c.is_ascii_digit());
+        if is_decimal_comma {
+            continue;
+        }
+        segments.push(&text[start..separator.start()]);
+        start = separator.end();
+    }
+    segments.push(&text[start..]);
+    segments
+}
+
+/// Позиция описания: вес в граммах и название продукта без служебных слов.
+#[derive(Debug)]
+struct Portion {
+    grams: f32,
+    name: String,
+}
+
+/// Выделяет из фрагмента «200 г гречки» вес и название.
+fn parse_portion(segment: &str) -> Option<Portion> {
+    let (grams, rest) = extract_grams(segment)?;
+    let name = strip_stopwords(&rest);
+    if name.is_empty() {
+        return None;
+    }
+
+    Some(Portion { grams, name })
+}
+
+/// Находит во фрагменте вес («200 г», «1,5 кг») и возвращает его в граммах вместе
+/// с остатком текста.
+pub(crate) fn extract_grams(segment: &str) -> Option<(f32, String)> {
+    let cap = QUANTITY_RE.captures(segment)?;
+    let amount: f32 = cap.get(1)?.as_str().replace(',', ".").parse().ok()?;
+    let unit = cap.get(2)?.as_str().to_lowercase();
+    // Килограммы и литры переводим в граммы (плотность жидкостей считаем равной 1).
+    let is_kilo = unit.starts_with("кг") || unit.starts_with("kg") || unit.starts_with("килограмм");
+    let is_litre = unit == "л" || unit == "l" || unit.starts_with("литр");
+    let factor = if is_kilo || is_litre { 1000.0 } else { 1.0 };
+
+    Some((amount * factor, QUANTITY_RE.replace(segment, " ").into_owned()))
+}
+
+pub(crate) fn strip_stopwords(text: &str) -> String {
+    normalize(text)
+        .split_whitespace()
+        .filter(|w| !STOPWORDS.contains(w) && !w.chars().any(|c| c.is_ascii_digit()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Считает КБЖУ описания по локальной базе и обращается к модели только за
+/// нераспознанными позициями. Если локально не найдено ничего, работает как
+/// `nutrition::analyze_food_description`.
+pub async fn resolve_food_description(
+    text: &str,
+    lang: &str,
+) -> Result<(FoodSummary, String), NutritionError> {
+    let index = match food_index().await {
+        Ok(index) => index,
+        Err(e) => {
+            log::warn!("Food index unavailable, falling back to AI: {}", e);
+            return nutrition::analyze_food_description(text, lang).await;
+        }
+    };
+
+    let mut lines = Vec::new();
+    let mut totals = [0.0f32; 4];
+    let mut unknown = Vec::new();
+
+    for segment in split_segments(text) {
+        let found = parse_portion(segment).and_then(|p| index.find(&p.name).map(|f| (p, f)));
+        match found {
+            Some((portion, food)) => {
+                let k = portion.grams / 100.0;
+                let values = [food.calories * k, food.proteins * k, food.fats * k, food.carbs * k];
+                for (total, value) in totals.iter_mut().zip(values) {
+                    *total += value;
+                }
+                lines.push(format!(
+                    "📗 {} — {:.0} g: {:.0} kcal | 🥩 {:.1}P / 🧈 {:.1}F / 🍞 {:.1}C",
+                    food.name, portion.grams, values[0], values[1], values[2], values[3]
+                ));
+            }
+            None if !strip_stopwords(segment).is_empty() => unknown.push(segment.trim()),
+            None => {}
+        }
+    }
+
+    if lines.is_empty() {
+        return nutrition::analyze_food_description(text, lang).await;
+    }
+
+    let mut summary = FoodSummary {
+        name: text.to_string(),
+        calories: Some(totals[0]),
+        proteins: Some(totals[1]),
+        fats: Some(totals[2]),
+        carbs: Some(totals[3]),
+        time_hint: extract_time_hint(text),
+        micros: Micronutrients::default(),
+        model: None,
+    };
+
+    if !unknown.is_empty() {
+        let (ai_summary, ai_text) =
+            nutrition::analyze_food_description(&unknown.join(", "), lang).await?;
+        summary.calories = Some(totals[0] + ai_summary.calories.unwrap_or(0.0));
+        summary.proteins = Some(totals[1] + ai_summary.proteins.unwrap_or(0.0));
+        summary.fats = Some(totals[2] + ai_summary.fats.unwrap_or(0.0));
+        summary.carbs = Some(totals[3] + ai_summary.carbs.unwrap_or(0.0));
+        // Для продуктов из локальной базы микронутриентов нет — берём оценку модели.
+        summary.micros = ai_summary.micros;
+        summary.model = ai_summary.model;
+        lines.push(ai_text);
+    }
+
+    Ok((summary, lines.join("\n")))
+}
+
+/// Сколько пропущенных строк импорта сохраняется в отчёте как примеры.
+const MAX_SKIPPED_EXAMPLES: usize = 20;
+
+/// Результат импорта CSV.
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub imported: usize,
+    /// Число пропущенных строк по причинам.
+    pub skipped: BTreeMap<&'static str, usize>,
+    /// Первые пропущенные строки: номер, причина и подробности.
+    pub examples: Vec<(usize, &'static str, String)>,
+}
+
+impl ImportReport {
+    /// Учитывает пропущенную строку; подробности сохраняются только для первых строк,
+    /// чтобы отчёт по многомиллионной выгрузке не рос без ограничений.
+    pub fn skip(&mut self, line: usize, reason: &'static str, detail: impl Into<String>) {
+        *self.skipped.entry(reason).or_default() += 1;
+        if self.examples.len() < MAX_SKIPPED_EXAMPLES {
+            self.examples.push((line, reason, detail.into()));
+        }
+    }
+
+    pub fn skipped_total(&self) -> usize {
+        self.skipped.values().sum()
+    }
+}
+
+/// Колонки CSV, найденные по заголовку.
+struct Columns {
+    name: usize,
+    calories: usize,
+    proteins: Option<usize>,
+    fats: Option<usize>,
+    carbs: Option<usize>,
+    external_id: Option<usize>,
+    lang: Option<usize>,
+    aliases: Option<usize>,
+}
+
+impl Columns {
+    /// Сопоставляет заголовки распространённых таблиц (USDA, национальные таблицы
+    /// состава продуктов, собственный формат `name,calories,proteins,fats,carbs,lang,aliases`).
+    fn detect(headers: &csv::StringRecord) -> Option<Self> {
+        let headers: Vec<String> = headers.iter().map(|h| h.trim().to_lowercase()).collect();
+        let find = |exact: &[&str], contains: &[&str]| {
+            headers
+                .iter()
+                .position(|h| exact.contains(&h.as_str()))
+                .or_else(|| headers.iter().position(|h| contains.iter().any(|c| h.contains(c))))
+        };
+
+        Some(Columns {
+            name: find(&["name", "description", "food", "название", "продукт"], &["name", "назван", "descr"])?,
+            calories: find(&["calories", "kcal", "energy", "ккал", "калории"], &["kcal", "calor", "energy", "ккал", "калор", "энерг"])?,
+            proteins: find(&["proteins", "protein", "белки"], &["protein", "белк"]),
+            fats: find(&["fats", "fat", "total_fat", "total lipid (fat)", "жиры"], &["lipid", "fat", "жир"]),
+            carbs: find(&["carbs", "carbohydrates", "carbohydrate", "углеводы"], &["carb", "углев"]),
+            external_id: find(&["id", "fdc_id", "code", "ndb_no", "код"], &[]),
+            lang: find(&["lang", "language", "язык"], &[]),
+            aliases: find(&["aliases", "synonyms", "синонимы"], &[]),
+        })
+    }
+}
+
+/// Импортирует таблицу состава продуктов (значения на 100 г). Разделитель (`,` или `;`)
+/// определяется по заголовку; повторный импорт обновляет КБЖУ.
+pub async fn import_csv(
+    path: &Path,
+    source: &str,
+) -> Result<ImportReport, Box<dyn std::error::Error + Send + Sync>> {
+    let content = std::fs::read_to_string(path)?;
+    let header_line = content.lines().next().unwrap_or_default();
+    let delimiter = if header_line.matches(';').count() > header_line.matches(',').count() {
+        b';'
+    } else {
+        b','
+    };
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_reader(content.as_bytes());
+    let columns = Columns::detect(reader.headers()?)
+        .ok_or("CSV must contain name and calories columns")?;
+
+    let mut report = ImportReport::default();
+    for (i, record) in reader.records().enumerate() {
+        let line = i + 2;
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                report.skip(line, "invalid row", e.to_string());
+                continue;
+            }
+        };
+
+        let field = |idx: Option<usize>| idx.and_then(|i| record.get(i)).map(str::trim).filter(|v| !v.is_empty());
+        let number = |idx: Option<usize>| field(idx).and_then(|v| v.replace(',', ".").parse::<f32>().ok());
+
+        let Some(name) = field(Some(columns.name)) else {
+            report.skip(line, "empty name", "");
+            continue;
+        };
+        let Some(calories) = number(Some(columns.calories)) else {
+            report.skip(line, "invalid calories", name);
+            continue;
+        };
+        let per_100g = (
+            calories,
+            number(columns.proteins).unwrap_or(0.0),
+            number(columns.fats).unwrap_or(0.0),
+            number(columns.carbs).unwrap_or(0.0),
+        );
+
+        let food_id = queries::upsert_food(source, field(columns.external_id), name, per_100g).await?;
+        if let Some(aliases) = field(columns.aliases) {
+            let lang = field(columns.lang);
+            for alias in aliases.split('|').map(str::trim).filter(|a| !a.is_empty()) {
+                queries::add_food_alias(food_id, lang, alias).await?;
+            }
+        }
+        report.imported += 1;
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimal_comma_is_not_a_separator() {
+        assert_eq!(split_segments("1,5 кг картошки"), vec!["1,5 кг картошки"]);
+        assert_eq!(split_segments("0,5 л кефира, 200 г гречки"), vec!["0,5 л кефира", "200 г гречки"]);
+    }
+
+    #[test]
+    fn splits_on_list_separators() {
+        assert_eq!(
+            split_segments("200 г гречки и 2 яйца; 100 g rice + 1 apple\nчай"),
+            vec!["200 г гречки", "2 яйца", "100 g rice", "1 apple", "чай"]
+        );
+        assert_eq!(split_segments("1, 5 кг"), vec!["1", "5 кг"]);
+    }
+
+    #[test]
+    fn extracts_grams_with_decimal_comma() {
+        let (grams, _) = extract_grams("1,5 кг картошки").unwrap();
+        assert_eq!(grams, 1500.0);
+        let (grams, _) = extract_grams("0,5 л кефира").unwrap();
+        assert_eq!(grams, 500.0);
+        let (grams, _) = extract_grams("2.5 kg of potatoes").unwrap();
+        assert_eq!(grams, 2500.0);
+    }
+
+    #[test]
+    fn parses_portion_name_without_stopwords() {
+        let portion = parse_portion("я съел 200 г гречки").unwrap();
+        assert_eq!(portion.grams, 200.0);
+        assert_eq!(portion.name, "гречки");
+        assert!(parse_portion("гречка").is_none());
+    }
+}
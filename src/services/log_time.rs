@@ -0,0 +1,257 @@
+use crate::services::meal::MealType;
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Максимальная глубина записи задним числом по умолчанию (в днях).
+const DEFAULT_MAX_BACKDATE_DAYS: i64 = 7;
+
+/// Допустимое опережение текущего времени (часы у пользователя могут спешить).
+const FUTURE_TOLERANCE_MINUTES: i64 = 5;
+
+/// «в 19:30», «at 8pm», «в 8 утра». Час без минут засчитывается только с am/pm или словом
+/// вроде «утра»/«часов» — иначе «кефир в 2.5%» стал бы временем 02:00.
+static TIME_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)(?:\bв|\bat|\baround|\bоколо)\s+(\d{1,2})(?:[:.](\d{2}))?(?:\s*(am|pm|o'clock|утра|вечера|дня|ночи|час\w*|ч)\b)?",
+    )
+    .unwrap()
+});
+
+/// Подсказка о времени приёма пищи, извлечённая из текста или команды.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TimeHint {
+    pub date: Option<NaiveDate>,
+    pub days_ago: Option<i64>,
+    pub time: Option<NaiveTime>,
+    pub meal: Option<MealType>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackdateError {
+    InFuture,
+    TooOld(i64),
+}
+
+impl TimeHint {
+    pub fn is_empty(&self) -> bool {
+        self.date.is_none() && self.days_ago.is_none() && self.time.is_none() && self.meal.is_none()
+    }
+
+    /// Дополняет подсказку недостающими полями из `other`.
+    pub fn or(self, other: TimeHint) -> TimeHint {
+        TimeHint {
+            date: self.date.or(other.date),
+            days_ago: self.days_ago.or(other.days_ago),
+            time: self.time.or(other.time),
+            meal: self.meal.or(other.meal),
+        }
+    }
+
+    /// Переводит подсказку в момент времени (UTC) и приём пищи. Время без дня, которое
+    /// сегодня ещё не наступило («ужин в 23:30», отправленный в 00:10), относится ко вчера.
+    pub fn resolve(&self, now: DateTime<Utc>, tz: Tz) -> (DateTime<Utc>, MealType) {
+        let local_now = now.with_timezone(&tz);
+        let date = self
+            .date
+            .unwrap_or_else(|| local_now.date_naive() - Duration::days(self.days_ago.unwrap_or(0)));
+        let is_other_day = date != local_now.date_naive();
+
+        let time = match (self.time, self.meal) {
+            (Some(time), _) => time,
+            (None, Some(meal)) if is_other_day => meal.default_time(),
+            _ => local_now.time(),
+        };
+        let meal = self.meal.unwrap_or_else(|| MealType::from_local_time(time));
+
+        let mut eaten_at = local_to_utc(date, time, tz).unwrap_or(now);
+        if self.date.is_none()
+            && self.days_ago.is_none()
+            && eaten_at > now + Duration::minutes(FUTURE_TOLERANCE_MINUTES)
+        {
+            eaten_at = local_to_utc(date - Duration::days(1), time, tz).unwrap_or(now);
+        }
+        (eaten_at, meal)
+    }
+}
+
+/// Переводит локальные дату и время пользователя в UTC.
+pub fn local_to_utc(date: NaiveDate, time: NaiveTime, tz: Tz) -> Option<DateTime<Utc>> {
+    tz.from_local_datetime(&date.and_time(time))
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Ищет в описании еды упоминания дня, времени и приёма пищи
+/// («вчера на ужин», «yesterday at 8pm for lunch»).
+pub fn extract_time_hint(text: &str) -> TimeHint {
+    let lower = text.to_lowercase();
+
+    let days_ago = if lower.contains("позавчера") || lower.contains("day before yesterday") {
+        Some(2)
+    } else if lower.contains("вчера") || lower.contains("yesterday") || lower.contains("last night") {
+        Some(1)
+    } else {
+        None
+    };
+
+    let meal = if lower.contains("завтрак") || lower.contains("breakfast") {
+        Some(MealType::Breakfast)
+    } else if lower.contains("обед") || lower.contains("lunch") {
+        Some(MealType::Lunch)
+    } else if lower.contains("ужин") || lower.contains("dinner") || lower.contains("last night") {
+        Some(MealType::Dinner)
+    } else if lower.contains("перекус") || lower.contains("snack") {
+        Some(MealType::Snack)
+    } else {
+        None
+    };
+
+    let time = TIME_RE.captures_iter(&lower).find_map(|cap| {
+        // Число продолжается («в 2.5%», «at 3,2 km») — это не время.
+        let rest = &lower[cap.get(0)?.end()..];
+        let mut next = rest.chars();
+        match (next.next(), next.next()) {
+            (Some('%'), _) => return None,
+            (Some(c), _) if c.is_ascii_digit() => return None,
+            (Some('.' | ','), Some(c)) if c.is_ascii_digit() => return None,
+            _ => {}
+        }
+
+        let suffix = cap.get(3).map(|m| m.as_str());
+        if cap.get(2).is_none() && suffix.is_none() {
+            return None;
+        }
+        let mut hour: u32 = cap.get(1)?.as_str().parse().ok()?;
+        let minute: u32 = cap.get(2).map_or(Some(0), |m| m.as_str().parse().ok())?;
+        match suffix {
+            Some("pm" | "вечера" | "дня") if hour < 12 => hour += 12,
+            Some("am" | "ночи") if hour == 12 => hour = 0,
+            _ => {}
+        }
+        NaiveTime::from_hms_opt(hour, minute, 0)
+    });
+
+    TimeHint {
+        date: None,
+        days_ago,
+        time,
+        meal,
+    }
+}
+
+/// Разбирает дату/время, введённые вручную: `2026-10-18 19:30`, `2026-10-18`, `19:30`.
+pub fn parse_datetime_input(text: &str) -> Option<TimeHint> {
+    let mut hint = TimeHint::default();
+
+    for token in text.split_whitespace() {
+        if let Ok(date) = NaiveDate::parse_from_str(token, "%Y-%m-%d") {
+            hint.date = Some(date);
+        } else if let Ok(date) = NaiveDate::parse_from_str(token, "%d.%m.%Y") {
+            hint.date = Some(date);
+        } else if let Ok(time) = NaiveTime::parse_from_str(token, "%H:%M") {
+            hint.time = Some(time);
+        } else {
+            return None;
+        }
+    }
+
+    if hint.is_empty() { None } else { Some(hint) }
+}
+
+/// Глубина записи задним числом из `MAX_BACKDATE_DAYS` (по умолчанию 7 дней).
+pub fn max_backdate_days() -> i64 {
+    std::env::var("MAX_BACKDATE_DAYS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_MAX_BACKDATE_DAYS)
+}
+
+/// Проверяет, что время записи не в будущем и не старше допустимого окна.
+pub fn validate(eaten_at: DateTime<Utc>, now: DateTime<Utc>) -> Result<(), BackdateError> {
+    if eaten_at > now + Duration::minutes(FUTURE_TOLERANCE_MINUTES) {
+        return Err(BackdateError::InFuture);
+    }
+
+    let max_days = max_backdate_days();
+    if eaten_at < now - Duration::days(max_days) {
+        return Err(BackdateError::TooOld(max_days));
+    }
+
+    Ok(())
+}
+
+impl BackdateError {
+    pub fn message(&self, lang: &str) -> String {
+        match (self, lang) {
+            (BackdateError::InFuture, "ru") => "⏳ Нельзя записать еду в будущем.".to_string(),
+            (BackdateError::InFuture, "th") => "⏳ ไม่สามารถบันทึกอาหารในอนาคตได้".to_string(),
+            (BackdateError::InFuture, "zh") => "⏳ 不能记录未来的饮食。".to_string(),
+            (BackdateError::InFuture, _) => "⏳ You can't log food in the future.".to_string(),
+            (BackdateError::TooOld(days), "ru") => {
+                format!("⏳ Записывать задним числом можно не более чем за {} дн.", days)
+            }
+            (BackdateError::TooOld(days), "th") => {
+                format!("⏳ บันทึกย้อนหลังได้ไม่เกิน {} วัน", days)
+            }
+            (BackdateError::TooOld(days), "zh") => format!("⏳ 最多只能补记 {} 天内的饮食。", days),
+            (BackdateError::TooOld(days), _) => {
+                format!("⏳ Entries can be backdated by at most {} days.", days)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(text: &str) -> Option<NaiveTime> {
+        extract_time_hint(text).time
+    }
+
+    #[test]
+    fn recognizes_times() {
+        assert_eq!(time("ужин в 19:30"), NaiveTime::from_hms_opt(19, 30, 0));
+        assert_eq!(time("завтрак в 8.15"), NaiveTime::from_hms_opt(8, 15, 0));
+        assert_eq!(time("pizza at 8pm"), NaiveTime::from_hms_opt(20, 0, 0));
+        assert_eq!(time("каша в 8 утра"), NaiveTime::from_hms_opt(8, 0, 0));
+        assert_eq!(time("суп в 2 дня"), NaiveTime::from_hms_opt(14, 0, 0));
+        assert_eq!(time("чай около 7 часов"), NaiveTime::from_hms_opt(7, 0, 0));
+    }
+
+    #[test]
+    fn future_time_without_day_means_yesterday() {
+        let tz = chrono_tz::Europe::Moscow;
+        // 00:10 по Москве 20 октября.
+        let now = Utc.with_ymd_and_hms(2026, 10, 19, 21, 10, 0).unwrap();
+        let at = |date: Option<NaiveDate>, days_ago: Option<i64>, h: u32, m: u32| {
+            TimeHint {
+                date,
+                days_ago,
+                time: NaiveTime::from_hms_opt(h, m, 0),
+                meal: None,
+            }
+            .resolve(now, tz)
+            .0
+        };
+
+        assert_eq!(at(None, None, 23, 30), Utc.with_ymd_and_hms(2026, 10, 19, 20, 30, 0).unwrap());
+        assert_eq!(at(None, None, 0, 5), Utc.with_ymd_and_hms(2026, 10, 19, 21, 5, 0).unwrap());
+        // В пределах допуска время остаётся сегодняшним.
+        assert_eq!(at(None, None, 0, 14), Utc.with_ymd_and_hms(2026, 10, 19, 21, 14, 0).unwrap());
+        assert_eq!(at(None, Some(0), 23, 30), Utc.with_ymd_and_hms(2026, 10, 20, 20, 30, 0).unwrap());
+        let today = NaiveDate::from_ymd_opt(2026, 10, 20);
+        assert_eq!(at(today, None, 23, 30), Utc.with_ymd_and_hms(2026, 10, 20, 20, 30, 0).unwrap());
+    }
+
+    #[test]
+    fn numbers_that_are_not_times() {
+        assert_eq!(time("кефир в 2.5%"), None);
+        assert_eq!(time("молоко в 3,2%"), None);
+        assert_eq!(time("творог в 5 %"), None);
+        assert_eq!(time("at 3 restaurants"), None);
+        assert_eq!(time("в 2 пачках"), None);
+    }
+}
@@ -0,0 +1,101 @@
+use chrono::{NaiveTime, Timelike};
+
+/// Тип приёма пищи. В базе хранится в виде строки (`breakfast`, `lunch`, ...).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MealType {
+    Breakfast,
+    Lunch,
+    Dinner,
+    Snack,
+}
+
+impl MealType {
+    pub const ALL: [MealType; 4] = [
+        MealType::Breakfast,
+        MealType::Lunch,
+        MealType::Dinner,
+        MealType::Snack,
+    ];
+
+    /// Определяет приём пищи по локальному времени пользователя.
+    pub fn from_local_time(time: NaiveTime) -> Self {
+        match time.hour() {
+            5..=10 => MealType::Breakfast,
+            11..=15 => MealType::Lunch,
+            17..=21 => MealType::Dinner,
+            _ => MealType::Snack,
+        }
+    }
+
+    /// Типичное время приёма пищи — для записей задним числом без точного времени.
+    pub fn default_time(&self) -> NaiveTime {
+        let hour = match self {
+            MealType::Breakfast => 8,
+            MealType::Lunch => 13,
+            MealType::Dinner => 19,
+            MealType::Snack => 16,
+        };
+        NaiveTime::from_hms_opt(hour, 0, 0).unwrap()
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "breakfast" => Some(MealType::Breakfast),
+            "lunch" => Some(MealType::Lunch),
+            "dinner" => Some(MealType::Dinner),
+            "snack" => Some(MealType::Snack),
+            _ => None,
+        }
+    }
+
+    /// Понимает ключ (`lunch`) или название на любом из языков бота («обед», «午餐»).
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase();
+        Self::parse(&name).or_else(|| {
+            Self::ALL.into_iter().find(|meal| {
+                ["ru", "th", "zh", "en"]
+                    .iter()
+                    .any(|lang| meal.label(lang).to_lowercase() == name)
+            })
+        })
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MealType::Breakfast => "breakfast",
+            MealType::Lunch => "lunch",
+            MealType::Dinner => "dinner",
+            MealType::Snack => "snack",
+        }
+    }
+
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            MealType::Breakfast => "🍳",
+            MealType::Lunch => "🍲",
+            MealType::Dinner => "🍝",
+            MealType::Snack => "🍏",
+        }
+    }
+
+    pub fn label(&self, lang: &str) -> &'static str {
+        match (self, lang) {
+            (MealType::Breakfast, "ru") => "Завтрак",
+            (MealType::Lunch, "ru") => "Обед",
+            (MealType::Dinner, "ru") => "Ужин",
+            (MealType::Snack, "ru") => "Перекус",
+            (MealType::Breakfast, "th") => "มื้อเช้า",
+            (MealType::Lunch, "th") => "มื้อกลางวัน",
+            (MealType::Dinner, "th") => "มื้อเย็น",
+            (MealType::Snack, "th") => "ของว่าง",
+            (MealType::Breakfast, "zh") => "早餐",
+            (MealType::Lunch, "zh") => "午餐",
+            (MealType::Dinner, "zh") => "晚餐",
+            (MealType::Snack, "zh") => "加餐",
+            (MealType::Breakfast, _) => "Breakfast",
+            (MealType::Lunch, _) => "Lunch",
+            (MealType::Dinner, _) => "Dinner",
+            (MealType::Snack, _) => "Snack",
+        }
+    }
+}
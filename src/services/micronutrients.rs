@@ -0,0 +1,193 @@
+use crate::db::models::Micronutrients;
+
+/// Пол из профиля для выбора норм потребления.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sex {
+    Male,
+    Female,
+}
+
+impl Sex {
+    /// Понимает значения вроде `male`, `m`, `мужской`, `female`, `ж`.
+    pub fn parse(gender: &str) -> Option<Self> {
+        let gender = gender.trim().to_lowercase();
+        if gender.starts_with('m') || gender.starts_with('м') {
+            Some(Sex::Male)
+        } else if gender.starts_with('f') || gender.starts_with('w') || gender.starts_with('ж') {
+            Some(Sex::Female)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Nutrient {
+    Fiber,
+    Sugar,
+    SaturatedFat,
+    Sodium,
+    VitaminC,
+    Calcium,
+    Iron,
+    Potassium,
+}
+
+impl Nutrient {
+    pub const ALL: [Nutrient; 8] = [
+        Nutrient::Fiber,
+        Nutrient::Sugar,
+        Nutrient::SaturatedFat,
+        Nutrient::Sodium,
+        Nutrient::VitaminC,
+        Nutrient::Calcium,
+        Nutrient::Iron,
+        Nutrient::Potassium,
+    ];
+
+    /// Ключ в JSON-ответе модели (совпадает с названием колонки в `food_logs`).
+    pub fn json_key(self) -> &'static str {
+        match self {
+            Nutrient::Fiber => "fiber",
+            Nutrient::Sugar => "sugar",
+            Nutrient::SaturatedFat => "saturated_fat",
+            Nutrient::Sodium => "sodium_mg",
+            Nutrient::VitaminC => "vitamin_c_mg",
+            Nutrient::Calcium => "calcium_mg",
+            Nutrient::Iron => "iron_mg",
+            Nutrient::Potassium => "potassium_mg",
+        }
+    }
+
+    pub fn unit(self) -> &'static str {
+        match self {
+            Nutrient::Fiber | Nutrient::Sugar | Nutrient::SaturatedFat => "g",
+            _ => "mg",
+        }
+    }
+
+    pub fn emoji(self) -> &'static str {
+        match self {
+            Nutrient::Fiber => "🌾",
+            Nutrient::Sugar => "🍬",
+            Nutrient::SaturatedFat => "🧈",
+            Nutrient::Sodium => "🧂",
+            Nutrient::VitaminC => "🍊",
+            Nutrient::Calcium => "🥛",
+            Nutrient::Iron => "🥩",
+            Nutrient::Potassium => "🍌",
+        }
+    }
+
+    pub fn label(self, lang: &str) -> &'static str {
+        match (self, lang) {
+            (Nutrient::Fiber, "ru") => "Клетчатка",
+            (Nutrient::Fiber, "th") => "ใยอาหาร",
+            (Nutrient::Fiber, "zh") => "膳食纤维",
+            (Nutrient::Fiber, _) => "Fibre",
+            (Nutrient::Sugar, "ru") => "Сахар",
+            (Nutrient::Sugar, "th") => "น้ำตาล",
+            (Nutrient::Sugar, "zh") => "糖",
+            (Nutrient::Sugar, _) => "Sugar",
+            (Nutrient::SaturatedFat, "ru") => "Насыщенные жиры",
+            (Nutrient::SaturatedFat, "th") => "ไขมันอิ่มตัว",
+            (Nutrient::SaturatedFat, "zh") => "饱和脂肪",
+            (Nutrient::SaturatedFat, _) => "Saturated fat",
+            (Nutrient::Sodium, "ru") => "Натрий",
+            (Nutrient::Sodium, "th") => "โซเดียม",
+            (Nutrient::Sodium, "zh") => "钠",
+            (Nutrient::Sodium, _) => "Sodium",
+            (Nutrient::VitaminC, "ru") => "Витамин C",
+            (Nutrient::VitaminC, "th") => "วิตามินซี",
+            (Nutrient::VitaminC, "zh") => "维生素C",
+            (Nutrient::VitaminC, _) => "Vitamin C",
+            (Nutrient::Calcium, "ru") => "Кальций",
+            (Nutrient::Calcium, "th") => "แคลเซียม",
+            (Nutrient::Calcium, "zh") => "钙",
+            (Nutrient::Calcium, _) => "Calcium",
+            (Nutrient::Iron, "ru") => "Железо",
+            (Nutrient::Iron, "th") => "ธาตุเหล็ก",
+            (Nutrient::Iron, "zh") => "铁",
+            (Nutrient::Iron, _) => "Iron",
+            (Nutrient::Potassium, "ru") => "Калий",
+            (Nutrient::Potassium, "th") => "โพแทสเซียม",
+            (Nutrient::Potassium, "zh") => "钾",
+            (Nutrient::Potassium, _) => "Potassium",
+        }
+    }
+
+    /// Сахар, насыщенные жиры и натрий — верхние пределы, остальное — нормы, которых нужно достичь.
+    pub fn is_limit(self) -> bool {
+        matches!(self, Nutrient::Sugar | Nutrient::SaturatedFat | Nutrient::Sodium)
+    }
+
+    pub fn value(self, micros: &Micronutrients) -> Option<f32> {
+        match self {
+            Nutrient::Fiber => micros.fiber,
+            Nutrient::Sugar => micros.sugar,
+            Nutrient::SaturatedFat => micros.saturated_fat,
+            Nutrient::Sodium => micros.sodium_mg,
+            Nutrient::VitaminC => micros.vitamin_c_mg,
+            Nutrient::Calcium => micros.calcium_mg,
+            Nutrient::Iron => micros.iron_mg,
+            Nutrient::Potassium => micros.potassium_mg,
+        }
+    }
+
+    fn slot(self, micros: &mut Micronutrients) -> &mut Option<f32> {
+        match self {
+            Nutrient::Fiber => &mut micros.fiber,
+            Nutrient::Sugar => &mut micros.sugar,
+            Nutrient::SaturatedFat => &mut micros.saturated_fat,
+            Nutrient::Sodium => &mut micros.sodium_mg,
+            Nutrient::VitaminC => &mut micros.vitamin_c_mg,
+            Nutrient::Calcium => &mut micros.calcium_mg,
+            Nutrient::Iron => &mut micros.iron_mg,
+            Nutrient::Potassium => &mut micros.potassium_mg,
+        }
+    }
+
+    /// Суточная норма (или предел) для взрослого с учётом пола и возраста
+    /// (DRI IOM; для сахара и натрия — рекомендации ВОЗ). Без данных профиля —
+    /// значения Daily Value с этикеток.
+    pub fn daily_reference(self, sex: Option<Sex>, age: Option<i32>) -> f32 {
+        let age = age.unwrap_or(30);
+        let teen = age < 19;
+        let older = age > 50;
+        match (self, sex) {
+            (Nutrient::Fiber, Some(Sex::Male)) => if older { 30.0 } else { 38.0 },
+            (Nutrient::Fiber, Some(Sex::Female)) => if older { 21.0 } else { 25.0 },
+            (Nutrient::Fiber, None) => 28.0,
+            (Nutrient::Sugar, _) => 50.0,
+            (Nutrient::SaturatedFat, Some(Sex::Male)) => 30.0,
+            (Nutrient::SaturatedFat, _) => 20.0,
+            (Nutrient::Sodium, _) => 2000.0,
+            (Nutrient::VitaminC, Some(Sex::Male)) => 90.0,
+            (Nutrient::VitaminC, Some(Sex::Female)) => 75.0,
+            (Nutrient::VitaminC, None) => 90.0,
+            (Nutrient::Calcium, _) if teen => 1300.0,
+            (Nutrient::Calcium, Some(Sex::Male)) => if age > 70 { 1200.0 } else { 1000.0 },
+            (Nutrient::Calcium, Some(Sex::Female)) => if older { 1200.0 } else { 1000.0 },
+            (Nutrient::Calcium, None) => 1300.0,
+            (Nutrient::Iron, Some(Sex::Male)) => if teen { 11.0 } else { 8.0 },
+            (Nutrient::Iron, Some(Sex::Female)) => if teen { 15.0 } else if older { 8.0 } else { 18.0 },
+            (Nutrient::Iron, None) => 18.0,
+            (Nutrient::Potassium, Some(Sex::Male)) => 3400.0,
+            (Nutrient::Potassium, Some(Sex::Female)) => 2600.0,
+            (Nutrient::Potassium, None) => 4700.0,
+        }
+    }
+}
+
+/// Читает микронутриенты из JSON-ответа модели; отсутствующие и нечисловые поля остаются `None`.
+pub fn from_json(value: &serde_json::Value) -> Micronutrients {
+    let mut micros = Micronutrients::default();
+    for nutrient in Nutrient::ALL {
+        *nutrient.slot(&mut micros) = value
+            .get(nutrient.json_key())
+            .and_then(serde_json::Value::as_f64)
+            .filter(|v| *v >= 0.0)
+            .map(|v| v as f32);
+    }
+    micros
+}
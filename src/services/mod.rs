@@ -1,2 +1,21 @@
+pub mod achievements;
 pub mod nutrition;
-pub mod chart;
\ No newline at end of file
+pub mod chart;
+pub mod diary_import;
+pub mod export;
+pub mod meal;
+pub mod log_time;
+pub mod food_db;
+pub mod micronutrients;
+pub mod barcode;
+pub mod products;
+pub mod recipes;
+pub mod reminders;
+pub mod tips;
+pub mod water;
+pub mod profile;
+pub mod report;
+pub mod retention;
+pub mod pdf_report;
+pub mod activity;
+pub mod fasting;
@@ -1,13 +1,21 @@
+use crate::db::models::{FoodProvenance, Micronutrients};
+use crate::services::log_time::{extract_time_hint, TimeHint};
+use crate::services::micronutrients;
 use reqwest::Client;
 use std::env;
 use std::error::Error;
 use std::fmt;
 
+/// Модель, которая считает КБЖУ по тексту и фото; сохраняется в записях дневника.
+pub const ANALYSIS_MODEL: &str = "gpt-4o";
+/// Модель распознавания речи голосовых сообщений.
+pub const TRANSCRIPTION_MODEL: &str = "whisper-1";
+
 const LANG_PROMPTS: &[(&str, &str, &str, &str)] = &[
-    ("ru", "Отвечай на русском языке.", "Ответ должен быть строго в формате JSON: {\"name\": \"...\", \"calories\": ..., \"proteins\": ..., \"fats\": ..., \"carbs\": ...}", "Рассчитай калории и БЖУ для"),
-    ("en", "Answer in English.", "The answer must be strictly in JSON format: {\"name\": \"...\", \"calories\": ..., \"proteins\": ..., \"fats\": ..., \"carbs\": ...}", "Calculate calories and Proteins Fats Carbohydrates for"),
-    ("th", "ตอบเป็นภาษาไทย.", "คำตอบต้องอยู่ในรูปแบบ JSON เท่านั้น: {\"name\": \"...\", \"calories\": ..., \"proteins\": ..., \"fats\": ..., \"carbs\": ...}", "คำนวณแคลอรี่และโปรตีน ไขมัน คาร์โบไฮเดรตสำหรับ"),
-    ("zh", "请用中文回答。", "回答必须严格采用 JSON 格式: {\"name\": \"...\", \"calories\": ..., \"proteins\": ..., \"fats\": ..., \"carbs\": ...}", "计算卡路里和蛋白质脂肪碳水化合物"),
+    ("ru", "Отвечай на русском языке.", "Ответ должен быть строго в формате JSON: {\"name\": \"...\", \"calories\": ..., \"proteins\": ..., \"fats\": ..., \"carbs\": ..., \"fiber\": ..., \"sugar\": ..., \"saturated_fat\": ..., \"sodium_mg\": ..., \"vitamin_c_mg\": ..., \"calcium_mg\": ..., \"iron_mg\": ..., \"potassium_mg\": ...}", "Рассчитай калории и БЖУ для"),
+    ("en", "Answer in English.", "The answer must be strictly in JSON format: {\"name\": \"...\", \"calories\": ..., \"proteins\": ..., \"fats\": ..., \"carbs\": ..., \"fiber\": ..., \"sugar\": ..., \"saturated_fat\": ..., \"sodium_mg\": ..., \"vitamin_c_mg\": ..., \"calcium_mg\": ..., \"iron_mg\": ..., \"potassium_mg\": ...}", "Calculate calories and Proteins Fats Carbohydrates for"),
+    ("th", "ตอบเป็นภาษาไทย.", "คำตอบต้องอยู่ในรูปแบบ JSON เท่านั้น: {\"name\": \"...\", \"calories\": ..., \"proteins\": ..., \"fats\": ..., \"carbs\": ..., \"fiber\": ..., \"sugar\": ..., \"saturated_fat\": ..., \"sodium_mg\": ..., \"vitamin_c_mg\": ..., \"calcium_mg\": ..., \"iron_mg\": ..., \"potassium_mg\": ...}", "คำนวณแคลอรี่และโปรตีน ไขมัน คาร์โบไฮเดรตสำหรับ"),
+    ("zh", "请用中文回答。", "回答必须严格采用 JSON 格式: {\"name\": \"...\", \"calories\": ..., \"proteins\": ..., \"fats\": ..., \"carbs\": ..., \"fiber\": ..., \"sugar\": ..., \"saturated_fat\": ..., \"sodium_mg\": ..., \"vitamin_c_mg\": ..., \"calcium_mg\": ..., \"iron_mg\": ..., \"potassium_mg\": ...}", "计算卡路里和蛋白质脂肪碳水化合物"),
 ];
 
 fn get_lang_prompt(lang: &str) -> (&str, &str, &str) {
@@ -17,7 +25,7 @@ fn get_lang_prompt(lang: &str) -> (&str, &str, &str) {
         .map(|&(_, p1, p2, p3)| (p1, p2, p3))
         .unwrap_or((
             "Answer in English.",
-            "The answer must be strictly in JSON format: {\"name\": \"...\", \"calories\": ..., \"proteins\": ..., \"fats\": ..., \"carbs\": ...}",
+            "The answer must be strictly in JSON format: {\"name\": \"...\", \"calories\": ..., \"proteins\": ..., \"fats\": ..., \"carbs\": ..., \"fiber\": ..., \"sugar\": ..., \"saturated_fat\": ..., \"sodium_mg\": ..., \"vitamin_c_mg\": ..., \"calcium_mg\": ..., \"iron_mg\": ..., \"potassium_mg\": ...}",
             "Calculate calories and Proteins Fats Carbohydrates for"
         ))
 }
@@ -77,6 +85,92 @@ pub struct FoodSummary {
     pub proteins: Option<f32>,
     pub fats: Option<f32>,
     pub carbs: Option<f32>,
+    /// Упоминания дня/времени приёма пищи («вчера на ужин»).
+    pub time_hint: TimeHint,
+    pub micros: Micronutrients,
+    /// Модель, участвовавшая в расчёте; `None` — посчитано без модели.
+    pub model: Option<&'static str>,
+}
+
+/// Откуда пришла запись дневника.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputSource {
+    Text,
+    Photo,
+    Voice,
+    Barcode,
+    Favorite,
+    Recipe,
+    Import,
+}
+
+impl InputSource {
+    pub const ALL: [InputSource; 7] = [
+        InputSource::Text,
+        InputSource::Photo,
+        InputSource::Voice,
+        InputSource::Barcode,
+        InputSource::Favorite,
+        InputSource::Recipe,
+        InputSource::Import,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InputSource::Text => "text",
+            InputSource::Photo => "photo",
+            InputSource::Voice => "voice",
+            InputSource::Barcode => "barcode",
+            InputSource::Favorite => "favorite",
+            InputSource::Recipe => "recipe",
+            InputSource::Import => "import",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|source| source.as_str() == value)
+    }
+
+    pub fn label(&self, lang: &str) -> &'static str {
+        match (self, lang) {
+            (InputSource::Text, "ru") => "✍️ Текст",
+            (InputSource::Text, "th") => "✍️ ข้อความ",
+            (InputSource::Text, "zh") => "✍️ 文字",
+            (InputSource::Text, _) => "✍️ Text",
+            (InputSource::Photo, "ru") => "📷 Фото",
+            (InputSource::Photo, "th") => "📷 รูปภาพ",
+            (InputSource::Photo, "zh") => "📷 照片",
+            (InputSource::Photo, _) => "📷 Photo",
+            (InputSource::Voice, "ru") => "🎤 Голосовое сообщение",
+            (InputSource::Voice, "th") => "🎤 ข้อความเสียง",
+            (InputSource::Voice, "zh") => "🎤 语音消息",
+            (InputSource::Voice, _) => "🎤 Voice message",
+            (InputSource::Barcode, "ru") => "🏷 Штрихкод",
+            (InputSource::Barcode, "th") => "🏷 บาร์โค้ด",
+            (InputSource::Barcode, "zh") => "🏷 条形码",
+            (InputSource::Barcode, _) => "🏷 Barcode",
+            (InputSource::Favorite, "ru") => "⭐ Избранное",
+            (InputSource::Favorite, "th") => "⭐ รายการโปรด",
+            (InputSource::Favorite, "zh") => "⭐ 收藏",
+            (InputSource::Favorite, _) => "⭐ Favorite",
+            (InputSource::Recipe, "ru") => "🍲 Рецепт",
+            (InputSource::Recipe, "th") => "🍲 สูตรอาหาร",
+            (InputSource::Recipe, "zh") => "🍲 食谱",
+            (InputSource::Recipe, _) => "🍲 Recipe",
+            (InputSource::Import, "ru") => "📥 Импорт",
+            (InputSource::Import, "th") => "📥 นำเข้า",
+            (InputSource::Import, "zh") => "📥 导入",
+            (InputSource::Import, _) => "📥 Import",
+        }
+    }
+
+    /// Заготовка происхождения записи; ответ модели дописывает `log_food_and_reply`.
+    pub fn provenance(&self) -> FoodProvenance {
+        FoodProvenance {
+            source: Some(self.as_str().to_string()),
+            ..Default::default()
+        }
+    }
 }
 
 pub async fn analyze_food_description(text: &str, lang: &str) -> Result<(FoodSummary, String), NutritionError> {
@@ -84,7 +178,7 @@ pub async fn analyze_food_description(text: &str, lang: &str) -> Result<(FoodSum
 
     let api_key = env::var("OPENAI_API_KEY")?;
     let body = serde_json::json!({
-        "model": "gpt-4o",
+        "model": ANALYSIS_MODEL,
         "messages": [
             {
                 "role": "system",
@@ -113,10 +207,13 @@ pub async fn analyze_food_description(text: &str, lang: &str) -> Result<(FoodSum
         .to_string();
 
     let name = text.to_string();
-    let calories = extract_float(&content, "ккал").or_else(|| extract_float(&content, "kcal"));
-    let proteins = extract_float(&content, "белк").or_else(|| extract_float(&content, "protein"));
-    let fats = extract_float(&content, "жир").or_else(|| extract_float(&content, "fats"));
-    let carbs = extract_float(&content, "углев").or_else(|| extract_float(&content, "carbs"));
+    let time_hint = extract_time_hint(text);
+    let json = extract_json(&content);
+    let calories = extract_float(&content, "ккал").or_else(|| extract_float(&content, "kcal")).or_else(|| json_float(&json, "calories"));
+    let proteins = extract_float(&content, "белк").or_else(|| extract_float(&content, "protein")).or_else(|| json_float(&json, "proteins"));
+    let fats = extract_float(&content, "жир").or_else(|| extract_float(&content, "fats")).or_else(|| json_float(&json, "fats"));
+    let carbs = extract_float(&content, "углев").or_else(|| extract_float(&content, "carbs")).or_else(|| json_float(&json, "carbs"));
+    let micros = json.as_ref().map(micronutrients::from_json).unwrap_or_default();
 
     Ok((
         FoodSummary {
@@ -125,6 +222,9 @@ pub async fn analyze_food_description(text: &str, lang: &str) -> Result<(FoodSum
             proteins,
             fats,
             carbs,
+            time_hint,
+            micros,
+            model: Some(ANALYSIS_MODEL),
         },
         content,
     ))
@@ -138,16 +238,27 @@ fn extract_float(text: &str, key: &str) -> Option<f32> {
     val_str.parse::<f32>().ok()
 }
 
+/// Вырезает из ответа модели JSON-объект (ответ может быть обёрнут в текст или ```json).
+fn extract_json(content: &str) -> Option<serde_json::Value> {
+    let start = content.find('{')?;
+    let end = content.rfind('}')?;
+    serde_json::from_str(content.get(start..=end)?).ok()
+}
+
+fn json_float(json: &Option<serde_json::Value>, key: &str) -> Option<f32> {
+    json.as_ref()?.get(key)?.as_f64().map(|v| v as f32)
+}
+
 pub async fn analyze_image(url: &str, lang: &str) -> Result<(FoodSummary, String), NutritionError> {
-    let (lang_prompt, _, _) = get_lang_prompt(lang);
+    let (lang_prompt, format_prompt, _) = get_lang_prompt(lang);
 
     let api_key = env::var("OPENAI_API_KEY")?;
     let body = serde_json::json!({
-        "model": "gpt-4o",
+        "model": ANALYSIS_MODEL,
         "messages": [{
             "role": "user",
             "content": [
-                { "type": "text", "text": format!("{} What is depicted on this food? How many calories and Proteins Fats Carbohydrates?", lang_prompt) },
+                { "type": "text", "text": format!("{} What is depicted on this food? How many calories and Proteins Fats Carbohydrates? Finish with one JSON line. {}", lang_prompt, format_prompt) },
                 { "type": "image_url", "image_url": { "url": url } }
             ]
         }],
@@ -169,10 +280,12 @@ pub async fn analyze_image(url: &str, lang: &str) -> Result<(FoodSummary, String
         .to_string();
 
     let name = "Еда с фото".to_string();
-    let calories = extract_float(&content, "ккал").or_else(|| extract_float(&content, "kcal"));
-    let proteins = extract_float(&content, "белк").or_else(|| extract_float(&content, "protein"));
-    let fats = extract_float(&content, "жир");
-    let carbs = extract_float(&content, "углев");
+    let json = extract_json(&content);
+    let calories = extract_float(&content, "ккал").or_else(|| extract_float(&content, "kcal")).or_else(|| json_float(&json, "calories"));
+    let proteins = extract_float(&content, "белк").or_else(|| extract_float(&content, "protein")).or_else(|| json_float(&json, "proteins"));
+    let fats = extract_float(&content, "жир").or_else(|| json_float(&json, "fats"));
+    let carbs = extract_float(&content, "углев").or_else(|| json_float(&json, "carbs"));
+    let micros = json.as_ref().map(micronutrients::from_json).unwrap_or_default();
 
     Ok((
         FoodSummary {
@@ -181,12 +294,16 @@ pub async fn analyze_image(url: &str, lang: &str) -> Result<(FoodSummary, String
             proteins,
             fats,
             carbs,
+            time_hint: TimeHint::default(),
+            micros,
+            model: Some(ANALYSIS_MODEL),
         },
         content,
     ))
 }
 
-pub async fn analyze_audio(url: &str, lang: &str) -> Result<(FoodSummary, String), NutritionError> {
+/// Распознаёт речь голосового сообщения (Whisper).
+pub async fn transcribe_audio(url: &str) -> Result<String, NutritionError> {
     let api_key = env::var("OPENAI_API_KEY")?;
     let file_bytes = reqwest::get(url).await?.bytes().await?;
     let part = reqwest::multipart::Part::stream(file_bytes)
@@ -194,7 +311,7 @@ pub async fn analyze_audio(url: &str, lang: &str) -> Result<(FoodSummary, String
         .mime_str("audio/ogg")?;
     let form = reqwest::multipart::Form::new()
         .part("file", part)
-        .text("model", "whisper-1");
+        .text("model", TRANSCRIPTION_MODEL);
 
     let client = Client::new();
     let res = client
@@ -210,5 +327,5 @@ pub async fn analyze_audio(url: &str, lang: &str) -> Result<(FoodSummary, String
         .ok_or("No transcription text")?
         .to_string();
 
-    analyze_food_description(&text, lang).await
+    Ok(text)
 }
@@ -0,0 +1,361 @@
+use crate::services::profile::KCAL_PER_GRAM;
+use chrono::NaiveDate;
+use printpdf::{
+    Color, ColorBits, ColorSpace, Image, ImageTransform, ImageXObject, IndirectFontRef, Line, Mm,
+    PdfDocument, PdfDocumentReference, PdfLayerReference, Point, Px, Rect, Rgb,
+};
+use std::error::Error;
+
+/// Страница A4 и поля, мм.
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 18.0;
+/// Высота строки таблицы, мм.
+const ROW_HEIGHT: f32 = 6.5;
+/// Ширина графика на странице, мм (графики растягиваются на всю ширину текста).
+const CHART_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN;
+
+/// Тот же шрифт, что и на графиках: в нём есть кириллица.
+static FONT: &[u8] = include_bytes!("../../assets/DejaVuSans.ttf");
+
+/// Итоги одного дня для таблицы отчёта.
+pub struct ReportDay {
+    pub date: NaiveDate,
+    pub entries: i64,
+    pub calories: f32,
+    pub proteins: f32,
+    pub fats: f32,
+    pub carbs: f32,
+    pub burned: f32,
+}
+
+/// Подписи отчёта на языке пользователя.
+pub struct ReportLabels<'a> {
+    pub profile: &'a str,
+    pub targets: &'a str,
+    pub summary: &'a str,
+    pub macro_split: &'a str,
+    pub charts: &'a str,
+    pub daily: &'a str,
+    /// Белки, жиры, углеводы.
+    pub macros: [&'a str; 3],
+    /// Столбцы таблицы БЖУ: нутриент, г в день, доля энергии, рекомендуемая доля, норма.
+    pub macro_columns: [&'a str; 5],
+    /// Столбцы дневной таблицы: дата, записей, ккал, Б, Ж, У, сожжено, итог.
+    pub day_columns: [&'a str; 8],
+    pub average: &'a str,
+    pub page: &'a str,
+}
+
+/// Отчёт о питании за период; текст уже на языке пользователя.
+pub struct PdfReport<'a> {
+    pub title: &'a str,
+    /// Строка под заголовком: период и дата формирования.
+    pub subtitle: &'a str,
+    pub profile: &'a [(String, String)],
+    pub targets: &'a [(String, String)],
+    pub summary: &'a [(String, String)],
+    /// Все дни периода, в том числе без записей.
+    pub days: &'a [ReportDay],
+    /// Рекомендуемая доля энергии из белков, жиров и углеводов, %.
+    pub target_percent: [f32; 3],
+    /// Норма БЖУ в граммах, если известна норма калорий.
+    pub target_grams: Option<[f32; 3]>,
+    /// Графики из `services::chart` в формате PNG.
+    pub charts: &'a [Vec<u8>],
+    pub labels: ReportLabels<'a>,
+}
+
+/// Собирает многостраничный PDF: профиль и нормы, итоги периода, распределение БЖУ,
+/// графики и таблицу по дням. Номера страниц проставляются в конце, когда известно их число.
+pub fn render_pdf_report(report: &PdfReport) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+    let (doc, page, layer) = PdfDocument::new(report.title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "content");
+    let font = doc.add_external_font(FONT)?;
+    let first_layer = doc.get_page(page).get_layer(layer);
+    let mut writer = Writer {
+        doc,
+        font,
+        layers: vec![first_layer],
+        y: PAGE_HEIGHT - MARGIN,
+    };
+    let labels = &report.labels;
+
+    writer.text(report.title, 20.0, MARGIN, 8.0);
+    writer.text(report.subtitle, 10.0, MARGIN, 10.0);
+
+    writer.heading(labels.profile);
+    writer.key_values(report.profile);
+    if !report.targets.is_empty() {
+        writer.heading(labels.targets);
+        writer.key_values(report.targets);
+    }
+    writer.heading(labels.summary);
+    writer.key_values(report.summary);
+
+    writer.heading(labels.macro_split);
+    let macro_columns = [0.0, 40.0, 75.0, 108.0, 141.0];
+    writer.table(&macro_columns, &labels.macro_columns, &macro_rows(report), false);
+
+    if !report.charts.is_empty() {
+        writer.heading(labels.charts);
+        for png in report.charts {
+            writer.image(png)?;
+        }
+    }
+
+    writer.heading(labels.daily);
+    let day_columns = [0.0, 27.0, 47.0, 68.0, 88.0, 108.0, 128.0, 152.0];
+    writer.table(&day_columns, &labels.day_columns, &day_rows(report), true);
+
+    writer.finish(labels.page)
+}
+
+/// Строки таблицы БЖУ: средние граммы за день с записями, доля энергии и нормы.
+fn macro_rows(report: &PdfReport) -> Vec<Vec<String>> {
+    let logged: Vec<&ReportDay> = report.days.iter().filter(|d| d.entries > 0).collect();
+    let count = logged.len().max(1) as f32;
+    let grams = [
+        logged.iter().map(|d| d.proteins).sum::<f32>() / count,
+        logged.iter().map(|d| d.fats).sum::<f32>() / count,
+        logged.iter().map(|d| d.carbs).sum::<f32>() / count,
+    ];
+    let energy: Vec<f32> = grams.iter().zip(KCAL_PER_GRAM).map(|(g, k)| g * k).collect();
+    let total_energy: f32 = energy.iter().sum();
+
+    (0..3)
+        .map(|i| {
+            let share = if total_energy > 0.0 {
+                format!("{:.0}%", energy[i] / total_energy * 100.0)
+            } else {
+                "—".to_string()
+            };
+            vec![
+                report.labels.macros[i].to_string(),
+                format!("{:.0} g", grams[i]),
+                share,
+                format!("{:.0}%", report.target_percent[i]),
+                report
+                    .target_grams
+                    .map_or("—".to_string(), |t| format!("{:.0} g", t[i])),
+            ]
+        })
+        .collect()
+}
+
+/// Строки дневной таблицы и итоговая строка со средними за дни с записями.
+fn day_rows(report: &PdfReport) -> Vec<Vec<String>> {
+    let mut rows: Vec<Vec<String>> = report
+        .days
+        .iter()
+        .map(|day| {
+            if day.entries == 0 && day.burned == 0.0 {
+                let mut row = vec![day.date.format("%d.%m.%Y").to_string()];
+                row.extend(std::iter::repeat_n("—".to_string(), 7));
+                return row;
+            }
+            vec![
+                day.date.format("%d.%m.%Y").to_string(),
+                day.entries.to_string(),
+                format!("{:.0}", day.calories),
+                format!("{:.0}", day.proteins),
+                format!("{:.0}", day.fats),
+                format!("{:.0}", day.carbs),
+                format!("{:.0}", day.burned),
+                format!("{:.0}", day.calories - day.burned),
+            ]
+        })
+        .collect();
+
+    let logged: Vec<&ReportDay> = report.days.iter().filter(|d| d.entries > 0).collect();
+    if !logged.is_empty() {
+        let count = logged.len() as f32;
+        let average = |value: fn(&ReportDay) -> f32| {
+            format!("{:.0}", logged.iter().map(|d| value(d)).sum::<f32>() / count)
+        };
+        rows.push(vec![
+            report.labels.average.to_string(),
+            format!("{:.1}", logged.iter().map(|d| d.entries).sum::<i64>() as f32 / count),
+            average(|d| d.calories),
+            average(|d| d.proteins),
+            average(|d| d.fats),
+            average(|d| d.carbs),
+            average(|d| d.burned),
+            average(|d| d.calories - d.burned),
+        ]);
+    }
+    rows
+}
+
+/// Раскладывает содержимое по страницам сверху вниз; `y` — текущая позиция от низа страницы.
+struct Writer {
+    doc: PdfDocumentReference,
+    font: IndirectFontRef,
+    layers: Vec<PdfLayerReference>,
+    y: f32,
+}
+
+impl Writer {
+    fn layer(&self) -> &PdfLayerReference {
+        self.layers.last().expect("document has at least one page")
+    }
+
+    fn new_page(&mut self) {
+        let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "content");
+        self.layers.push(self.doc.get_page(page).get_layer(layer));
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    /// Переходит на новую страницу, если `height` мм не помещаются над нижним полем.
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height < MARGIN + 8.0 {
+            self.new_page();
+        }
+    }
+
+    /// Строка текста: `advance` — сколько миллиметров она занимает по вертикали.
+    fn text(&mut self, text: &str, size: f32, x: f32, advance: f32) {
+        self.ensure_space(advance);
+        self.y -= advance;
+        self.layer().use_text(text, size, Mm(x), Mm(self.y), &self.font);
+    }
+
+    fn heading(&mut self, text: &str) {
+        // Заголовок не должен остаться внизу страницы без содержимого
+        self.ensure_space(30.0);
+        self.y -= 4.0;
+        self.layer().set_fill_color(accent_color());
+        self.text(text, 14.0, MARGIN, 8.0);
+        self.layer().set_fill_color(text_color());
+        self.rule(0.6);
+        self.y -= 2.0;
+    }
+
+    fn key_values(&mut self, rows: &[(String, String)]) {
+        for (key, value) in rows {
+            self.ensure_space(ROW_HEIGHT);
+            self.y -= ROW_HEIGHT;
+            self.layer().use_text(key, 10.0, Mm(MARGIN), Mm(self.y), &self.font);
+            self.layer().use_text(value, 10.0, Mm(MARGIN + 60.0), Mm(self.y), &self.font);
+        }
+    }
+
+    /// Таблица с заголовком на сером фоне; на каждой новой странице заголовок повторяется.
+    /// `total_row` — последняя строка итоговая и отделяется линией.
+    fn table(&mut self, columns: &[f32], header: &[&str], rows: &[Vec<String>], total_row: bool) {
+        self.table_header(columns, header);
+        for (index, row) in rows.iter().enumerate() {
+            if self.y - ROW_HEIGHT < MARGIN + 8.0 {
+                self.new_page();
+                self.table_header(columns, header);
+            }
+            if total_row && index + 1 == rows.len() {
+                self.rule(0.4);
+            }
+            self.y -= ROW_HEIGHT;
+            for (cell, x) in row.iter().zip(columns) {
+                self.layer().use_text(cell, 9.5, Mm(MARGIN + x), Mm(self.y + 1.5), &self.font);
+            }
+        }
+        self.y -= 2.0;
+    }
+
+    fn table_header(&mut self, columns: &[f32], header: &[&str]) {
+        self.ensure_space(ROW_HEIGHT * 2.0);
+        self.y -= ROW_HEIGHT;
+        let layer = self.layer();
+        layer.set_fill_color(header_color());
+        layer.add_rect(Rect::new(
+            Mm(MARGIN),
+            Mm(self.y),
+            Mm(PAGE_WIDTH - MARGIN),
+            Mm(self.y + ROW_HEIGHT),
+        ));
+        layer.set_fill_color(text_color());
+        for (title, x) in header.iter().zip(columns) {
+            layer.use_text(*title, 9.0, Mm(MARGIN + x + 1.0), Mm(self.y + 1.8), &self.font);
+        }
+    }
+
+    /// Горизонтальная линия на всю ширину текста.
+    fn rule(&mut self, thickness: f32) {
+        let layer = self.layer();
+        layer.set_outline_color(accent_color());
+        layer.set_outline_thickness(thickness);
+        layer.add_line(Line {
+            points: vec![
+                (Point::new(Mm(MARGIN), Mm(self.y - 1.0)), false),
+                (Point::new(Mm(PAGE_WIDTH - MARGIN), Mm(self.y - 1.0)), false),
+            ],
+            is_closed: false,
+        });
+        self.y -= 1.0;
+    }
+
+    /// Вставляет PNG на всю ширину текста, сохраняя пропорции.
+    fn image(&mut self, png: &[u8]) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let image = image::load_from_memory(png)?.to_rgb8();
+        let (width, height) = image.dimensions();
+        let height_mm = CHART_WIDTH * height as f32 / width as f32;
+        self.ensure_space(height_mm + 4.0);
+        self.y -= height_mm + 4.0;
+
+        let xobject = ImageXObject {
+            width: Px(width as usize),
+            height: Px(height as usize),
+            color_space: ColorSpace::Rgb,
+            bits_per_component: ColorBits::Bit8,
+            interpolate: true,
+            image_data: image.into_raw(),
+            image_filter: None,
+            smask: None,
+            clipping_bbox: None,
+        };
+        Image::from(xobject).add_to_layer(
+            self.layer().clone(),
+            ImageTransform {
+                translate_x: Some(Mm(MARGIN)),
+                translate_y: Some(Mm(self.y)),
+                dpi: Some(width as f32 * 25.4 / CHART_WIDTH),
+                ..Default::default()
+            },
+        );
+        Ok(())
+    }
+
+    /// Проставляет «стр. N из M» внизу каждой страницы и сохраняет документ.
+    fn finish(self, page_label: &str) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+        let total = self.layers.len();
+        for (index, layer) in self.layers.iter().enumerate() {
+            layer.set_fill_color(muted_color());
+            layer.use_text(
+                format!("Kalorik · {} {} / {}", page_label, index + 1, total),
+                8.0,
+                Mm(MARGIN),
+                Mm(MARGIN / 2.0),
+                &self.font,
+            );
+        }
+        drop(self.layers);
+        Ok(self.doc.save_to_bytes()?)
+    }
+}
+
+fn rgb(r: f32, g: f32, b: f32) -> Color {
+    Color::Rgb(Rgb::new(r / 255.0, g / 255.0, b / 255.0, None))
+}
+
+fn text_color() -> Color {
+    rgb(30.0, 30.0, 30.0)
+}
+
+fn accent_color() -> Color {
+    rgb(40.0, 160.0, 90.0)
+}
+
+fn header_color() -> Color {
+    rgb(230.0, 236.0, 232.0)
+}
+
+fn muted_color() -> Color {
+    rgb(130.0, 130.0, 130.0)
+}
@@ -0,0 +1,126 @@
+use crate::db::models::Product;
+use crate::db::queries;
+use crate::services::food_db::ImportReport;
+use std::path::Path;
+
+/// Сколько продуктов записывается в БД одной транзакцией.
+const IMPORT_BATCH_SIZE: usize = 1000;
+
+/// Переводит UPC-A (12 цифр) в EAN-13, как их возвращает распознаватель штрихкодов.
+pub fn normalize_barcode(code: &str) -> String {
+    let code = code.trim();
+    if code.len() == 12 && code.chars().all(|c| c.is_ascii_digit()) {
+        format!("0{}", code)
+    } else {
+        code.to_string()
+    }
+}
+
+impl Product {
+    /// КБЖУ для заданного веса в граммах.
+    pub fn nutrition_for(&self, grams: f32) -> (f32, f32, f32, f32) {
+        let k = grams / 100.0;
+        (
+            self.calories * k,
+            self.proteins.unwrap_or(0.0) * k,
+            self.fats.unwrap_or(0.0) * k,
+            self.carbs.unwrap_or(0.0) * k,
+        )
+    }
+
+    pub fn display_name(&self) -> String {
+        match &self.brand {
+            Some(brand) if !brand.is_empty() => format!("{} ({})", self.name, brand),
+            _ => self.name.clone(),
+        }
+    }
+}
+
+/// Импортирует CSV-выгрузку Open Food Facts (разделитель — табуляция). Строки без
+/// штрихкода, названия или калорийности пропускаются; повторный импорт обновляет данные.
+pub async fn import_off_dump(
+    path: &Path,
+) -> Result<ImportReport, Box<dyn std::error::Error + Send + Sync>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(b'\t')
+        .flexible(true)
+        .quoting(false)
+        .from_path(path)?;
+
+    let headers = reader.headers()?.clone();
+    let column = |name: &str| headers.iter().position(|h| h == name);
+    let code_col = column("code").ok_or("missing 'code' column")?;
+    let name_col = column("product_name").ok_or("missing 'product_name' column")?;
+    let generic_col = column("generic_name");
+    let brand_col = column("brands");
+    let serving_col = column("serving_quantity");
+    let kcal_col = column("energy-kcal_100g");
+    let kj_col = column("energy_100g");
+    let proteins_col = column("proteins_100g");
+    let fats_col = column("fat_100g");
+    let carbs_col = column("carbohydrates_100g");
+
+    let mut report = ImportReport::default();
+    let mut batch = Vec::with_capacity(IMPORT_BATCH_SIZE);
+    for (i, record) in reader.records().enumerate() {
+        let line = i + 2;
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                report.skip(line, "invalid row", e.to_string());
+                continue;
+            }
+        };
+
+        let field = |idx: Option<usize>| idx.and_then(|i| record.get(i)).map(str::trim).filter(|v| !v.is_empty());
+        let number = |idx: Option<usize>| field(idx).and_then(|v| v.parse::<f32>().ok());
+
+        let Some(code) = field(Some(code_col)) else {
+            report.skip(line, "empty code", "");
+            continue;
+        };
+        let Some(name) = field(Some(name_col)).or_else(|| field(generic_col)) else {
+            report.skip(line, "no name", code);
+            continue;
+        };
+        let Some(calories) = number(kcal_col).or_else(|| number(kj_col).map(|kj| kj / 4.184)) else {
+            report.skip(line, "no energy value", code);
+            continue;
+        };
+
+        let product = Product {
+            barcode: normalize_barcode(code),
+            name: name.to_string(),
+            brand: field(brand_col).map(|b| b.split(',').next().unwrap_or(b).trim().to_string()),
+            serving_size_g: number(serving_col).filter(|g| *g > 0.0),
+            calories,
+            proteins: number(proteins_col),
+            fats: number(fats_col),
+            carbs: number(carbs_col),
+        };
+        batch.push(product);
+        if batch.len() == IMPORT_BATCH_SIZE {
+            report.imported += queries::upsert_products(&batch, "openfoodfacts").await? as usize;
+            batch.clear();
+        }
+    }
+    if !batch.is_empty() {
+        report.imported += queries::upsert_products(&batch, "openfoodfacts").await? as usize;
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pads_upc_a_to_ean13() {
+        assert_eq!(normalize_barcode("012345678905"), "0012345678905");
+        assert_eq!(normalize_barcode(" 036000291452\n"), "0036000291452");
+        assert_eq!(normalize_barcode("4006381333931"), "4006381333931");
+        assert_eq!(normalize_barcode("96385074"), "96385074");
+        assert_eq!(normalize_barcode("01234567890a"), "01234567890a");
+    }
+}
@@ -0,0 +1,102 @@
+use crate::db::models::User;
+use crate::services::micronutrients::Sex;
+
+/// Индекс массы тела; `None`, если рост в профиле не указан.
+pub fn bmi(weight_kg: f64, height_cm: Option<f64>) -> Option<f64> {
+    let height_m = height_cm.filter(|h| *h > 50.0)? / 100.0;
+    Some((weight_kg / (height_m * height_m) * 10.0).round() / 10.0)
+}
+
+pub fn bmi_category(bmi: f64, lang: &str) -> &'static str {
+    let index = match bmi {
+        b if b < 18.5 => 0,
+        b if b < 25.0 => 1,
+        b if b < 30.0 => 2,
+        _ => 3,
+    };
+    let labels: [&str; 4] = match lang {
+        "ru" => ["дефицит массы", "норма", "избыточный вес", "ожирение"],
+        "th" => ["น้ำหนักน้อย", "ปกติ", "น้ำหนักเกิน", "อ้วน"],
+        "zh" => ["偏瘦", "正常", "超重", "肥胖"],
+        _ => ["underweight", "normal", "overweight", "obese"],
+    };
+    labels[index]
+}
+
+/// Коэффициент активности для формулы Миффлина — Сан-Жеора.
+fn activity_factor(activity_level: Option<&str>) -> f64 {
+    let level = activity_level.unwrap_or_default().to_lowercase();
+    if level.contains("very") || level.contains("очень") {
+        1.9
+    } else if level.contains("active") || level.contains("high") || level.contains("высок") {
+        1.725
+    } else if level.contains("moderate") || level.contains("medium") || level.contains("сред") {
+        1.55
+    } else if level.contains("light") || level.contains("низк") || level.contains("лёгк") {
+        1.375
+    } else {
+        1.2
+    }
+}
+
+/// Поправка на цель: дефицит 15% для похудения, профицит 10% для набора массы.
+fn goal_factor(goal: Option<&str>) -> f64 {
+    let goal = goal.unwrap_or_default().to_lowercase();
+    if goal.contains("lose") || goal.contains("похуд") || goal.contains("сброс") {
+        0.85
+    } else if goal.contains("gain") || goal.contains("набор") || goal.contains("набрать") {
+        1.1
+    } else {
+        1.0
+    }
+}
+
+/// Цель из профиля в виде ключа: `lose`, `gain` или `maintain`.
+pub fn goal_key(goal: Option<&str>) -> &'static str {
+    let factor = goal_factor(goal);
+    if factor < 1.0 {
+        "lose"
+    } else if factor > 1.0 {
+        "gain"
+    } else {
+        "maintain"
+    }
+}
+
+/// Суточная норма калорий для указанного веса (Миффлин — Сан-Жеор × активность × цель).
+/// Нужны рост, возраст и пол из профиля.
+pub fn calorie_target(user: &User, weight_kg: f64) -> Option<i32> {
+    let height = user.height_cm.filter(|h| *h > 50.0)?;
+    let age = user.age.filter(|a| *a > 0)? as f64;
+    let sex = user.gender.as_deref().and_then(Sex::parse)?;
+
+    let bmr = 10.0 * weight_kg + 6.25 * height - 5.0 * age
+        + match sex {
+            Sex::Male => 5.0,
+            Sex::Female => -161.0,
+        };
+    let target = bmr * activity_factor(user.activity_level.as_deref()) * goal_factor(user.goal.as_deref());
+    Some(((target / 10.0).round() * 10.0) as i32)
+}
+
+/// Калорий в грамме белков, жиров и углеводов.
+pub const KCAL_PER_GRAM: [f32; 3] = [4.0, 9.0, 4.0];
+
+/// Рекомендуемая доля энергии из белков, жиров и углеводов (в процентах) с учётом цели:
+/// при похудении больше белка, при наборе массы — углеводов.
+pub fn macro_split(goal: Option<&str>) -> [f32; 3] {
+    let factor = goal_factor(goal);
+    if factor < 1.0 {
+        [30.0, 30.0, 40.0]
+    } else if factor > 1.0 {
+        [25.0, 25.0, 50.0]
+    } else {
+        [20.0, 30.0, 50.0]
+    }
+}
+
+/// Суточная норма белков, жиров и углеводов в граммах для нормы калорий.
+pub fn macro_targets_g(calorie_target: i32, goal: Option<&str>) -> [f32; 3] {
+    let split = macro_split(goal);
+    std::array::from_fn(|i| calorie_target as f32 * split[i] / 100.0 / KCAL_PER_GRAM[i])
+}
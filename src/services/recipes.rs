@@ -0,0 +1,93 @@
+use crate::db::models::Recipe;
+use crate::services::food_db::{extract_grams, match_key, strip_stopwords, MATCH_THRESHOLD};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+static SERVINGS_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(\d+(?:[.,]\d+)?)\s*(порци\w*|servings?|portions?)").unwrap()
+});
+
+/// Слова, которые могут стоять рядом с названием рецепта («150 г моей лазаньи»).
+const RECIPE_WORDS: &[&str] = &[
+    "мой", "моя", "мое", "моё", "моей", "моего", "мою", "my", "порция", "порции", "порцию",
+    "порций", "serving", "servings", "portion", "portions", "половина", "половину", "half", "рецепт",
+    "рецепта", "recipe",
+];
+
+/// Сколько съедено: вес в граммах или число порций.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RecipePortion {
+    Grams(f32),
+    Servings(f32),
+}
+
+impl Recipe {
+    pub fn serving_weight_g(&self) -> f32 {
+        self.cooked_weight_g / self.servings.max(1) as f32
+    }
+
+    /// КБЖУ для указанной порции.
+    pub fn nutrition_for(&self, portion: RecipePortion) -> (f32, f32, f32, f32) {
+        let share = match portion {
+            RecipePortion::Grams(grams) => grams / self.cooked_weight_g,
+            RecipePortion::Servings(servings) => servings / self.servings.max(1) as f32,
+        };
+        (
+            self.calories * share,
+            self.proteins * share,
+            self.fats * share,
+            self.carbs * share,
+        )
+    }
+}
+
+impl RecipePortion {
+    pub fn grams(&self, recipe: &Recipe) -> f32 {
+        match *self {
+            RecipePortion::Grams(grams) => grams,
+            RecipePortion::Servings(servings) => servings * recipe.serving_weight_g(),
+        }
+    }
+}
+
+/// Распознаёт сообщение вида «150 г моей лазаньи» или «2 порции борща». Срабатывает,
+/// только если кроме названия рецепта, количества и служебных слов в тексте ничего нет.
+pub fn find_recipe_portion<'a>(text: &str, recipes: &'a [Recipe]) -> Option<(&'a Recipe, RecipePortion)> {
+    let (portion, rest) = match extract_grams(text) {
+        Some((grams, rest)) => (RecipePortion::Grams(grams), rest),
+        None => match SERVINGS_RE.captures(text) {
+            Some(cap) => {
+                let servings = cap[1].replace(',', ".").parse().ok()?;
+                (RecipePortion::Servings(servings), SERVINGS_RE.replace(text, " ").into_owned())
+            }
+            None => {
+                let lower = text.to_lowercase();
+                let servings = if lower.contains("половин") || lower.contains("half") { 0.5 } else { 1.0 };
+                (RecipePortion::Servings(servings), text.to_string())
+            }
+        },
+    };
+
+    let words: Vec<String> = strip_stopwords(&rest)
+        .split_whitespace()
+        .filter(|w| !RECIPE_WORDS.contains(w))
+        .map(match_key)
+        .collect();
+    if words.is_empty() {
+        return None;
+    }
+
+    let similar = |a: &str, b: &str| strsim::jaro_winkler(a, b) >= MATCH_THRESHOLD;
+
+    recipes
+        .iter()
+        .filter(|recipe| {
+            let name = match_key(&recipe.name);
+            let name_words: Vec<&str> = name.split_whitespace().collect();
+            !name_words.is_empty()
+                && words.iter().all(|w| name_words.iter().any(|n| similar(w, n)))
+                && name_words.iter().all(|n| words.iter().any(|w| similar(w, n)))
+        })
+        .max_by_key(|recipe| recipe.name.len())
+        .map(|recipe| (recipe, portion))
+}
@@ -0,0 +1,108 @@
+use crate::db::models::MealReminder;
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+
+/// Сколько после назначенного времени напоминание ещё может быть отправлено
+/// (например, если бот был перезапущен или шли тихие часы).
+const CATCH_UP: Duration = Duration::hours(2);
+
+/// Пора ли отправить напоминание. Отложенное напоминание отправляется по `snoozed_until`,
+/// обычное — один раз в день после `remind_at`.
+pub fn is_due(
+    reminder: &MealReminder,
+    today: NaiveDate,
+    local_time: NaiveTime,
+    now: DateTime<Utc>,
+) -> bool {
+    if let Some(until) = reminder.snoozed_until {
+        return until <= now;
+    }
+    if reminder.last_sent == Some(today) {
+        return false;
+    }
+    local_time >= reminder.remind_at && local_time - reminder.remind_at <= CATCH_UP
+}
+
+/// Попадает ли время в тихие часы; интервал может переходить через полночь (22:00–08:00).
+pub fn in_quiet_hours(time: NaiveTime, quiet_hours: Option<(NaiveTime, NaiveTime)>) -> bool {
+    match quiet_hours {
+        Some((start, end)) if start <= end => time >= start && time < end,
+        Some((start, end)) => time >= start || time < end,
+        None => false,
+    }
+}
+
+/// Разбирает время `13:00`, `9:30` или просто час `13`.
+pub fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value, "%H:%M").ok().or_else(|| {
+        let hour: u32 = value.parse().ok()?;
+        NaiveTime::from_hms_opt(hour, 0, 0)
+    })
+}
+
+/// Разбирает тихие часы вида `22:00-08:00`.
+pub fn parse_quiet_hours(value: &str) -> Option<(NaiveTime, NaiveTime)> {
+    let (start, end) = value.split_once(['-', '–'])?;
+    let (start, end) = (parse_time(start.trim())?, parse_time(end.trim())?);
+    (start != end).then_some((start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn reminder(remind_at: (u32, u32), last_sent: Option<NaiveDate>) -> MealReminder {
+        MealReminder {
+            id: 1,
+            chat_id: 1,
+            meal_type: "lunch".to_string(),
+            remind_at: NaiveTime::from_hms_opt(remind_at.0, remind_at.1, 0).unwrap(),
+            only_if_not_logged: false,
+            last_sent,
+            snoozed_until: None,
+        }
+    }
+
+    fn time(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn due_within_catch_up_window() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        let now = Utc.with_ymd_and_hms(2026, 10, 19, 10, 0, 0).unwrap();
+        let lunch = reminder((13, 0), None);
+
+        assert!(!is_due(&lunch, today, time(12, 59), now));
+        assert!(is_due(&lunch, today, time(13, 0), now));
+        assert!(is_due(&lunch, today, time(14, 30), now));
+        assert!(is_due(&lunch, today, time(15, 0), now));
+        assert!(!is_due(&lunch, today, time(15, 1), now));
+        assert!(!is_due(&lunch, today, time(23, 0), now));
+    }
+
+    #[test]
+    fn sent_once_a_day() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        let now = Utc.with_ymd_and_hms(2026, 10, 19, 10, 0, 0).unwrap();
+        let yesterday = today.pred_opt();
+
+        assert!(!is_due(&reminder((13, 0), Some(today)), today, time(13, 30), now));
+        assert!(is_due(&reminder((13, 0), yesterday), today, time(13, 30), now));
+    }
+
+    #[test]
+    fn snoozed_reminder_follows_snoozed_until() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 20).unwrap();
+        let now = Utc.with_ymd_and_hms(2026, 10, 19, 21, 10, 0).unwrap();
+        let mut dinner = reminder((22, 0), today.pred_opt());
+
+        dinner.snoozed_until = Some(now + Duration::minutes(5));
+        assert!(!is_due(&dinner, today, time(0, 10), now));
+        // Отсрочка срабатывает и после полуночи, вне окна CATCH_UP и несмотря на `last_sent`.
+        dinner.snoozed_until = Some(now - Duration::minutes(1));
+        assert!(is_due(&dinner, today, time(0, 10), now));
+        dinner.last_sent = Some(today);
+        assert!(is_due(&dinner, today, time(0, 10), now));
+    }
+}
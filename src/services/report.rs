@@ -0,0 +1,150 @@
+use crate::db::models::{DailyTotals, WeightLog};
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Weekday};
+use chrono_tz::Tz;
+use std::collections::{BTreeMap, HashSet};
+
+/// Допустимое отклонение от нормы калорий, чтобы день считался выполненным.
+const ADHERENCE_TOLERANCE: f32 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportKind {
+    Weekly,
+    Monthly,
+}
+
+impl ReportKind {
+    /// Период отчёта, отправляемого в местную дату `today`: прошедшие 7 дней
+    /// или прошлый календарный месяц.
+    pub fn period(self, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+        let to = today - Duration::days(1);
+        match self {
+            ReportKind::Weekly => (today - Duration::days(7), to),
+            ReportKind::Monthly => (to.with_day(1).unwrap_or(to), to),
+        }
+    }
+
+    /// Отчёт пора отправить: недельный — по понедельникам, месячный — первого числа,
+    /// после выбранного времени и если за эту дату он ещё не отправлялся.
+    pub fn is_due(
+        self,
+        today: NaiveDate,
+        now: NaiveTime,
+        send_time: NaiveTime,
+        last_sent: Option<NaiveDate>,
+    ) -> bool {
+        let day_matches = match self {
+            ReportKind::Weekly => today.weekday() == Weekday::Mon,
+            ReportKind::Monthly => today.day() == 1,
+        };
+        day_matches && now >= send_time && last_sent != Some(today)
+    }
+}
+
+/// Итоги периода для отчёта.
+#[derive(Debug, Clone)]
+pub struct PeriodReport {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub logged_days: usize,
+    pub avg_calories: f32,
+    /// Средние белки, жиры, углеводы за день с записями, г.
+    pub avg_macros: [f32; 3],
+    /// Лучший и худший день: ближе всего к норме и дальше всего от неё
+    /// (без нормы — меньше и больше всего калорий).
+    pub best_day: Option<(NaiveDate, f32)>,
+    pub worst_day: Option<(NaiveDate, f32)>,
+    /// Доля дней периода, когда калории (за вычетом сожжённых) были в пределах ±10% нормы.
+    pub adherence_percent: Option<f32>,
+    pub weight_change: Option<f64>,
+}
+
+impl PeriodReport {
+    pub fn days(&self) -> i64 {
+        (self.to - self.from).num_days() + 1
+    }
+}
+
+/// Считает итоги периода по дневным суммам, сожжённым калориям и измерениям веса;
+/// даты измерений берутся в поясе `tz`.
+pub fn build_report(
+    from: NaiveDate,
+    to: NaiveDate,
+    totals: &[DailyTotals],
+    burned: &BTreeMap<NaiveDate, f32>,
+    target: Option<f32>,
+    weights: &[WeightLog],
+    tz: Tz,
+) -> PeriodReport {
+    let logged: Vec<&DailyTotals> = totals
+        .iter()
+        .filter(|d| d.date >= from && d.date <= to && d.calories > 0.0)
+        .collect();
+    let count = logged.len().max(1) as f32;
+    let avg_calories = logged.iter().map(|d| d.calories).sum::<f32>() / count;
+    let avg_macros = [
+        logged.iter().map(|d| d.proteins).sum::<f32>() / count,
+        logged.iter().map(|d| d.fats).sum::<f32>() / count,
+        logged.iter().map(|d| d.carbs).sum::<f32>() / count,
+    ];
+
+    let net = |d: &DailyTotals| d.calories - burned.get(&d.date).copied().unwrap_or(0.0);
+    let score = |d: &DailyTotals| match target {
+        Some(target) => (net(d) - target).abs(),
+        None => d.calories,
+    };
+    let best_day = logged
+        .iter()
+        .min_by(|a, b| score(a).total_cmp(&score(b)))
+        .map(|d| (d.date, d.calories));
+    let worst_day = logged
+        .iter()
+        .max_by(|a, b| score(a).total_cmp(&score(b)))
+        .map(|d| (d.date, d.calories));
+
+    let days = (to - from).num_days() + 1;
+    let adherence_percent = target.filter(|t| *t > 0.0).map(|target| {
+        let on_target = logged
+            .iter()
+            .filter(|d| (net(d) - target).abs() <= target * ADHERENCE_TOLERANCE)
+            .count();
+        on_target as f32 / days as f32 * 100.0
+    });
+
+    PeriodReport {
+        from,
+        to,
+        logged_days: logged.len(),
+        avg_calories,
+        avg_macros,
+        best_day,
+        worst_day,
+        adherence_percent,
+        weight_change: weight_change(weights, from, to, tz),
+    }
+}
+
+/// Изменение веса за период: последнее измерение до конца периода минус
+/// последнее измерение до его начала (или первое внутри периода). Границы — местные даты.
+fn weight_change(weights: &[WeightLog], from: NaiveDate, to: NaiveDate, tz: Tz) -> Option<f64> {
+    let local_date = |w: &WeightLog| w.created_at.with_timezone(&tz).date_naive();
+    let before = weights.iter().rev().find(|w| local_date(w) < from);
+    let within: Vec<&WeightLog> = weights
+        .iter()
+        .filter(|w| (from..=to).contains(&local_date(w)))
+        .collect();
+    let start = before.or(within.first().copied())?;
+    let end = within.last()?;
+    (start.created_at != end.created_at).then_some(end.weight_kg - start.weight_kg)
+}
+
+/// Дней подряд с записями о еде, заканчивая днём `end` включительно.
+pub fn logging_streak(dates: &[NaiveDate], end: NaiveDate) -> u32 {
+    let dates: HashSet<&NaiveDate> = dates.iter().collect();
+    let mut day = end;
+    let mut streak = 0;
+    while dates.contains(&day) {
+        streak += 1;
+        day -= Duration::days(1);
+    }
+    streak
+}
@@ -0,0 +1,241 @@
+use crate::db::queries;
+use chrono::{DateTime, Duration, NaiveDate, Timelike, Utc};
+use std::sync::Mutex;
+
+/// Час (UTC), в который планировщик раз в сутки применяет правила хранения.
+const DEFAULT_PURGE_HOUR: u32 = 3;
+
+/// Дата последнего запуска по расписанию, чтобы очистка шла не чаще раза в сутки.
+static LAST_RUN: Mutex<Option<NaiveDate>> = Mutex::new(None);
+
+/// Правило хранения: какие данные удаляются по истечении срока.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    /// Аккаунты без активности удаляются целиком, как по `/deleteme`.
+    InactiveAccounts,
+    FoodLogs,
+    /// Исходный ввод записей дневника: текст, файлы, расшифровки и ответы модели.
+    FoodLogInputs,
+    TipHistory,
+}
+
+impl Rule {
+    pub const ALL: [Rule; 4] = [
+        Rule::InactiveAccounts,
+        Rule::FoodLogs,
+        Rule::FoodLogInputs,
+        Rule::TipHistory,
+    ];
+
+    /// Переменная окружения со сроком хранения в днях; `0` или `off` отключает правило.
+    pub fn env_var(&self) -> &'static str {
+        match self {
+            Rule::InactiveAccounts => "RETENTION_INACTIVE_ACCOUNT_DAYS",
+            Rule::FoodLogs => "RETENTION_FOOD_LOG_DAYS",
+            Rule::FoodLogInputs => "RETENTION_FOOD_LOG_INPUT_DAYS",
+            Rule::TipHistory => "RETENTION_TIP_HISTORY_DAYS",
+        }
+    }
+
+    /// Срок по умолчанию. Правила, удаляющие данные пользователей (аккаунты, дневник и его
+    /// исходный ввод), без явной настройки выключены — удаление включает оператор.
+    fn default_days(&self) -> Option<i64> {
+        match self {
+            Rule::InactiveAccounts => None,
+            Rule::FoodLogs => None,
+            Rule::FoodLogInputs => None,
+            Rule::TipHistory => Some(365),
+        }
+    }
+
+    /// Срок хранения по значению переменной окружения (`None` — переменная не задана);
+    /// при неверном значении берётся срок по умолчанию.
+    fn days(&self, value: Option<&str>) -> Option<i64> {
+        match value {
+            Some(value) => parse_days(value).unwrap_or_else(|| {
+                log::warn!("Invalid {}={:?}, using default", self.env_var(), value);
+                self.default_days()
+            }),
+            None => self.default_days(),
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Rule::InactiveAccounts => "inactive accounts (no diary entries or profile changes)",
+            Rule::FoodLogs => "food log entries",
+            Rule::FoodLogInputs => "food log inputs (messages, file ids, transcripts, model replies)",
+            Rule::TipHistory => "sent daily tip records",
+        }
+    }
+}
+
+/// Действующие правила хранения и режим запуска по расписанию.
+#[derive(Debug, Clone)]
+pub struct RetentionPolicy {
+    /// Включённые правила и срок хранения в днях.
+    pub rules: Vec<(Rule, i64)>,
+    /// Плановый запуск только считает, что было бы удалено (`RETENTION_DRY_RUN`).
+    pub dry_run: bool,
+    pub purge_hour: u32,
+}
+
+impl RetentionPolicy {
+    pub fn from_env() -> Self {
+        let rules = Rule::ALL
+            .into_iter()
+            .filter_map(|rule| {
+                let days = rule.days(std::env::var(rule.env_var()).ok().as_deref());
+                days.map(|days| (rule, days))
+            })
+            .collect();
+        let dry_run = std::env::var("RETENTION_DRY_RUN")
+            .ok()
+            .is_some_and(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"));
+        let purge_hour = std::env::var("RETENTION_PURGE_HOUR")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .filter(|hour| *hour < 24)
+            .unwrap_or(DEFAULT_PURGE_HOUR);
+
+        RetentionPolicy {
+            rules,
+            dry_run,
+            purge_hour,
+        }
+    }
+}
+
+/// Срок хранения: число дней (не больше 100 лет), `0`/`off` — правило выключено (`Some(None)`).
+fn parse_days(value: &str) -> Option<Option<i64>> {
+    let value = value.trim().to_lowercase();
+    if value == "off" || value == "0" {
+        return Some(None);
+    }
+    value.parse::<i64>().ok().filter(|d| (1..=36_500).contains(d)).map(Some)
+}
+
+/// Граница правила: удаляется всё, что старше `days` дней на момент `now`.
+fn cutoff(now: DateTime<Utc>, days: i64) -> DateTime<Utc> {
+    now - Duration::days(days)
+}
+
+/// Результат применения одного правила.
+#[derive(Debug, Clone)]
+pub struct PurgeResult {
+    pub rule: Rule,
+    pub days: i64,
+    pub cutoff: DateTime<Utc>,
+    /// Удалено (или было бы удалено при `dry_run`) записей; для аккаунтов — аккаунтов.
+    pub affected: u64,
+}
+
+/// Применяет правила политики; при `dry_run` ничего не удаляет, а только считает.
+pub async fn purge(
+    policy: &RetentionPolicy,
+    now: DateTime<Utc>,
+    dry_run: bool,
+) -> Result<Vec<PurgeResult>, sqlx::Error> {
+    let mut results = Vec::new();
+    for &(rule, days) in &policy.rules {
+        let cutoff = cutoff(now, days);
+        let affected = match rule {
+            Rule::InactiveAccounts => {
+                let chat_ids = queries::get_inactive_accounts(cutoff).await?;
+                if !dry_run {
+                    for chat_id in &chat_ids {
+                        queries::delete_account(*chat_id, "retention").await?;
+                    }
+                }
+                chat_ids.len() as u64
+            }
+            Rule::FoodLogs => queries::purge_food_logs(cutoff, dry_run).await?,
+            Rule::FoodLogInputs => queries::purge_food_log_inputs(cutoff, dry_run).await?,
+            Rule::TipHistory => queries::purge_tip_history(cutoff, dry_run).await?,
+        };
+        results.push(PurgeResult {
+            rule,
+            days,
+            cutoff,
+            affected,
+        });
+    }
+    Ok(results)
+}
+
+/// Вызывается планировщиком: раз в сутки в `purge_hour` применяет политику из окружения
+/// и пишет итог в лог.
+pub async fn run_if_due() {
+    let now = Utc::now();
+    let policy = RetentionPolicy::from_env();
+    if now.hour() < policy.purge_hour || policy.rules.is_empty() {
+        return;
+    }
+    {
+        let mut last_run = LAST_RUN.lock().unwrap();
+        if *last_run == Some(now.date_naive()) {
+            return;
+        }
+        *last_run = Some(now.date_naive());
+    }
+
+    match purge(&policy, now, policy.dry_run).await {
+        Ok(results) => {
+            for result in results {
+                log::info!(
+                    "Retention{}: {} ({} days) — {} {}",
+                    if policy.dry_run { " (dry run)" } else { "" },
+                    result.rule.description(),
+                    result.days,
+                    result.affected,
+                    if policy.dry_run { "would be deleted" } else { "deleted" }
+                );
+            }
+        }
+        Err(e) => log::error!("Error applying retention policy: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn parses_days() {
+        assert_eq!(parse_days("30"), Some(Some(30)));
+        assert_eq!(parse_days(" 365 "), Some(Some(365)));
+        assert_eq!(parse_days("36500"), Some(Some(36_500)));
+        assert_eq!(parse_days("0"), Some(None));
+        assert_eq!(parse_days("off"), Some(None));
+        assert_eq!(parse_days("OFF"), Some(None));
+        assert_eq!(parse_days("-7"), None);
+        assert_eq!(parse_days("36501"), None);
+        assert_eq!(parse_days("30d"), None);
+        assert_eq!(parse_days(""), None);
+    }
+
+    #[test]
+    fn defaults_keep_user_data() {
+        for rule in [Rule::InactiveAccounts, Rule::FoodLogs, Rule::FoodLogInputs] {
+            assert_eq!(rule.days(None), None, "{:?}", rule);
+            assert_eq!(rule.days(Some("garbage")), None, "{:?}", rule);
+            assert_eq!(rule.days(Some("-1")), None, "{:?}", rule);
+            assert_eq!(rule.days(Some("90")), Some(90), "{:?}", rule);
+        }
+        assert_eq!(Rule::TipHistory.days(None), Some(365));
+        assert_eq!(Rule::TipHistory.days(Some("garbage")), Some(365));
+        assert_eq!(Rule::TipHistory.days(Some("-30")), Some(365));
+        assert_eq!(Rule::TipHistory.days(Some("0")), None);
+        assert_eq!(Rule::TipHistory.days(Some("off")), None);
+        assert_eq!(Rule::TipHistory.days(Some("30")), Some(30));
+    }
+
+    #[test]
+    fn cutoff_is_days_before_now() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 1, 3, 15, 0).unwrap();
+        assert_eq!(cutoff(now, 1), Utc.with_ymd_and_hms(2026, 2, 28, 3, 15, 0).unwrap());
+        assert_eq!(cutoff(now, 365), Utc.with_ymd_and_hms(2025, 3, 1, 3, 15, 0).unwrap());
+        assert_eq!(cutoff(now, 36_500), Utc.with_ymd_and_hms(1926, 3, 26, 3, 15, 0).unwrap());
+    }
+}
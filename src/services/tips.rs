@@ -0,0 +1,145 @@
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Каталог советов: `assets/tips.json`, тексты на всех языках бота.
+static CATALOGUE: Lazy<Vec<Tip>> = Lazy::new(|| {
+    serde_json::from_str(include_str!("../../assets/tips.json")).unwrap_or_else(|e| {
+        log::error!("Failed to parse tips catalogue: {}", e);
+        Vec::new()
+    })
+});
+
+/// Доля белка от нормы, ниже которой стоит подсказать про белок.
+const LOW_PROTEIN_RATIO: f32 = 0.8;
+/// Доля нормы воды, ниже которой стоит подсказать про воду.
+const LOW_WATER_RATIO: f32 = 0.7;
+/// Превышение нормы калорий, после которого стоит подсказать про порции.
+const OVER_CALORIES_RATIO: f32 = 1.1;
+/// Меньше стольких дней с записями за неделю — данных мало, советуем записывать.
+const MIN_LOGGED_DAYS: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Topic {
+    Protein,
+    Water,
+    Calories,
+    Logging,
+    Activity,
+    Fiber,
+    Sugar,
+    Sleep,
+    General,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Tip {
+    pub id: String,
+    pub topic: Topic,
+    /// Цели (`lose`, `gain`, `maintain`), для которых совет подходит; пусто — для всех.
+    #[serde(default)]
+    pub goals: Vec<String>,
+    text: HashMap<String, String>,
+}
+
+impl Tip {
+    /// Текст на языке пользователя, иначе на английском.
+    pub fn text(&self, lang: &str) -> &str {
+        self.text
+            .get(lang)
+            .or_else(|| self.text.get("en"))
+            .map(String::as_str)
+            .unwrap_or_default()
+    }
+
+    fn fits_goal(&self, goal: &str) -> bool {
+        self.goals.is_empty() || self.goals.iter().any(|g| g == goal)
+    }
+}
+
+/// Данные пользователя за последнюю неделю, по которым выбирается тема совета.
+#[derive(Debug, Clone, Default)]
+pub struct RecentData {
+    pub logged_days: usize,
+    pub avg_calories: f32,
+    pub avg_protein: f32,
+    pub calorie_target: Option<f32>,
+    pub protein_target: Option<f32>,
+    /// Средний объём воды за дни с записями; `None`, если воду не отмечали.
+    pub avg_water_ml: Option<f32>,
+    pub water_goal_ml: f32,
+    pub active_days: usize,
+}
+
+/// Темы, актуальные по последним данным, — от самой важной к менее важной.
+pub fn relevant_topics(data: &RecentData) -> Vec<Topic> {
+    if data.logged_days < MIN_LOGGED_DAYS {
+        return vec![Topic::Logging];
+    }
+
+    let mut topics = Vec::new();
+    if let Some(target) = data.protein_target.filter(|t| *t > 0.0)
+        && data.avg_protein < target * LOW_PROTEIN_RATIO
+    {
+        topics.push(Topic::Protein);
+    }
+    if let Some(target) = data.calorie_target.filter(|t| *t > 0.0)
+        && data.avg_calories > target * OVER_CALORIES_RATIO
+    {
+        topics.push(Topic::Calories);
+    }
+    if data.avg_water_ml.unwrap_or(0.0) < data.water_goal_ml * LOW_WATER_RATIO {
+        topics.push(Topic::Water);
+    }
+    if data.active_days == 0 {
+        topics.push(Topic::Activity);
+    }
+    topics
+}
+
+/// Пора ли отправить совет дня: после выбранного времени и если сегодня ещё не отправляли.
+pub fn is_due(
+    today: NaiveDate,
+    now: NaiveTime,
+    send_time: NaiveTime,
+    last_sent: Option<NaiveDate>,
+) -> bool {
+    now >= send_time && last_sent != Some(today)
+}
+
+/// Выбирает совет: подходящий цели пользователя, ещё не показанный (а когда показаны все —
+/// из давно показанных), по возможности на актуальную тему. `seed` чередует советы с равным приоритетом.
+pub fn pick_tip(
+    goal: &str,
+    topics: &[Topic],
+    history: &HashMap<String, DateTime<Utc>>,
+    seed: u64,
+) -> Option<&'static Tip> {
+    let candidates: Vec<&Tip> = CATALOGUE.iter().filter(|t| t.fits_goal(goal)).collect();
+    let unseen: Vec<&Tip> = candidates
+        .iter()
+        .filter(|t| !history.contains_key(&t.id))
+        .copied()
+        .collect();
+    let pool = if unseen.is_empty() {
+        // Все советы уже показаны — начинаем новый круг с самой давно показанной половины
+        let mut seen = candidates;
+        seen.sort_by_key(|t| history.get(&t.id).copied());
+        seen.truncate(seen.len().div_ceil(2));
+        seen
+    } else {
+        unseen
+    };
+
+    let preferred = topics
+        .iter()
+        .map(|topic| pool.iter().filter(|t| t.topic == *topic).copied().collect::<Vec<_>>())
+        .find(|tips| !tips.is_empty())
+        .unwrap_or(pool);
+    if preferred.is_empty() {
+        return None;
+    }
+    Some(preferred[(seed % preferred.len() as u64) as usize])
+}
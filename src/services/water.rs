@@ -0,0 +1,26 @@
+/// Норма воды, если вес в профиле не указан.
+const DEFAULT_GOAL_ML: i64 = 2000;
+
+/// Миллилитров воды на килограмм веса.
+const ML_PER_KG: f64 = 30.0;
+
+/// Суточная норма воды: 30 мл на кг веса, округлённая до 50 мл (от 1,5 до 4 л).
+pub fn daily_goal_ml(weight_kg: Option<f64>) -> i64 {
+    match weight_kg {
+        Some(weight) if weight > 0.0 => {
+            let goal = (weight * ML_PER_KG / 50.0).round() as i64 * 50;
+            goal.clamp(1500, 4000)
+        }
+        _ => DEFAULT_GOAL_ML,
+    }
+}
+
+/// Полоска прогресса из десяти делений: `▰▰▰▱▱▱▱▱▱▱`.
+pub fn progress_bar(current_ml: i64, goal_ml: i64) -> String {
+    let filled = if goal_ml > 0 {
+        ((current_ml * 10 + goal_ml / 2) / goal_ml).clamp(0, 10) as usize
+    } else {
+        0
+    };
+    format!("{}{}", "▰".repeat(filled), "▱".repeat(10 - filled))
+}
@@ -0,0 +1,189 @@
+use crate::db::queries;
+use crate::locales::messages::Messages;
+use crate::telegram::state;
+use chrono::Utc;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, InputFile},
+};
+
+/// `/mydata` — всё, что хранится о пользователе, одним JSON-файлом.
+pub async fn handle_mydata_command(bot: &Bot, chat_id: ChatId, lang: &str) -> ResponseResult<()> {
+    let data = match queries::get_account_data(chat_id.0).await {
+        Ok(data) => data,
+        Err(e) => {
+            log::error!("Error in get_account_data: {}", e);
+            bot.send_message(chat_id, &Messages::get(lang).error).await?;
+            return Ok(());
+        }
+    };
+    let bytes = match serde_json::from_str::<serde_json::Value>(&data)
+        .and_then(|value| serde_json::to_vec_pretty(&value))
+    {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            log::error!("Error formatting account data: {}", e);
+            bot.send_message(chat_id, &Messages::get(lang).error).await?;
+            return Ok(());
+        }
+    };
+
+    let file_name = format!("kalorik-mydata-{}.json", Utc::now().format("%Y-%m-%d"));
+    bot.send_document(chat_id, InputFile::memory(bytes).file_name(file_name))
+        .caption(mydata_caption(lang))
+        .await?;
+
+    Ok(())
+}
+
+/// `/deleteme` — предупреждение и кнопки подтверждения удаления аккаунта.
+pub async fn handle_deleteme_command(bot: &Bot, chat_id: ChatId, lang: &str) -> ResponseResult<()> {
+    let subscribed = queries::get_user(chat_id.0)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|u| u.subscription_ends_at)
+        .is_some_and(|ends| ends > Utc::now());
+
+    let mut text = warning_text(lang).to_string();
+    if subscribed {
+        text.push_str("\n\n");
+        text.push_str(subscription_warning(lang));
+    }
+    bot.send_message(chat_id, text)
+        .reply_markup(InlineKeyboardMarkup::new(vec![
+            vec![InlineKeyboardButton::callback(
+                format!("🗑 {}", confirm_label(lang)),
+                "acc:delete",
+            )],
+            vec![InlineKeyboardButton::callback(
+                format!("✖️ {}", cancel_label(lang)),
+                "acc:cancel",
+            )],
+        ]))
+        .await?;
+
+    Ok(())
+}
+
+/// Кнопки подтверждения: `acc:delete` — удалить всё, `acc:cancel` — оставить.
+pub async fn handle_account_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    data: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    bot.answer_callback_query(q.id.clone()).await?;
+    let Some(message) = q.message.as_ref() else {
+        return Ok(());
+    };
+    let chat_id = message.chat().id;
+
+    if data != "acc:delete" {
+        bot.edit_message_text(chat_id, message.id(), cancelled_text(lang))
+            .await?;
+        return Ok(());
+    }
+
+    match queries::delete_account(chat_id.0, "user_request").await {
+        Ok((erased, retained_payments)) => {
+            log::info!(
+                "Account {} deleted at user's request: {} rows erased, {} payments retained",
+                chat_id.0,
+                erased,
+                retained_payments
+            );
+            state::clear_pending(chat_id.0);
+            state::clear_pending_import(chat_id.0);
+            bot.edit_message_text(chat_id, message.id(), deleted_text(retained_payments > 0, lang))
+                .await?;
+        }
+        Err(e) => {
+            log::error!("Error in delete_account: {}", e);
+            bot.edit_message_text(chat_id, message.id(), &Messages::get(lang).error)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+fn mydata_caption(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "📦 Все данные, которые бот хранит о вас: профиль, дневник, вес, вода, тренировки, рецепты, настройки и платежи.",
+        "th" => "📦 ข้อมูลทั้งหมดที่บอทเก็บเกี่ยวกับคุณ: โปรไฟล์ บันทึกอาหาร น้ำหนัก น้ำ การออกกำลังกาย สูตรอาหาร การตั้งค่า และการชำระเงิน",
+        "zh" => "📦 机器人保存的关于您的全部数据：个人资料、饮食日记、体重、饮水、运动、食谱、设置和付款记录。",
+        _ => "📦 Everything the bot stores about you: profile, diary, weight, water, workouts, recipes, settings and payments.",
+    }
+}
+
+fn warning_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "⚠️ Удалить аккаунт?\n\nБудут безвозвратно удалены профиль, дневник питания, вес, вода, тренировки, голодания, избранное, рецепты, напоминания и достижения.\n\nЗаписи о платежах мы обязаны хранить по закону — они останутся, но без связи с вашим аккаунтом.\n\nСначала можно скачать копию данных: /mydata",
+        "th" => "⚠️ ลบบัญชีหรือไม่?\n\nโปรไฟล์ บันทึกอาหาร น้ำหนัก น้ำ การออกกำลังกาย การอดอาหาร รายการโปรด สูตรอาหาร การแจ้งเตือน และความสำเร็จ จะถูกลบอย่างถาวร\n\nบันทึกการชำระเงินต้องเก็บไว้ตามกฎหมาย แต่จะไม่เชื่อมโยงกับบัญชีของคุณอีก\n\nดาวน์โหลดสำเนาข้อมูลก่อนได้: /mydata",
+        "zh" => "⚠️ 删除账户？\n\n个人资料、饮食日记、体重、饮水、运动、断食、收藏、食谱、提醒和成就将被永久删除。\n\n付款记录依法必须保留，但将不再与您的账户关联。\n\n可以先下载数据副本：/mydata",
+        _ => "⚠️ Delete your account?\n\nYour profile, food diary, weight, water, workouts, fasts, favorites, recipes, reminders and achievements will be permanently erased.\n\nPayment records must be kept by law — they stay, but are no longer linked to your account.\n\nYou can download a copy of your data first: /mydata",
+    }
+}
+
+fn subscription_warning(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "💳 У вас активная подписка — после удаления она будет потеряна.",
+        "th" => "💳 คุณมีการสมัครสมาชิกที่ยังใช้งานอยู่ และจะหายไปหลังการลบ",
+        "zh" => "💳 您的订阅仍有效，删除后将失效。",
+        _ => "💳 You have an active subscription — it will be lost after deletion.",
+    }
+}
+
+fn confirm_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Удалить всё",
+        "th" => "ลบทั้งหมด",
+        "zh" => "全部删除",
+        _ => "Delete everything",
+    }
+}
+
+fn cancel_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Отмена",
+        "th" => "ยกเลิก",
+        "zh" => "取消",
+        _ => "Cancel",
+    }
+}
+
+fn cancelled_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "👍 Аккаунт не удалён.",
+        "th" => "👍 ไม่ได้ลบบัญชี",
+        "zh" => "👍 账户未删除。",
+        _ => "👍 Your account was not deleted.",
+    }
+}
+
+fn deleted_text(payments_retained: bool, lang: &str) -> String {
+    let mut text = match lang {
+        "ru" => "🗑 Ваши данные удалены.",
+        "th" => "🗑 ลบข้อมูลของคุณแล้ว",
+        "zh" => "🗑 您的数据已删除。",
+        _ => "🗑 Your data has been deleted.",
+    }
+    .to_string();
+    if payments_retained {
+        text.push(' ');
+        text.push_str(match lang {
+            "ru" => "Записи о платежах сохранены без привязки к аккаунту, как того требует закон.",
+            "th" => "บันทึกการชำระเงินถูกเก็บไว้โดยไม่เชื่อมโยงกับบัญชีตามที่กฎหมายกำหนด",
+            "zh" => "付款记录已依法保留，但不再与账户关联。",
+            _ => "Payment records are kept, unlinked from the account, as required by law.",
+        });
+    }
+    text.push_str(match lang {
+        "ru" => "\n\nЧтобы начать заново, отправьте /start.",
+        "th" => "\n\nหากต้องการเริ่มใหม่ ส่ง /start",
+        "zh" => "\n\n如需重新开始，请发送 /start。",
+        _ => "\n\nTo start over, send /start.",
+    });
+    text
+}
@@ -0,0 +1,211 @@
+use crate::db::queries;
+use crate::locales::messages::Messages;
+use crate::services::achievements::{earned_badges, Badge, Progress};
+use crate::telegram::handlers::get_user_today;
+use chrono::{Duration, NaiveDate, Utc};
+use std::collections::BTreeMap;
+use teloxide::prelude::*;
+
+/// За сколько дней назад считать серии.
+const STREAK_LOOKBACK_DAYS: i64 = 365;
+
+/// `/achievements` — показатели, полученные значки и прогресс до следующих.
+pub async fn handle_achievements_command(
+    bot: &Bot,
+    chat_id: ChatId,
+    lang: &str,
+) -> ResponseResult<()> {
+    let progress = match load_progress(chat_id.0).await {
+        Ok(progress) => progress,
+        Err(e) => {
+            log::error!("Error loading achievements progress: {}", e);
+            bot.send_message(chat_id, &Messages::get(lang).error).await?;
+            return Ok(());
+        }
+    };
+    // Значки, заработанные до появления этой функции, выдаём при первом просмотре
+    award(chat_id.0, &progress).await;
+    let awarded: BTreeMap<String, chrono::DateTime<Utc>> = queries::get_badges(chat_id.0)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("get_badges failed: {}", e);
+            Vec::new()
+        })
+        .into_iter()
+        .map(|b| (b.badge, b.awarded_at))
+        .collect();
+
+    let labels = stat_labels(lang);
+    let mut text = format!(
+        "🏆 {}\n\n🍽 {}: {}\n🔥 {}: {} ({} {})",
+        title(lang),
+        labels[0],
+        progress.meals_logged,
+        labels[1],
+        progress.logging_streak,
+        labels[3],
+        progress.best_logging_streak,
+    );
+    if progress.has_target {
+        text.push_str(&format!(
+            "\n🎯 {}: {} ({} {})",
+            labels[2], progress.under_target_streak, labels[3], progress.best_under_target_streak
+        ));
+    } else {
+        text.push_str(&format!("\n🎯 {}", no_target_text(lang)));
+    }
+    text.push('\n');
+
+    for badge in Badge::ALL {
+        match awarded.get(badge.key()) {
+            Some(awarded_at) => text.push_str(&format!(
+                "\n✅ {} {} — {}",
+                badge.emoji(),
+                badge.title(lang),
+                awarded_at.format("%d.%m.%Y")
+            )),
+            None => {
+                let (value, goal) = badge.progress(&progress);
+                text.push_str(&format!(
+                    "\n🔒 {} {} — {}/{}",
+                    badge.emoji(),
+                    badge.title(lang),
+                    value.min(goal),
+                    goal
+                ));
+            }
+        }
+    }
+
+    bot.send_message(chat_id, text).await?;
+    Ok(())
+}
+
+/// Проверяет достижения после новой записи и поздравляет с новыми значками от имени Фит-Хомяка.
+/// Пользователю с историей записей, но ещё без значков (дневник вёлся до появления достижений
+/// или был импортирован), значки выдаются молча — иначе первая же запись принесла бы
+/// поздравление со всем, что заработано за годы.
+pub async fn announce_new_badges(bot: &Bot, chat_id: ChatId, lang: &str) {
+    let loaded = async {
+        let progress = load_progress(chat_id.0).await?;
+        let has_badges = queries::has_badges(chat_id.0).await?;
+        Ok::<_, sqlx::Error>((progress, has_badges))
+    };
+    let (progress, has_badges) = match loaded.await {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            log::warn!("Failed to load achievements progress: {}", e);
+            return;
+        }
+    };
+    let new_badges = award(chat_id.0, &progress).await;
+    if new_badges.is_empty() {
+        return;
+    }
+    if !has_badges && progress.meals_logged > 1 {
+        log::info!("Backfilled {} badges for {} without announcing", new_badges.len(), chat_id);
+        return;
+    }
+
+    let mut text = format!("🐹 {}", congrats_text(new_badges.len(), lang));
+    for badge in &new_badges {
+        text.push_str(&format!("\n🏅 {} {}", badge.emoji(), badge.title(lang)));
+    }
+    text.push_str(&format!("\n\n{}: /achievements", all_label(lang)));
+    if let Err(e) = bot.send_message(chat_id, text).await {
+        log::warn!("Failed to announce badges to {}: {}", chat_id, e);
+    }
+}
+
+/// Сохраняет заработанные значки и возвращает новые.
+async fn award(chat_id: i64, progress: &Progress) -> Vec<Badge> {
+    let keys: Vec<String> = earned_badges(progress)
+        .iter()
+        .map(|badge| badge.key().to_string())
+        .collect();
+    if keys.is_empty() {
+        return Vec::new();
+    }
+    match queries::award_badges(chat_id, &keys).await {
+        Ok(awarded) => {
+            let mut badges: Vec<Badge> = awarded.iter().filter_map(|key| Badge::parse(key)).collect();
+            badges.sort_by_key(|badge| Badge::ALL.iter().position(|b| b == badge));
+            badges
+        }
+        Err(e) => {
+            log::error!("Error in award_badges: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+async fn load_progress(chat_id: i64) -> Result<Progress, sqlx::Error> {
+    let today = get_user_today(chat_id).await;
+    let from = today - Duration::days(STREAK_LOOKBACK_DAYS);
+    let meals_logged = queries::count_food_logs(chat_id).await?;
+    let totals = queries::get_daily_totals_range(chat_id, from, today).await?;
+    let burned: BTreeMap<NaiveDate, f32> = queries::get_burned_by_day(chat_id, from, today)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("get_burned_by_day failed: {}", e);
+            Vec::new()
+        })
+        .into_iter()
+        .collect();
+    let target = queries::get_user(chat_id)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|u| u.calorie_target)
+        .map(|t| t as f32);
+
+    Ok(Progress::build(meals_logged, &totals, &burned, target, today))
+}
+
+fn title(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Достижения",
+        "th" => "ความสำเร็จ",
+        "zh" => "成就",
+        _ => "Achievements",
+    }
+}
+
+/// Подписи: записей, серия записей, серия в пределах нормы, «рекорд».
+fn stat_labels(lang: &str) -> [&'static str; 4] {
+    match lang {
+        "ru" => ["Записей о еде", "Дней подряд с записями", "Дней подряд в пределах нормы", "рекорд"],
+        "th" => ["บันทึกอาหาร", "บันทึกต่อเนื่อง (วัน)", "อยู่ในเป้าหมายต่อเนื่อง (วัน)", "สูงสุด"],
+        "zh" => ["已记录餐数", "连续记录天数", "连续不超目标天数", "最佳"],
+        _ => ["Meals logged", "Logging streak", "Days under target in a row", "best"],
+    }
+}
+
+fn no_target_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Укажите рост, возраст и пол в профиле, чтобы считать дни в пределах нормы.",
+        "th" => "ระบุส่วนสูง อายุ และเพศในโปรไฟล์เพื่อนับวันที่อยู่ในเป้าหมาย",
+        "zh" => "在个人资料中填写身高、年龄和性别，即可统计不超目标的天数。",
+        _ => "Add your height, age and sex to the profile to track days under target.",
+    }
+}
+
+fn congrats_text(count: usize, lang: &str) -> &'static str {
+    match (count, lang) {
+        (1, "ru") => "Фит-Хомяк поздравляет! Новое достижение:",
+        (_, "ru") => "Фит-Хомяк поздравляет! Новые достижения:",
+        (_, "th") => "Fit-Hamster ขอแสดงความยินดี! ความสำเร็จใหม่:",
+        (_, "zh") => "健身仓鼠恭喜您！获得新成就：",
+        (1, _) => "Fit-Hamster says congrats! New achievement:",
+        (_, _) => "Fit-Hamster says congrats! New achievements:",
+    }
+}
+
+fn all_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Все достижения",
+        "th" => "ความสำเร็จทั้งหมด",
+        "zh" => "全部成就",
+        _ => "All achievements",
+    }
+}
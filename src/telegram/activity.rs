@@ -0,0 +1,173 @@
+use crate::db::models::NewActivityLog;
+use crate::db::queries;
+use crate::locales::messages::Messages;
+use crate::services::activity::{calories_burned, parse_activity, ActivityKind, ParsedActivity};
+use crate::telegram::handlers::get_user_today;
+use crate::telegram::stats::{remaining_budget, remaining_text};
+use teloxide::prelude::*;
+
+/// `/exercise` — активности за сегодня; `/exercise бег 30 мин` записывает новую.
+pub async fn handle_exercise_command(
+    bot: &Bot,
+    chat_id: ChatId,
+    text: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    let arg = text.trim_start_matches("/exercise").trim();
+    if !arg.is_empty() {
+        if !try_log_activity_text(bot, chat_id, arg, lang).await? {
+            bot.send_message(chat_id, usage_text(lang)).await?;
+        }
+        return Ok(());
+    }
+
+    let today = get_user_today(chat_id.0).await;
+    let logs = match queries::get_activity_logs_by_date(chat_id.0, today).await {
+        Ok(logs) => logs,
+        Err(e) => {
+            log::error!("Error in get_activity_logs_by_date: {}", e);
+            bot.send_message(chat_id, &Messages::get(lang).error).await?;
+            return Ok(());
+        }
+    };
+    if logs.is_empty() {
+        bot.send_message(chat_id, usage_text(lang)).await?;
+        return Ok(());
+    }
+
+    let mut reply = format!("🏅 {}:", today_title(lang));
+    for log in &logs {
+        let (emoji, label) = ActivityKind::find(&log.activity)
+            .map(|kind| (kind.emoji, kind.label(lang)))
+            .unwrap_or(("🏅", log.activity.as_str()));
+        reply.push_str(&format!(
+            "\n{} {} · {:.0} min — 🔥 {:.0} kcal",
+            emoji, label, log.duration_min, log.calories
+        ));
+    }
+    let total: f32 = logs.iter().map(|l| l.calories).sum();
+    reply.push_str(&format!("\n\n🔥 {}: {:.0} kcal", total_label(lang), total));
+    bot.send_message(chat_id, reply).await?;
+
+    Ok(())
+}
+
+/// Записывает активность, если сообщение на неё похоже («бег 5 км 30 мин»).
+/// Возвращает `false`, если это не активность — тогда сообщение разбирается как еда.
+pub async fn try_log_activity_text(
+    bot: &Bot,
+    chat_id: ChatId,
+    text: &str,
+    lang: &str,
+) -> ResponseResult<bool> {
+    let Some(activity) = parse_activity(text) else {
+        return Ok(false);
+    };
+
+    let weight = queries::get_user(chat_id.0)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|u| u.weight_kg);
+    let calories = calories_burned(&activity, weight);
+    let entry = NewActivityLog {
+        activity: activity.kind.key.to_string(),
+        description: text.trim().to_string(),
+        duration_min: activity.duration_min,
+        distance_km: activity.distance_km,
+        calories,
+    };
+    if let Err(e) = queries::add_activity_log(chat_id.0, &entry).await {
+        log::error!("Error in add_activity_log: {}", e);
+        bot.send_message(chat_id, &Messages::get(lang).error).await?;
+        return Ok(true);
+    }
+
+    bot.send_message(chat_id, confirmation_text(chat_id.0, &activity, calories, weight.is_none(), lang).await)
+        .await?;
+
+    Ok(true)
+}
+
+async fn confirmation_text(
+    chat_id: i64,
+    activity: &ParsedActivity,
+    calories: f32,
+    default_weight: bool,
+    lang: &str,
+) -> String {
+    let mut text = format!(
+        "✅ {} {} · {:.0} min",
+        activity.kind.emoji,
+        activity.kind.label(lang),
+        activity.duration_min
+    );
+    if let Some(distance) = activity.distance_km {
+        text.push_str(&format!(" · {:.1} km", distance));
+    }
+    text.push_str(&format!(" — 🔥 {:.0} kcal", calories));
+
+    let today = get_user_today(chat_id).await;
+    let (eaten, _, _, _) = queries::get_daily_summary(chat_id)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("get_daily_summary failed: {}", e);
+            (0.0, 0.0, 0.0, 0.0)
+        });
+    let burned = queries::get_daily_burned(chat_id, today)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("get_daily_burned failed: {}", e);
+            calories
+        });
+    text.push_str(&format!(
+        "\n📊 {}: 🍽 {:.0} · 🔥 {:.0} · = {:.0} kcal",
+        today_title(lang),
+        eaten,
+        burned,
+        eaten - burned
+    ));
+    if let Some(left) = remaining_budget(chat_id, eaten, burned).await {
+        text.push_str(&format!("\n{}", remaining_text(left, lang)));
+    }
+    if default_weight {
+        text.push_str(&format!("\n\nℹ️ {}", default_weight_note(lang)));
+    }
+    text
+}
+
+fn today_title(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Сегодня",
+        "th" => "วันนี้",
+        "zh" => "今天",
+        _ => "Today",
+    }
+}
+
+fn total_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Всего сожжено",
+        "th" => "เผาผลาญทั้งหมด",
+        "zh" => "总消耗",
+        _ => "Total burned",
+    }
+}
+
+fn default_weight_note(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Расчёт для веса 70 кг. Укажите свой: /weight 82.4",
+        "th" => "คำนวณจากน้ำหนัก 70 กก. ระบุน้ำหนักของคุณ: /weight 82.4",
+        "zh" => "按体重70公斤计算。设置您的体重：/weight 82.4",
+        _ => "Estimated for 70 kg. Set your weight: /weight 82.4",
+    }
+}
+
+fn usage_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "🏃 Опишите активность с длительностью или дистанцией, например: «бег 5 км 30 мин» или /exercise йога 1 ч",
+        "th" => "🏃 ระบุกิจกรรมพร้อมเวลาหรือระยะทาง เช่น «วิ่ง 5 กม 30 นาที» หรือ /exercise โยคะ 1 ชั่วโมง",
+        "zh" => "🏃 请描述运动及时长或距离，例如：«跑步 5 公里 30 分钟» 或 /exercise 瑜伽 1 小时",
+        _ => "🏃 Describe the activity with a duration or distance, e.g. \"run 5 km 30 min\" or /exercise yoga 1 h",
+    }
+}
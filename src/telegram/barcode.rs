@@ -0,0 +1,252 @@
+use crate::db::models::{FoodProvenance, Micronutrients, Product};
+use crate::db::queries;
+use crate::locales::messages::Messages;
+use crate::services::barcode::detect_barcode;
+use crate::services::log_time::TimeHint;
+use crate::services::nutrition::{FoodSummary, InputSource};
+use crate::telegram::food_log;
+use crate::telegram::state::{self, PendingInput};
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup},
+};
+
+/// Ищет штрихкод на фото и, если продукт есть в базе, предлагает выбрать порцию.
+/// Возвращает `false`, если код не найден или продукт неизвестен — тогда фото
+/// анализируется моделью как обычно.
+pub async fn try_handle_barcode(
+    bot: &Bot,
+    chat_id: ChatId,
+    url: &str,
+    lang: &str,
+) -> ResponseResult<bool> {
+    let bytes = match reqwest::get(url).await.and_then(|r| r.error_for_status()) {
+        Ok(response) => match response.bytes().await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                log::warn!("Failed to download photo for barcode scan: {}", e);
+                return Ok(false);
+            }
+        },
+        Err(e) => {
+            log::warn!("Failed to download photo for barcode scan: {}", e);
+            return Ok(false);
+        }
+    };
+
+    let code = tokio::task::spawn_blocking(move || detect_barcode(&bytes))
+        .await
+        .ok()
+        .flatten();
+    let Some(code) = code else {
+        return Ok(false);
+    };
+
+    let product = match queries::get_product(&code).await {
+        Ok(Some(product)) => product,
+        Ok(None) => {
+            log::info!("Barcode {} not found in product database", code);
+            return Ok(false);
+        }
+        Err(e) => {
+            log::error!("Error in get_product: {}", e);
+            return Ok(false);
+        }
+    };
+
+    let text = format!(
+        "🏷 {}\n{}: {:.0} kcal | 🥩 {:.1}P / 🧈 {:.1}F / 🍞 {:.1}C\n\n{}",
+        product.display_name(),
+        per_100g_label(lang),
+        product.calories,
+        product.proteins.unwrap_or(0.0),
+        product.fats.unwrap_or(0.0),
+        product.carbs.unwrap_or(0.0),
+        choose_portion_text(lang)
+    );
+    bot.send_message(chat_id, text)
+        .reply_markup(portion_keyboard(&product, lang))
+        .await?;
+
+    Ok(true)
+}
+
+fn portion_keyboard(product: &Product, lang: &str) -> InlineKeyboardMarkup {
+    let mut row = Vec::new();
+    if let Some(serving) = product.serving_size_g {
+        row.push(InlineKeyboardButton::callback(
+            format!("🍽 {} ({:.0} g)", serving_label(lang), serving),
+            format!("bc:{}:serving", product.barcode),
+        ));
+    }
+    row.push(InlineKeyboardButton::callback(
+        "100 g",
+        format!("bc:{}:100", product.barcode),
+    ));
+
+    InlineKeyboardMarkup::new(vec![
+        row,
+        vec![InlineKeyboardButton::callback(
+            enter_weight_label(lang),
+            format!("bc:{}:input", product.barcode),
+        )],
+    ])
+}
+
+/// Обрабатывает выбор порции (`bc:<код>:serving|100|input`).
+pub async fn handle_barcode_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    data: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    bot.answer_callback_query(q.id.clone()).await?;
+
+    let Some(message) = q.message.as_ref() else {
+        return Ok(());
+    };
+    let chat_id = message.chat().id;
+    let mut parts = data.split(':').skip(1);
+    let (Some(code), Some(action)) = (parts.next(), parts.next()) else {
+        return Ok(());
+    };
+
+    let product = match queries::get_product(code).await {
+        Ok(Some(product)) => product,
+        Ok(None) => return Ok(()),
+        Err(e) => {
+            log::error!("Error in get_product: {}", e);
+            bot.send_message(chat_id, &Messages::get(lang).error).await?;
+            return Ok(());
+        }
+    };
+
+    match action {
+        "input" => {
+            state::set_pending(chat_id.0, PendingInput::ProductWeight(product.barcode));
+            bot.send_message(chat_id, enter_weight_prompt(lang)).await?;
+        }
+        "serving" => {
+            let grams = product.serving_size_g.unwrap_or(100.0);
+            log_product(bot, chat_id, lang, &product, grams).await?;
+        }
+        grams => {
+            let grams = grams.parse::<f32>().unwrap_or(100.0);
+            log_product(bot, chat_id, lang, &product, grams).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Обрабатывает вес, введённый после кнопки «Указать вес».
+pub async fn handle_product_weight_input(
+    bot: &Bot,
+    chat_id: ChatId,
+    barcode: String,
+    text: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    let grams = text
+        .trim()
+        .trim_end_matches(|c: char| c.is_alphabetic() || c.is_whitespace())
+        .replace(',', ".")
+        .parse::<f32>()
+        .ok()
+        .filter(|g| *g > 0.0 && *g < 10_000.0);
+    let Some(grams) = grams else {
+        state::set_pending(chat_id.0, PendingInput::ProductWeight(barcode));
+        bot.send_message(chat_id, enter_weight_prompt(lang)).await?;
+        return Ok(());
+    };
+
+    match queries::get_product(&barcode).await {
+        Ok(Some(product)) => log_product(bot, chat_id, lang, &product, grams).await,
+        Ok(None) => Ok(()),
+        Err(e) => {
+            log::error!("Error in get_product: {}", e);
+            bot.send_message(chat_id, &Messages::get(lang).error).await?;
+            Ok(())
+        }
+    }
+}
+
+async fn log_product(
+    bot: &Bot,
+    chat_id: ChatId,
+    lang: &str,
+    product: &Product,
+    grams: f32,
+) -> ResponseResult<()> {
+    let (calories, proteins, fats, carbs) = product.nutrition_for(grams);
+    let summary = FoodSummary {
+        name: format!("{} — {:.0} g", product.display_name(), grams),
+        calories: Some(calories),
+        proteins: Some(proteins),
+        fats: Some(fats),
+        carbs: Some(carbs),
+        time_hint: TimeHint::default(),
+        micros: Micronutrients::default(),
+        model: None,
+    };
+    let suggestion = format!(
+        "🏷 {} · {:.0} g: {:.0} kcal | 🥩 {:.1}P / 🧈 {:.1}F / 🍞 {:.1}C",
+        product.display_name(),
+        grams,
+        calories,
+        proteins,
+        fats,
+        carbs
+    );
+
+    let provenance = FoodProvenance {
+        input_text: Some(product.barcode.clone()),
+        ..InputSource::Barcode.provenance()
+    };
+    food_log::log_food_and_reply(bot, chat_id, lang, &summary, &suggestion, provenance).await
+}
+
+fn per_100g_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "На 100 г",
+        "th" => "ต่อ 100 ก.",
+        "zh" => "每100克",
+        _ => "Per 100 g",
+    }
+}
+
+fn choose_portion_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Сколько вы съели?",
+        "th" => "คุณกินไปเท่าไหร่?",
+        "zh" => "您吃了多少？",
+        _ => "How much did you eat?",
+    }
+}
+
+fn serving_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Порция",
+        "th" => "หนึ่งหน่วยบริโภค",
+        "zh" => "一份",
+        _ => "Serving",
+    }
+}
+
+fn enter_weight_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "✏️ Указать вес",
+        "th" => "✏️ ระบุน้ำหนัก",
+        "zh" => "✏️ 输入重量",
+        _ => "✏️ Enter weight",
+    }
+}
+
+fn enter_weight_prompt(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "⚖️ Введите вес в граммах, например: 150",
+        "th" => "⚖️ ระบุน้ำหนักเป็นกรัม เช่น 150",
+        "zh" => "⚖️ 请输入克数，例如：150",
+        _ => "⚖️ Enter the weight in grams, e.g. 150",
+    }
+}
@@ -0,0 +1,275 @@
+use crate::db::queries;
+use crate::locales::messages::Messages;
+use crate::services::diary_import::{parse_csv, ParsedImport};
+use crate::services::meal::MealType;
+use crate::telegram::handlers::{file_url, get_user_tz};
+use crate::telegram::{achievements, state};
+use chrono::{Duration, Utc};
+use chrono_tz::Tz;
+use teloxide::{
+    prelude::*,
+    types::{Document, InlineKeyboardButton, InlineKeyboardMarkup},
+};
+
+/// Максимальный размер загружаемого файла.
+const MAX_FILE_BYTES: u32 = 10 * 1024 * 1024;
+/// Сколько нераспознанных строк и примеров записей показывать в превью.
+const PREVIEW_SKIPPED: usize = 10;
+const PREVIEW_EXAMPLES: usize = 3;
+
+/// `/import` — как выгрузить дневник из другого приложения.
+pub async fn handle_import_command(bot: &Bot, chat_id: ChatId, lang: &str) -> ResponseResult<()> {
+    bot.send_message(chat_id, instructions_text(lang)).await?;
+    Ok(())
+}
+
+/// Принимает CSV-файл с выгрузкой дневника и показывает превью импорта.
+/// Возвращает `false`, если документ не похож на CSV.
+pub async fn try_handle_document(
+    bot: &Bot,
+    chat_id: ChatId,
+    document: &Document,
+    lang: &str,
+) -> ResponseResult<bool> {
+    let is_csv = document
+        .file_name
+        .as_deref()
+        .is_some_and(|name| name.to_lowercase().ends_with(".csv"))
+        || document
+            .mime_type
+            .as_ref()
+            .is_some_and(|mime| mime.essence_str() == "text/csv");
+    if !is_csv {
+        return Ok(false);
+    }
+    if document.file.size > MAX_FILE_BYTES {
+        bot.send_message(chat_id, too_large_text(lang)).await?;
+        return Ok(true);
+    }
+
+    let file = bot.get_file(&document.file.id).send().await?;
+    let bytes = match download(&file_url(bot, &file.path)).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            log::error!("Error downloading import file: {}", e);
+            bot.send_message(chat_id, &Messages::get(lang).error).await?;
+            return Ok(true);
+        }
+    };
+
+    let tz = get_user_tz(chat_id.0).await;
+    let mut parsed = match parse_csv(&String::from_utf8_lossy(&bytes), tz, Utc::now(), lang) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            bot.send_message(chat_id, e.message(lang)).await?;
+            return Ok(true);
+        }
+    };
+
+    let duplicates = match parsed.date_range() {
+        Some((first, last)) => {
+            match queries::get_food_logs_between(chat_id.0, first, last + Duration::minutes(1)).await {
+                Ok(existing) => {
+                    let existing: Vec<_> = existing
+                        .into_iter()
+                        .map(|log| (log.created_at, log.food_name, log.calories))
+                        .collect();
+                    parsed.remove_existing(&existing)
+                }
+                Err(e) => {
+                    log::error!("Error loading existing logs for import: {}", e);
+                    bot.send_message(chat_id, &Messages::get(lang).error).await?;
+                    return Ok(true);
+                }
+            }
+        }
+        None => 0,
+    };
+
+    let text = preview_text(&parsed, duplicates, tz, lang);
+    if parsed.entries.is_empty() {
+        bot.send_message(chat_id, text).await?;
+        return Ok(true);
+    }
+
+    let import_id = state::set_pending_import(chat_id.0, parsed.entries);
+    bot.send_message(chat_id, text)
+        .reply_markup(InlineKeyboardMarkup::new(vec![vec![
+            InlineKeyboardButton::callback(format!("✅ {}", confirm_label(lang)), format!("imp:ok:{}", import_id)),
+            InlineKeyboardButton::callback(format!("✖️ {}", cancel_label(lang)), format!("imp:cancel:{}", import_id)),
+        ]]))
+        .await?;
+
+    Ok(true)
+}
+
+/// Кнопки превью: `imp:ok:<id>` — импортировать, `imp:cancel:<id>` — отменить.
+/// Кнопки старого или устаревшего превью не действуют.
+pub async fn handle_import_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    data: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    bot.answer_callback_query(q.id.clone()).await?;
+    let Some(message) = q.message.as_ref() else {
+        return Ok(());
+    };
+    let chat_id = message.chat().id;
+
+    let Some((action, import_id)) = data
+        .strip_prefix("imp:")
+        .and_then(|rest| rest.split_once(':'))
+        .and_then(|(action, id)| Some((action, id.parse::<u64>().ok()?)))
+    else {
+        bot.edit_message_text(chat_id, message.id(), expired_text(lang)).await?;
+        return Ok(());
+    };
+    let Some(entries) = state::take_pending_import(chat_id.0, import_id) else {
+        bot.edit_message_text(chat_id, message.id(), expired_text(lang)).await?;
+        return Ok(());
+    };
+    if action != "ok" {
+        bot.edit_message_text(chat_id, message.id(), cancelled_text(lang)).await?;
+        return Ok(());
+    }
+
+    queries::register_user(chat_id.0).await.ok();
+    match queries::add_food_logs(chat_id.0, &entries).await {
+        Ok(count) => {
+            bot.edit_message_text(chat_id, message.id(), imported_text(count, lang))
+                .await?;
+            achievements::announce_new_badges(bot, chat_id, lang).await;
+        }
+        Err(e) => {
+            log::error!("Error in add_food_logs: {}", e);
+            bot.edit_message_text(chat_id, message.id(), &Messages::get(lang).error)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn download(url: &str) -> Result<Vec<u8>, reqwest::Error> {
+    let response = reqwest::get(url).await?.error_for_status()?;
+    Ok(response.bytes().await?.to_vec())
+}
+
+fn preview_text(parsed: &ParsedImport, duplicates: usize, tz: Tz, lang: &str) -> String {
+    let labels = match lang {
+        "ru" => ["Импорт из", "Готово к импорту", "Уже есть в дневнике (пропущены)", "Не удалось прочитать строк", "строка", "Например"],
+        "th" => ["นำเข้าจาก", "พร้อมนำเข้า", "มีอยู่ในบันทึกแล้ว (ข้าม)", "แถวที่อ่านไม่ได้", "แถว", "ตัวอย่าง"],
+        "zh" => ["导入来源", "可导入", "日记中已存在（跳过）", "无法读取的行", "第", "示例"],
+        _ => ["Import from", "Ready to import", "Already in your diary (skipped)", "Rows that couldn't be read", "line", "For example"],
+    };
+
+    let mut text = format!("📥 {} {}\n\n✅ {}: {}", labels[0], parsed.source.label(), labels[1], parsed.entries.len());
+    if let Some((first, last)) = parsed.date_range() {
+        text.push_str(&format!(
+            "\n📅 {} – {}",
+            first.with_timezone(&tz).format("%d.%m.%Y"),
+            last.with_timezone(&tz).format("%d.%m.%Y")
+        ));
+    }
+    if duplicates > 0 {
+        text.push_str(&format!("\n♻️ {}: {}", labels[2], duplicates));
+    }
+    if !parsed.skipped.is_empty() {
+        text.push_str(&format!("\n⚠️ {}: {}", labels[3], parsed.skipped.len()));
+        for (line, reason) in parsed.skipped.iter().take(PREVIEW_SKIPPED) {
+            text.push_str(&format!("\n  • {} {}: {}", labels[4], line, reason));
+        }
+        if parsed.skipped.len() > PREVIEW_SKIPPED {
+            text.push_str("\n  • …");
+        }
+    }
+    if !parsed.entries.is_empty() {
+        text.push_str(&format!("\n\n{}:", labels[5]));
+        for entry in parsed.entries.iter().take(PREVIEW_EXAMPLES) {
+            let meal = MealType::parse(&entry.meal_type).unwrap_or(MealType::Snack);
+            text.push_str(&format!(
+                "\n{} {} {} — {:.0} kcal",
+                entry.eaten_at.with_timezone(&tz).format("%d.%m.%Y %H:%M"),
+                meal.emoji(),
+                entry.food_name,
+                entry.calories.unwrap_or(0.0)
+            ));
+        }
+    } else {
+        text.push_str(&format!("\n\n{}", nothing_text(lang)));
+    }
+    text
+}
+
+fn instructions_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "📥 Перенесите историю из другого приложения: выгрузите дневник в CSV и отправьте файл сюда.\n\n• MyFitnessPal: Настройки → Экспорт данных (файл Nutrition Summary)\n• Cronometer: Settings → Account → Export Data → Food & Recipe Entries\n• FatSecret: Дневник питания → Отчёты → Экспорт в CSV\n\nПеред импортом я покажу, что нашлось, — записи, уже сохранённые в дневнике, повторно не добавятся.",
+        "th" => "📥 ย้ายประวัติจากแอปอื่น: ส่งออกบันทึกเป็น CSV แล้วส่งไฟล์มาที่นี่\n\n• MyFitnessPal: Settings → Export Data (ไฟล์ Nutrition Summary)\n• Cronometer: Settings → Account → Export Data → Food & Recipe Entries\n• FatSecret: Food Diary → Reports → Export to CSV\n\nก่อนนำเข้า บอทจะแสดงตัวอย่าง และจะไม่เพิ่มรายการที่มีอยู่แล้วซ้ำ",
+        "zh" => "📥 从其他应用迁移历史记录：将日记导出为 CSV，然后把文件发送到这里。\n\n• MyFitnessPal：Settings → Export Data（Nutrition Summary 文件）\n• Cronometer：Settings → Account → Export Data → Food & Recipe Entries\n• FatSecret：Food Diary → Reports → Export to CSV\n\n导入前会先显示预览，日记中已有的记录不会重复添加。",
+        _ => "📥 Bring your history from another app: export your diary as CSV and send the file here.\n\n• MyFitnessPal: Settings → Export Data (Nutrition Summary file)\n• Cronometer: Settings → Account → Export Data → Food & Recipe Entries\n• FatSecret: Food Diary → Reports → Export to CSV\n\nYou'll see a preview before anything is imported, and entries already in your diary won't be added twice.",
+    }
+}
+
+fn confirm_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Импортировать",
+        "th" => "นำเข้า",
+        "zh" => "导入",
+        _ => "Import",
+    }
+}
+
+fn cancel_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Отмена",
+        "th" => "ยกเลิก",
+        "zh" => "取消",
+        _ => "Cancel",
+    }
+}
+
+fn imported_text(count: u64, lang: &str) -> String {
+    match lang {
+        "ru" => format!("✅ Импортировано записей: {}. Посмотреть: /history", count),
+        "th" => format!("✅ นำเข้าแล้ว {} รายการ ดู: /history", count),
+        "zh" => format!("✅ 已导入 {} 条记录。查看：/history", count),
+        _ => format!("✅ Imported {} entries. Take a look: /history", count),
+    }
+}
+
+fn nothing_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Нечего импортировать.",
+        "th" => "ไม่มีรายการให้นำเข้า",
+        "zh" => "没有可导入的记录。",
+        _ => "Nothing to import.",
+    }
+}
+
+fn cancelled_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "✖️ Импорт отменён.",
+        "th" => "✖️ ยกเลิกการนำเข้าแล้ว",
+        "zh" => "✖️ 已取消导入。",
+        _ => "✖️ Import cancelled.",
+    }
+}
+
+fn expired_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "⌛ Превью устарело — отправьте файл ещё раз.",
+        "th" => "⌛ ตัวอย่างหมดอายุแล้ว กรุณาส่งไฟล์อีกครั้ง",
+        "zh" => "⌛ 预览已过期，请重新发送文件。",
+        _ => "⌛ This preview has expired — please send the file again.",
+    }
+}
+
+fn too_large_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "📄 Файл слишком большой (не более 10 МБ).",
+        "th" => "📄 ไฟล์ใหญ่เกินไป (ไม่เกิน 10 MB)",
+        "zh" => "📄 文件过大（不超过 10 MB）。",
+        _ => "📄 The file is too large (10 MB max).",
+    }
+}
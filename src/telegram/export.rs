@@ -0,0 +1,198 @@
+use crate::db::queries;
+use crate::locales::messages::Messages;
+use crate::services::export::{Diary, ExportFormat};
+use crate::services::log_time::local_to_utc;
+use crate::telegram::handlers::get_user_tz;
+use chrono::{DateTime, Days, Duration, NaiveDate, NaiveTime, Utc};
+use teloxide::{prelude::*, types::InputFile};
+
+/// Самый длинный период, заданный числом дней; весь дневник — `/export` без периода.
+const MAX_RANGE_DAYS: u64 = 3660;
+
+/// `/export [csv|json] [YYYY-MM-DD YYYY-MM-DD | <дней>]` — дневник файлом;
+/// без периода — за всё время.
+pub async fn handle_export_command(
+    bot: &Bot,
+    chat_id: ChatId,
+    text: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    let tz = get_user_tz(chat_id.0).await;
+    let Some((format, range)) = parse_args(text, Utc::now().with_timezone(&tz).date_naive()) else {
+        bot.send_message(chat_id, usage_text(lang)).await?;
+        return Ok(());
+    };
+
+    let (since, until) = match range {
+        Some((from, to)) => (
+            local_to_utc(from, NaiveTime::MIN, tz).unwrap_or(DateTime::UNIX_EPOCH),
+            to.checked_add_days(Days::new(1))
+                .and_then(|next| local_to_utc(next, NaiveTime::MIN, tz))
+                .unwrap_or_else(|| Utc::now() + Duration::days(1)),
+        ),
+        None => (DateTime::UNIX_EPOCH, Utc::now() + Duration::days(1)),
+    };
+
+    let loaded = async {
+        let foods = queries::get_food_logs_between(chat_id.0, since, until).await?;
+        let weights = queries::get_weight_logs_between(chat_id.0, since, until).await?;
+        let activities = queries::get_activity_logs_between(chat_id.0, since, until).await?;
+        Ok::<_, sqlx::Error>((foods, weights, activities))
+    };
+    let (foods, weights, activities) = match loaded.await {
+        Ok(data) => data,
+        Err(e) => {
+            log::error!("Error loading diary for export: {}", e);
+            bot.send_message(chat_id, &Messages::get(lang).error).await?;
+            return Ok(());
+        }
+    };
+
+    let diary = Diary::new(tz, range, &foods, &weights, &activities);
+    if diary.is_empty() {
+        bot.send_message(chat_id, empty_text(lang)).await?;
+        return Ok(());
+    }
+    let bytes = match diary.to_bytes(format) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            log::error!("Error serializing diary export: {}", e);
+            bot.send_message(chat_id, &Messages::get(lang).error).await?;
+            return Ok(());
+        }
+    };
+
+    let period = match range {
+        Some((from, to)) => format!("{}_{}", from, to),
+        None => "all".to_string(),
+    };
+    let file_name = format!("kalorik-diary-{}.{}", period, format.extension());
+    bot.send_document(chat_id, InputFile::memory(bytes).file_name(file_name))
+        .caption(caption(&diary, lang))
+        .await?;
+
+    Ok(())
+}
+
+/// Разбирает формат и период. `None` — непонятные аргументы.
+fn parse_args(text: &str, today: NaiveDate) -> Option<(ExportFormat, Option<(NaiveDate, NaiveDate)>)> {
+    let mut format = ExportFormat::Csv;
+    let mut dates = Vec::new();
+    let mut days = None;
+    for arg in text.trim_start_matches("/export").split_whitespace() {
+        if let Some(parsed) = ExportFormat::parse(arg) {
+            format = parsed;
+        } else if let Ok(date) = NaiveDate::parse_from_str(arg, "%Y-%m-%d") {
+            dates.push(date);
+        } else if let Ok(count) = arg.parse::<u64>()
+            && (1..=MAX_RANGE_DAYS).contains(&count)
+        {
+            days = Some(count);
+        } else {
+            return None;
+        }
+    }
+
+    let range = match (dates.as_slice(), days) {
+        ([], None) => None,
+        ([], Some(days)) => Some((today.checked_sub_days(Days::new(days - 1))?, today)),
+        ([from, to], None) if from <= to => Some((*from, *to)),
+        _ => return None,
+    };
+    Some((format, range))
+}
+
+fn caption(diary: &Diary, lang: &str) -> String {
+    let labels = match lang {
+        "ru" => ["Дневник питания", "записей о еде", "взвешиваний", "тренировок", "за всё время"],
+        "th" => ["บันทึกอาหาร", "รายการอาหาร", "การชั่งน้ำหนัก", "การออกกำลังกาย", "ทั้งหมด"],
+        "zh" => ["饮食日记", "条饮食记录", "次称重", "次运动", "全部时间"],
+        _ => ["Food diary", "meals", "weigh-ins", "activities", "all time"],
+    };
+    let period = match (diary.from, diary.to) {
+        (Some(from), Some(to)) => format!("{} – {}", from.format("%d.%m.%Y"), to.format("%d.%m.%Y")),
+        _ => labels[4].to_string(),
+    };
+    format!(
+        "📤 {} ({})\n🍽 {} {} · ⚖️ {} {} · 🏃 {} {}\n🕒 {}",
+        labels[0],
+        period,
+        diary.food.len(),
+        labels[1],
+        diary.weights.len(),
+        labels[2],
+        diary.activities.len(),
+        labels[3],
+        diary.timezone
+    )
+}
+
+fn empty_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "📭 За этот период нет записей.",
+        "th" => "📭 ไม่มีบันทึกในช่วงเวลานี้",
+        "zh" => "📭 该时间段内没有记录。",
+        _ => "📭 No entries for this period.",
+    }
+}
+
+fn usage_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "/export — дневник за всё время в CSV\n/export json — в формате JSON\n/export 30 — за последние 30 дней\n/export 2026-01-01 2026-01-31 — за период",
+        "th" => "/export — บันทึกทั้งหมดเป็น CSV\n/export json — เป็น JSON\n/export 30 — 30 วันล่าสุด\n/export 2026-01-01 2026-01-31 — ตามช่วงวันที่",
+        "zh" => "/export — 导出全部日记（CSV）\n/export json — 导出为 JSON\n/export 30 — 最近30天\n/export 2026-01-01 2026-01-31 — 指定时间段",
+        _ => "/export — your whole diary as CSV\n/export json — as JSON\n/export 30 — the last 30 days\n/export 2026-01-01 2026-01-31 — a date range",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn format_and_whole_diary() {
+        let today = date(2026, 10, 19);
+        assert_eq!(parse_args("/export", today), Some((ExportFormat::Csv, None)));
+        assert_eq!(parse_args("/export json", today), Some((ExportFormat::Json, None)));
+        assert_eq!(parse_args("/export csv", today), Some((ExportFormat::Csv, None)));
+        assert_eq!(parse_args("/export xml", today), None);
+    }
+
+    #[test]
+    fn last_days() {
+        let today = date(2026, 10, 19);
+        assert_eq!(
+            parse_args("/export 30 json", today),
+            Some((ExportFormat::Json, Some((date(2026, 9, 20), today))))
+        );
+        assert_eq!(parse_args("/export 1", today), Some((ExportFormat::Csv, Some((today, today)))));
+        let longest = parse_args(&format!("/export {}", MAX_RANGE_DAYS), today);
+        assert_eq!(
+            longest.and_then(|(_, range)| range).map(|(from, to)| (to - from).num_days() + 1),
+            Some(MAX_RANGE_DAYS as i64)
+        );
+        assert_eq!(parse_args(&format!("/export {}", MAX_RANGE_DAYS + 1), today), None);
+        assert_eq!(parse_args("/export 0", today), None);
+        assert_eq!(parse_args("/export 99999999999999999999", today), None);
+    }
+
+    #[test]
+    fn date_range() {
+        let today = date(2026, 10, 19);
+        assert_eq!(
+            parse_args("/export 2026-01-01 2026-01-31", today),
+            Some((ExportFormat::Csv, Some((date(2026, 1, 1), date(2026, 1, 31)))))
+        );
+        assert_eq!(
+            parse_args("/export json 2026-03-05 2026-03-05", today),
+            Some((ExportFormat::Json, Some((date(2026, 3, 5), date(2026, 3, 5)))))
+        );
+        assert_eq!(parse_args("/export 2026-01-31 2026-01-01", today), None);
+        assert_eq!(parse_args("/export 2026-01-01", today), None);
+        assert_eq!(parse_args("/export 2026-01-01 2026-01-31 7", today), None);
+    }
+}
@@ -0,0 +1,386 @@
+use crate::db::models::Fast;
+use crate::db::queries;
+use crate::locales::messages::Messages;
+use crate::services::fasting::{
+    format_duration, is_completed, streak, target, Protocol, DEFAULT_PROTOCOL,
+};
+use crate::services::water::progress_bar;
+use crate::telegram::handlers::{get_user_lang, get_user_tz};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use teloxide::{prelude::*, utils::markdown};
+
+/// За сколько дней показывать историю голоданий и считать серию.
+const FASTING_HISTORY_DAYS: i32 = 60;
+
+/// Сколько последних голоданий выводить в `/fast`.
+const HISTORY_LIMIT: usize = 7;
+
+/// `/fast start [16:8]`, `/fast stop`, `/fast protocol 18:6`; без аргументов — статус и история.
+pub async fn handle_fast_command(
+    bot: &Bot,
+    chat_id: ChatId,
+    text: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    let arg = text.trim_start_matches("/fast").trim();
+    let (action, rest) = arg.split_once(' ').unwrap_or((arg, ""));
+
+    match action {
+        "start" => start_fast(bot, chat_id, rest.trim(), lang).await,
+        "stop" | "end" => stop_fast(bot, chat_id, lang).await,
+        "protocol" | "plan" => set_protocol(bot, chat_id, rest.trim(), lang).await,
+        "" | "status" => show_status(bot, chat_id, lang).await,
+        _ => {
+            bot.send_message(chat_id, usage_text(lang)).await?;
+            Ok(())
+        }
+    }
+}
+
+async fn start_fast(bot: &Bot, chat_id: ChatId, arg: &str, lang: &str) -> ResponseResult<()> {
+    let protocol = if arg.is_empty() {
+        let saved = queries::get_fasting_protocol(chat_id.0).await.ok().flatten();
+        saved
+            .as_deref()
+            .and_then(Protocol::parse)
+            .or_else(|| Protocol::parse(DEFAULT_PROTOCOL))
+    } else {
+        Protocol::parse(arg)
+    };
+    let Some(protocol) = protocol else {
+        bot.send_message(chat_id, usage_text(lang)).await?;
+        return Ok(());
+    };
+
+    let now = Utc::now();
+    queries::register_user(chat_id.0).await.ok();
+    match queries::start_fast(chat_id.0, &protocol.name, protocol.target_hours, now).await {
+        Ok(Some(_)) => {
+            let tz = get_user_tz(chat_id.0).await;
+            let goal_at = now + chrono::Duration::minutes((protocol.target_hours * 60.0).round() as i64);
+            bot.send_message(
+                chat_id,
+                format!(
+                    "⏳ {} ({})\n🎯 {}: {}",
+                    started_label(lang),
+                    protocol.name,
+                    goal_label(lang),
+                    format_local(goal_at, tz)
+                ),
+            )
+            .await?;
+        }
+        Ok(None) => {
+            bot.send_message(chat_id, already_fasting_text(lang)).await?;
+        }
+        Err(e) => {
+            log::error!("Error in start_fast: {}", e);
+            bot.send_message(chat_id, &Messages::get(lang).error).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn stop_fast(bot: &Bot, chat_id: ChatId, lang: &str) -> ResponseResult<()> {
+    match queries::end_fast(chat_id.0, Utc::now()).await {
+        Ok(Some(fast)) => {
+            let mut reply = finished_text(&fast, lang);
+            let current = current_streak(chat_id.0).await;
+            if current > 0 {
+                reply.push_str(&format!("\n🔥 {}: {}", streak_label(lang), current));
+            }
+            bot.send_message(chat_id, reply).await?;
+        }
+        Ok(None) => {
+            bot.send_message(chat_id, not_fasting_text(lang)).await?;
+        }
+        Err(e) => {
+            log::error!("Error in end_fast: {}", e);
+            bot.send_message(chat_id, &Messages::get(lang).error).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn set_protocol(bot: &Bot, chat_id: ChatId, arg: &str, lang: &str) -> ResponseResult<()> {
+    let Some(protocol) = Protocol::parse(arg) else {
+        bot.send_message(chat_id, usage_text(lang)).await?;
+        return Ok(());
+    };
+
+    queries::register_user(chat_id.0).await.ok();
+    if let Err(e) = queries::set_fasting_protocol(chat_id.0, &protocol.name).await {
+        log::error!("Error in set_fasting_protocol: {}", e);
+        bot.send_message(chat_id, &Messages::get(lang).error).await?;
+        return Ok(());
+    }
+    bot.send_message(
+        chat_id,
+        format!("✅ {}: {}", protocol_label(lang), protocol.name),
+    )
+    .await?;
+
+    Ok(())
+}
+
+async fn show_status(bot: &Bot, chat_id: ChatId, lang: &str) -> ResponseResult<()> {
+    let active = match queries::get_active_fast(chat_id.0).await {
+        Ok(active) => active,
+        Err(e) => {
+            log::error!("Error in get_active_fast: {}", e);
+            bot.send_message(chat_id, &Messages::get(lang).error).await?;
+            return Ok(());
+        }
+    };
+    let history = queries::get_finished_fasts(chat_id.0, FASTING_HISTORY_DAYS)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("get_finished_fasts failed: {}", e);
+            Vec::new()
+        });
+    let protocol = queries::get_fasting_protocol(chat_id.0)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| DEFAULT_PROTOCOL.to_string());
+    let tz = get_user_tz(chat_id.0).await;
+
+    let mut reply = match &active {
+        Some(fast) => {
+            let elapsed = Utc::now() - fast.started_at;
+            format!(
+                "⏳ {} ({}): {} / {}\n{}\n🎯 {}: {}",
+                fasting_label(lang),
+                fast.protocol,
+                format_duration(elapsed, lang),
+                format_duration(target(fast), lang),
+                progress_bar(elapsed.num_minutes(), target(fast).num_minutes()),
+                goal_label(lang),
+                format_local(fast.started_at + target(fast), tz)
+            )
+        }
+        None => format!(
+            "{}\n{}: {}",
+            not_fasting_text(lang),
+            protocol_label(lang),
+            protocol
+        ),
+    };
+
+    if !history.is_empty() {
+        reply.push_str(&format!("\n\n📜 {}:", history_label(lang)));
+        for fast in history.iter().take(HISTORY_LIMIT) {
+            let Some(ended_at) = fast.ended_at else {
+                continue;
+            };
+            reply.push_str(&format!(
+                "\n{} {} — {} ({})",
+                if is_completed(fast) { "✅" } else { "▫️" },
+                fast.started_at.with_timezone(&tz).format("%d.%m"),
+                format_duration(ended_at - fast.started_at, lang),
+                fast.protocol
+            ));
+        }
+        let current = streak(&history, Utc::now().with_timezone(&tz).date_naive(), tz);
+        reply.push_str(&format!("\n\n🔥 {}: {}", streak_label(lang), current));
+    }
+
+    bot.send_message(chat_id, reply).await?;
+
+    Ok(())
+}
+
+/// Завершает идущее голодание при записи еды. Возвращает строку для подтверждения записи.
+pub async fn end_fast_on_meal(chat_id: i64, eaten_at: DateTime<Utc>, lang: &str) -> Option<String> {
+    match queries::end_fast(chat_id, eaten_at).await {
+        Ok(fast) => fast.map(|fast| finished_text(&fast, lang)),
+        Err(e) => {
+            log::warn!("end_fast failed: {}", e);
+            None
+        }
+    }
+}
+
+/// Строка о голодании для `/stats` (MarkdownV2): текущий прогресс или серия.
+/// Подписи экранируются — в них бывают зарезервированные символы («ต่อเนื่อง (วัน)»).
+pub async fn stats_line(chat_id: i64, lang: &str) -> Option<String> {
+    if let Ok(Some(fast)) = queries::get_active_fast(chat_id).await {
+        let elapsed = Utc::now() - fast.started_at;
+        return Some(format!(
+            "⏳ {}: `{}` / `{}` {}",
+            markdown::escape(fasting_label(lang)),
+            format_duration(elapsed, lang),
+            format_duration(target(&fast), lang),
+            progress_bar(elapsed.num_minutes(), target(&fast).num_minutes())
+        ));
+    }
+
+    let current = current_streak(chat_id).await;
+    (current > 0).then(|| format!("🔥 {}: `{}`", markdown::escape(streak_label(lang)), current))
+}
+
+/// Сообщает о голоданиях, которые достигли цели. Вызывается планировщиком.
+pub async fn notify_due_fasts(bot: &Bot) {
+    let fasts = match queries::get_due_fasts().await {
+        Ok(fasts) => fasts,
+        Err(e) => {
+            log::error!("Error in get_due_fasts: {}", e);
+            return;
+        }
+    };
+
+    for fast in fasts {
+        let lang = get_user_lang(fast.chat_id).await;
+        let text = format!(
+            "🎉 {} ({}, {})\n{}",
+            goal_reached_text(&lang),
+            fast.protocol,
+            format_duration(target(&fast), &lang),
+            goal_reached_hint(&lang)
+        );
+        if let Err(e) = bot.send_message(ChatId(fast.chat_id), text).await {
+            log::warn!("Failed to notify fast {}: {}", fast.id, e);
+        }
+        // Отмечаем даже при ошибке отправки (например, бот заблокирован), чтобы не повторять каждую минуту
+        if let Err(e) = queries::mark_fast_notified(fast.id).await {
+            log::error!("Error in mark_fast_notified: {}", e);
+        }
+    }
+}
+
+async fn current_streak(chat_id: i64) -> u32 {
+    let history = queries::get_finished_fasts(chat_id, FASTING_HISTORY_DAYS)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("get_finished_fasts failed: {}", e);
+            Vec::new()
+        });
+    let tz = get_user_tz(chat_id).await;
+    streak(&history, Utc::now().with_timezone(&tz).date_naive(), tz)
+}
+
+fn finished_text(fast: &Fast, lang: &str) -> String {
+    let ended_at = fast.ended_at.unwrap_or_else(Utc::now);
+    format!(
+        "{} {}: {} / {}",
+        if is_completed(fast) { "✅" } else { "⏹" },
+        finished_label(lang),
+        format_duration(ended_at - fast.started_at, lang),
+        format_duration(target(fast), lang)
+    )
+}
+
+fn format_local(at: DateTime<Utc>, tz: Tz) -> String {
+    at.with_timezone(&tz).format("%d.%m %H:%M").to_string()
+}
+
+fn started_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Голодание началось",
+        "th" => "เริ่มอดอาหารแล้ว",
+        "zh" => "断食开始",
+        _ => "Fast started",
+    }
+}
+
+fn fasting_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Голодание",
+        "th" => "การอดอาหาร",
+        "zh" => "断食",
+        _ => "Fasting",
+    }
+}
+
+fn finished_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Голодание завершено",
+        "th" => "สิ้นสุดการอดอาหาร",
+        "zh" => "断食结束",
+        _ => "Fast finished",
+    }
+}
+
+fn goal_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Цель",
+        "th" => "เป้าหมาย",
+        "zh" => "目标",
+        _ => "Goal",
+    }
+}
+
+fn protocol_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Протокол",
+        "th" => "รูปแบบ",
+        "zh" => "方案",
+        _ => "Protocol",
+    }
+}
+
+fn history_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Последние голодания",
+        "th" => "การอดอาหารล่าสุด",
+        "zh" => "最近的断食",
+        _ => "Recent fasts",
+    }
+}
+
+fn streak_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Серия, дней",
+        "th" => "ต่อเนื่อง (วัน)",
+        "zh" => "连续天数",
+        _ => "Streak, days",
+    }
+}
+
+fn already_fasting_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "⏳ Голодание уже идёт. Посмотреть прогресс: /fast, завершить: /fast stop",
+        "th" => "⏳ คุณกำลังอดอาหารอยู่แล้ว ดูความคืบหน้า: /fast หยุด: /fast stop",
+        "zh" => "⏳ 断食已在进行中。查看进度：/fast，结束：/fast stop",
+        _ => "⏳ You are already fasting. Progress: /fast, finish: /fast stop",
+    }
+}
+
+fn not_fasting_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "🍽 Сейчас голодание не идёт. Начать: /fast start",
+        "th" => "🍽 ตอนนี้ไม่ได้อดอาหาร เริ่ม: /fast start",
+        "zh" => "🍽 当前没有进行断食。开始：/fast start",
+        _ => "🍽 You are not fasting right now. Start: /fast start",
+    }
+}
+
+fn goal_reached_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Цель голодания достигнута!",
+        "th" => "ถึงเป้าหมายการอดอาหารแล้ว!",
+        "zh" => "已达到断食目标！",
+        _ => "You reached your fasting goal!",
+    }
+}
+
+fn goal_reached_hint(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Голодание завершится, когда вы запишете еду, или командой /fast stop.",
+        "th" => "การอดอาหารจะสิ้นสุดเมื่อคุณบันทึกอาหาร หรือใช้ /fast stop",
+        "zh" => "记录饮食或发送 /fast stop 即可结束断食。",
+        _ => "The fast ends when you log a meal, or with /fast stop.",
+    }
+}
+
+fn usage_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "⏳ Интервальное голодание:\n/fast start — начать (или /fast start 18:6)\n/fast stop — завершить\n/fast protocol 16:8 — протокол по умолчанию (12:12, 14:10, 16:8, 18:6, 20:4, omad или часы, например 36h)\n/fast — прогресс, история и серия",
+        "th" => "⏳ การอดอาหารแบบ IF:\n/fast start — เริ่ม (หรือ /fast start 18:6)\n/fast stop — หยุด\n/fast protocol 16:8 — รูปแบบเริ่มต้น (12:12, 14:10, 16:8, 18:6, 20:4, omad หรือจำนวนชั่วโมง เช่น 36h)\n/fast — ความคืบหน้า ประวัติ และสถิติต่อเนื่อง",
+        "zh" => "⏳ 间歇性断食：\n/fast start — 开始（或 /fast start 18:6）\n/fast stop — 结束\n/fast protocol 16:8 — 默认方案（12:12、14:10、16:8、18:6、20:4、omad 或小时数，如 36h）\n/fast — 进度、历史和连续天数",
+        _ => "⏳ Intermittent fasting:\n/fast start — start (or /fast start 18:6)\n/fast stop — finish\n/fast protocol 16:8 — default protocol (12:12, 14:10, 16:8, 18:6, 20:4, omad or hours, e.g. 36h)\n/fast — progress, history and streak",
+    }
+}
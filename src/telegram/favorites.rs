@@ -0,0 +1,250 @@
+use crate::db::models::Micronutrients;
+use crate::db::queries;
+use crate::locales::messages::Messages;
+use crate::services::log_time::TimeHint;
+use crate::services::nutrition::{FoodSummary, InputSource};
+use crate::telegram::food_log;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup},
+};
+
+/// Сколько частых блюд показывать в `/favorites`.
+const FREQUENT_MEALS_LIMIT: i64 = 5;
+
+/// Максимальная длина названия блюда на кнопке.
+const BUTTON_NAME_LEN: usize = 32;
+
+/// `/favorites` — избранное и часто повторяемые блюда для записи в одно нажатие.
+pub async fn handle_favorites_command(bot: &Bot, chat_id: ChatId, lang: &str) -> ResponseResult<()> {
+    match render_favorites(chat_id.0, lang).await {
+        Ok(Some((text, markup))) => {
+            bot.send_message(chat_id, text).reply_markup(markup).await?;
+        }
+        Ok(None) => {
+            bot.send_message(chat_id, empty_text(lang)).await?;
+        }
+        Err(e) => {
+            log::error!("Error loading favorites for {}: {}", chat_id, e);
+            bot.send_message(chat_id, &Messages::get(lang).error).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Обрабатывает кнопки `fav:add:<log_id>`, `fav:log:<id>`, `fav:del:<id>` и `freq:<log_id>`.
+pub async fn handle_favorites_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    data: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    let Some(message) = q.message.as_ref() else {
+        bot.answer_callback_query(q.id.clone()).await?;
+        return Ok(());
+    };
+    let chat_id = message.chat().id;
+    let parts: Vec<&str> = data.split(':').collect();
+    let messages = Messages::get(lang);
+
+    match parts.as_slice() {
+        ["fav", "add", log_id] => {
+            let Ok(log_id) = log_id.parse::<i32>() else {
+                bot.answer_callback_query(q.id.clone()).await?;
+                return Ok(());
+            };
+            let text = match queries::add_favorite_from_log(chat_id.0, log_id).await {
+                Ok(Some(_)) => added_text(lang).to_string(),
+                Ok(None) => messages.error.clone(),
+                Err(e) => {
+                    log::error!("Error in add_favorite_from_log: {}", e);
+                    messages.error.clone()
+                }
+            };
+            bot.answer_callback_query(q.id.clone()).text(text).await?;
+        }
+        ["fav", "log", id] => {
+            bot.answer_callback_query(q.id.clone()).await?;
+            let Ok(id) = id.parse::<i32>() else {
+                return Ok(());
+            };
+            match queries::get_favorite(chat_id.0, id).await {
+                Ok(Some(favorite)) => {
+                    let micros = queries::get_favorite_micronutrients(chat_id.0, id)
+                        .await
+                        .unwrap_or_else(|e| {
+                            log::warn!("get_favorite_micronutrients failed: {}", e);
+                            Micronutrients::default()
+                        });
+                    let summary = FoodSummary {
+                        name: favorite.name,
+                        calories: favorite.calories,
+                        proteins: favorite.proteins,
+                        fats: favorite.fats,
+                        carbs: favorite.carbs,
+                        time_hint: TimeHint::default(),
+                        micros,
+                        model: None,
+                    };
+                    relog(bot, chat_id, lang, summary).await?;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    log::error!("Error in get_favorite: {}", e);
+                    bot.send_message(chat_id, &messages.error).await?;
+                }
+            }
+        }
+        ["fav", "del", id] => {
+            bot.answer_callback_query(q.id.clone()).await?;
+            let Ok(id) = id.parse::<i32>() else {
+                return Ok(());
+            };
+            if let Err(e) = queries::delete_favorite(chat_id.0, id).await {
+                log::error!("Error in delete_favorite: {}", e);
+                bot.send_message(chat_id, &messages.error).await?;
+                return Ok(());
+            }
+            match render_favorites(chat_id.0, lang).await {
+                Ok(Some((text, markup))) => {
+                    bot.edit_message_text(chat_id, message.id(), text)
+                        .reply_markup(markup)
+                        .await?;
+                }
+                Ok(None) => {
+                    bot.edit_message_text(chat_id, message.id(), empty_text(lang))
+                        .await?;
+                }
+                Err(e) => log::error!("Error loading favorites for {}: {}", chat_id, e),
+            }
+        }
+        ["freq", log_id] => {
+            bot.answer_callback_query(q.id.clone()).await?;
+            let Ok(log_id) = log_id.parse::<i32>() else {
+                return Ok(());
+            };
+            match queries::get_food_log(chat_id.0, log_id).await {
+                Ok(Some(entry)) => {
+                    let micros = queries::get_food_log_micronutrients(chat_id.0, log_id)
+                        .await
+                        .unwrap_or_else(|e| {
+                            log::warn!("get_food_log_micronutrients failed: {}", e);
+                            Micronutrients::default()
+                        });
+                    let summary = FoodSummary {
+                        name: entry.food_name,
+                        calories: entry.calories,
+                        proteins: entry.proteins,
+                        fats: entry.fats,
+                        carbs: entry.carbs,
+                        time_hint: TimeHint::default(),
+                        micros,
+                        model: None,
+                    };
+                    relog(bot, chat_id, lang, summary).await?;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    log::error!("Error in get_food_log: {}", e);
+                    bot.send_message(chat_id, &messages.error).await?;
+                }
+            }
+        }
+        _ => {
+            log::warn!("Unknown favorites callback: {}", data);
+            bot.answer_callback_query(q.id.clone()).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Записывает сохранённое блюдо без обращения к модели.
+async fn relog(bot: &Bot, chat_id: ChatId, lang: &str, summary: FoodSummary) -> ResponseResult<()> {
+    let suggestion = format!(
+        "🔁 {} — {:.0} kcal | 🥩 {:.1}P / 🧈 {:.1}F / 🍞 {:.1}C",
+        summary.name,
+        summary.calories.unwrap_or(0.0),
+        summary.proteins.unwrap_or(0.0),
+        summary.fats.unwrap_or(0.0),
+        summary.carbs.unwrap_or(0.0)
+    );
+    let provenance = InputSource::Favorite.provenance();
+    food_log::log_food_and_reply(bot, chat_id, lang, &summary, &suggestion, provenance).await
+}
+
+/// Формирует список избранного и частых блюд. Возвращает `None`, если оба списка пусты.
+async fn render_favorites(
+    chat_id: i64,
+    lang: &str,
+) -> Result<Option<(String, InlineKeyboardMarkup)>, sqlx::Error> {
+    let favorites = queries::get_favorites(chat_id).await?;
+    let frequent = queries::get_frequent_meals(chat_id, FREQUENT_MEALS_LIMIT).await?;
+    if favorites.is_empty() && frequent.is_empty() {
+        return Ok(None);
+    }
+
+    let mut rows: Vec<Vec<InlineKeyboardButton>> = Vec::new();
+    for favorite in &favorites {
+        rows.push(vec![
+            InlineKeyboardButton::callback(
+                format!(
+                    "⭐ {} · {:.0} kcal",
+                    shorten(&favorite.name),
+                    favorite.calories.unwrap_or(0.0)
+                ),
+                format!("fav:log:{}", favorite.id),
+            ),
+            InlineKeyboardButton::callback("🗑", format!("fav:del:{}", favorite.id)),
+        ]);
+    }
+    for meal in &frequent {
+        rows.push(vec![InlineKeyboardButton::callback(
+            format!(
+                "🔁 {} · {:.0} kcal (×{})",
+                shorten(&meal.food_name),
+                meal.calories.unwrap_or(0.0),
+                meal.times
+            ),
+            format!("freq:{}", meal.id),
+        )]);
+    }
+
+    Ok(Some((title_text(lang).to_string(), InlineKeyboardMarkup::new(rows))))
+}
+
+fn shorten(name: &str) -> String {
+    if name.chars().count() > BUTTON_NAME_LEN {
+        format!("{}…", name.chars().take(BUTTON_NAME_LEN).collect::<String>())
+    } else {
+        name.to_string()
+    }
+}
+
+fn title_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "⭐ Избранное и частые блюда — нажмите, чтобы записать:",
+        "th" => "⭐ รายการโปรดและเมนูที่กินบ่อย — แตะเพื่อบันทึก:",
+        "zh" => "⭐ 收藏和常吃的餐 — 点击即可记录：",
+        _ => "⭐ Favorites and frequent meals — tap to log:",
+    }
+}
+
+fn empty_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "⭐ Избранное пусто. Нажмите ⭐ под записью, чтобы добавить блюдо.",
+        "th" => "⭐ ยังไม่มีรายการโปรด แตะ ⭐ ใต้รายการอาหารเพื่อเพิ่ม",
+        "zh" => "⭐ 收藏为空。点击记录下方的 ⭐ 添加。",
+        _ => "⭐ No favorites yet. Tap ⭐ under a logged meal to add it.",
+    }
+}
+
+fn added_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "⭐ Добавлено в избранное",
+        "th" => "⭐ เพิ่มในรายการโปรดแล้ว",
+        "zh" => "⭐ 已添加到收藏",
+        _ => "⭐ Added to favorites",
+    }
+}
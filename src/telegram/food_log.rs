@@ -0,0 +1,609 @@
+use crate::db::models::{FoodProvenance, NewFoodLog};
+use crate::db::queries;
+use crate::locales::messages::Messages;
+use crate::services::log_time::{self, TimeHint};
+use crate::services::meal::MealType;
+use crate::services::nutrition::{FoodSummary, InputSource};
+use crate::telegram::{achievements, fasting};
+use crate::telegram::handlers::get_user_tz;
+use crate::telegram::state::{self, PendingInput};
+use crate::telegram::stats::{remaining_budget, remaining_text};
+use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup},
+};
+
+/// Сохраняет распознанную еду и отправляет подтверждение с выбором приёма пищи.
+/// Время приёма берётся из `summary.time_hint` («вчера на ужин»), иначе — текущее.
+/// К `provenance` (источник и исходный ввод) добавляются ответ и модель.
+pub async fn log_food_and_reply(
+    bot: &Bot,
+    chat_id: ChatId,
+    lang: &str,
+    summary: &FoodSummary,
+    suggestion: &str,
+    provenance: FoodProvenance,
+) -> ResponseResult<()> {
+    let tz = get_user_tz(chat_id.0).await;
+    let now = Utc::now();
+    let (eaten_at, meal) = summary.time_hint.resolve(now, tz);
+
+    if let Err(e) = log_time::validate(eaten_at, now) {
+        bot.send_message(chat_id, e.message(lang)).await?;
+        return Ok(());
+    }
+
+    let entry = NewFoodLog {
+        food_name: summary.name.clone(),
+        calories: summary.calories,
+        proteins: summary.proteins,
+        fats: summary.fats,
+        carbs: summary.carbs,
+        meal_type: meal.as_str().to_string(),
+        eaten_at,
+        micros: summary.micros.clone(),
+        provenance: FoodProvenance {
+            suggestion: Some(suggestion.to_string()),
+            model: summary.model.map(str::to_string),
+            ..provenance
+        },
+    };
+    let log_id = queries::add_food_log(chat_id.0, &entry).await.ok();
+
+    let (cal, pr, fa, ch) = queries::get_daily_summary(chat_id.0)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("get_daily_summary failed: {}", e);
+            (0.0, 0.0, 0.0, 0.0)
+        });
+
+    let mut response = format!("✅ {}\n{} {}", suggestion, meal.emoji(), meal.label(lang));
+    if now - eaten_at > Duration::minutes(1) {
+        response.push_str(&format!(" · 🕒 {}", format_local(eaten_at, tz)));
+    }
+    response.push_str(&format!(
+        "\n📊 {}: {:.0} kcal | 🥩 {:.1}P / 🧈 {:.1}F / 🍞 {:.1}C",
+        today_label(lang),
+        cal,
+        pr,
+        fa,
+        ch
+    ));
+    let burned = queries::get_daily_burned(chat_id.0, now.with_timezone(&tz).date_naive())
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("get_daily_burned failed: {}", e);
+            0.0
+        });
+    if let Some(left) = remaining_budget(chat_id.0, cal, burned).await {
+        response.push_str(&format!("\n{}", remaining_text(left, lang)));
+    }
+
+    if log_id.is_some()
+        && let Some(fast) = fasting::end_fast_on_meal(chat_id.0, eaten_at, lang).await
+    {
+        response.push_str(&format!("\n{}", fast));
+    }
+
+    let request = bot.send_message(chat_id, response);
+    match log_id {
+        Some(id) => request.reply_markup(food_log_keyboard(id, meal, lang)).await?,
+        None => request.await?,
+    };
+    if log_id.is_some() {
+        achievements::announce_new_badges(bot, chat_id, lang).await;
+    }
+
+    Ok(())
+}
+
+/// `/log 2026-10-18 [19:30] <описание>` — запись задним числом.
+pub async fn handle_log_command(
+    bot: &Bot,
+    chat_id: ChatId,
+    text: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    let args = text.trim_start_matches("/log").trim();
+    let mut tokens = args.splitn(3, ' ');
+
+    let mut hint = TimeHint::default();
+    let mut description = String::new();
+    for _ in 0..2 {
+        let Some(token) = tokens.next() else { break };
+        match log_time::parse_datetime_input(token) {
+            Some(parsed) => hint = hint.or(parsed),
+            None => {
+                description = token.to_string();
+                break;
+            }
+        }
+    }
+    let rest = tokens.collect::<Vec<_>>().join(" ");
+    let description = format!("{} {}", description, rest).trim().to_string();
+
+    if hint.date.is_none() || description.is_empty() {
+        bot.send_message(chat_id, log_usage(lang)).await?;
+        return Ok(());
+    }
+
+    let tz = get_user_tz(chat_id.0).await;
+    let now = Utc::now();
+    let (eaten_at, _) = hint.resolve(now, tz);
+    if let Err(e) = log_time::validate(eaten_at, now) {
+        bot.send_message(chat_id, e.message(lang)).await?;
+        return Ok(());
+    }
+
+    match crate::services::food_db::resolve_food_description(&description, lang).await {
+        Ok((mut summary, suggestion)) => {
+            summary.time_hint = hint.or(summary.time_hint);
+            let provenance = FoodProvenance {
+                input_text: Some(description.clone()),
+                ..InputSource::Text.provenance()
+            };
+            log_food_and_reply(bot, chat_id, lang, &summary, &suggestion, provenance).await?;
+        }
+        Err(e) => {
+            log::error!("Error in resolve_food_description: {}", e);
+            bot.send_message(chat_id, &Messages::get(lang).unknown).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Кнопки под подтверждением: выбор приёма пищи (текущий отмечен галочкой),
+/// смена времени, добавление в избранное и просмотр источника записи.
+fn food_log_keyboard(log_id: i32, selected: MealType, lang: &str) -> InlineKeyboardMarkup {
+    let buttons: Vec<InlineKeyboardButton> = MealType::ALL
+        .iter()
+        .map(|meal| {
+            let mark = if *meal == selected { "✅" } else { meal.emoji() };
+            InlineKeyboardButton::callback(
+                format!("{} {}", mark, meal.label(lang)),
+                format!("meal:{}:{}", log_id, meal.as_str()),
+            )
+        })
+        .collect();
+
+    let mut rows: Vec<Vec<InlineKeyboardButton>> =
+        buttons.chunks(2).map(|row| row.to_vec()).collect();
+    rows.push(vec![
+        InlineKeyboardButton::callback(change_time_label(lang), format!("time:{}", log_id)),
+        InlineKeyboardButton::callback("⭐", format!("fav:add:{}", log_id)),
+        InlineKeyboardButton::callback("ℹ️", format!("src:{}", log_id)),
+    ]);
+
+    InlineKeyboardMarkup::new(rows)
+}
+
+/// Варианты переноса записи по времени.
+fn time_options_keyboard(log_id: i32, lang: &str) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![
+        vec![
+            InlineKeyboardButton::callback("−1h", format!("time:{}:-60", log_id)),
+            InlineKeyboardButton::callback("−3h", format!("time:{}:-180", log_id)),
+            InlineKeyboardButton::callback(yesterday_label(lang), format!("time:{}:-1440", log_id)),
+        ],
+        vec![
+            InlineKeyboardButton::callback(enter_time_label(lang), format!("time:{}:input", log_id)),
+            InlineKeyboardButton::callback("⬅️", format!("time:{}:back", log_id)),
+        ],
+    ])
+}
+
+/// Обрабатывает смену приёма пищи (`meal:<log_id>:<type>`).
+pub async fn handle_meal_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    data: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    let mut parts = data.split(':').skip(1);
+    let parsed = match (parts.next(), parts.next()) {
+        (Some(id), Some(meal)) => id.parse::<i32>().ok().zip(MealType::parse(meal)),
+        _ => None,
+    };
+    let (Some((log_id, meal)), Some(message)) = (parsed, q.message.as_ref()) else {
+        bot.answer_callback_query(q.id.clone()).await?;
+        return Ok(());
+    };
+    let chat_id = message.chat().id;
+
+    match queries::update_food_log_meal_type(chat_id.0, log_id, meal.as_str()).await {
+        Ok(true) => {
+            bot.answer_callback_query(q.id.clone())
+                .text(format!("{} {}", meal.emoji(), meal.label(lang)))
+                .await?;
+            bot.edit_message_reply_markup(chat_id, message.id())
+                .reply_markup(food_log_keyboard(log_id, meal, lang))
+                .await
+                .ok();
+        }
+        Ok(false) => {
+            bot.answer_callback_query(q.id.clone()).await?;
+        }
+        Err(e) => {
+            log::error!("Error in update_food_log_meal_type: {}", e);
+            bot.answer_callback_query(q.id.clone())
+                .text(Messages::get(lang).error)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Обрабатывает кнопки смены времени (`time:<log_id>[:<сдвиг в минутах>|input|back]`).
+pub async fn handle_time_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    data: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    let mut parts = data.split(':').skip(1);
+    let log_id = parts.next().and_then(|id| id.parse::<i32>().ok());
+    let action = parts.next();
+    let (Some(log_id), Some(message)) = (log_id, q.message.as_ref()) else {
+        bot.answer_callback_query(q.id.clone()).await?;
+        return Ok(());
+    };
+    let chat_id = message.chat().id;
+
+    let entry = match queries::get_food_log(chat_id.0, log_id).await {
+        Ok(Some(entry)) => entry,
+        Ok(None) => {
+            bot.answer_callback_query(q.id.clone()).await?;
+            return Ok(());
+        }
+        Err(e) => {
+            log::error!("Error in get_food_log: {}", e);
+            bot.answer_callback_query(q.id.clone())
+                .text(Messages::get(lang).error)
+                .await?;
+            return Ok(());
+        }
+    };
+    let current_meal = MealType::parse(&entry.meal_type).unwrap_or(MealType::Snack);
+
+    match action {
+        None => {
+            bot.answer_callback_query(q.id.clone()).await?;
+            bot.edit_message_reply_markup(chat_id, message.id())
+                .reply_markup(time_options_keyboard(log_id, lang))
+                .await
+                .ok();
+        }
+        Some("back") => {
+            bot.answer_callback_query(q.id.clone()).await?;
+            bot.edit_message_reply_markup(chat_id, message.id())
+                .reply_markup(food_log_keyboard(log_id, current_meal, lang))
+                .await
+                .ok();
+        }
+        Some("input") => {
+            state::set_pending(chat_id.0, PendingInput::LogTime(log_id));
+            bot.answer_callback_query(q.id.clone()).await?;
+            bot.send_message(chat_id, enter_time_prompt(lang)).await?;
+        }
+        Some(shift) => {
+            let Ok(minutes) = shift.parse::<i64>() else {
+                bot.answer_callback_query(q.id.clone()).await?;
+                return Ok(());
+            };
+            let tz = get_user_tz(chat_id.0).await;
+            let eaten_at = entry.created_at + Duration::minutes(minutes);
+
+            match move_entry(chat_id.0, log_id, eaten_at, tz).await {
+                Ok(meal) => {
+                    bot.answer_callback_query(q.id.clone())
+                        .text(format!("🕒 {}", format_local(eaten_at, tz)))
+                        .await?;
+                    bot.edit_message_reply_markup(chat_id, message.id())
+                        .reply_markup(food_log_keyboard(log_id, meal, lang))
+                        .await
+                        .ok();
+                }
+                Err(text) => {
+                    bot.answer_callback_query(q.id.clone())
+                        .text(text.for_lang(lang))
+                        .show_alert(true)
+                        .await?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Показывает, из чего получена запись (`src:<log_id>`): источник, модель, исходный ввод и ответ.
+pub async fn handle_source_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    data: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    bot.answer_callback_query(q.id.clone()).await?;
+    let log_id = data.strip_prefix("src:").and_then(|id| id.parse::<i32>().ok());
+    let (Some(log_id), Some(message)) = (log_id, q.message.as_ref()) else {
+        return Ok(());
+    };
+    let chat_id = message.chat().id;
+
+    match queries::get_food_log_provenance(chat_id.0, log_id).await {
+        Ok(Some(provenance)) => {
+            bot.send_message(chat_id, provenance_text(&provenance, lang)).await?;
+        }
+        Ok(None) => {}
+        Err(e) => {
+            log::error!("Error in get_food_log_provenance: {}", e);
+            bot.send_message(chat_id, &Messages::get(lang).error).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Длинные ответы модели обрезаются, чтобы сообщение уложилось в лимит Telegram.
+const MAX_PROVENANCE_FIELD_CHARS: usize = 1500;
+
+fn provenance_text(provenance: &FoodProvenance, lang: &str) -> String {
+    let Some(source) = provenance.source.as_deref() else {
+        return no_provenance_text(lang).to_string();
+    };
+
+    let mut text = format!(
+        "ℹ️ {}: {}",
+        source_label(lang),
+        InputSource::parse(source).map_or(source, |s| s.label(lang))
+    );
+    text.push_str(&format!(
+        "\n🤖 {}: {}",
+        model_label(lang),
+        provenance.model.as_deref().unwrap_or(no_model_label(lang))
+    ));
+    if let Some(model) = &provenance.transcription_model {
+        text.push_str(&format!("\n🎙 {}: {}", transcription_model_label(lang), model));
+    }
+    let fields = [
+        ("💬", input_label(lang), &provenance.input_text),
+        ("🎤", transcript_label(lang), &provenance.transcript),
+        ("🧾", reply_label(lang), &provenance.suggestion),
+    ];
+    for (emoji, label, value) in fields {
+        if let Some(value) = value {
+            text.push_str(&format!("\n\n{} {}:\n{}", emoji, label, truncate(value)));
+        }
+    }
+    text
+}
+
+fn truncate(value: &str) -> String {
+    match value.char_indices().nth(MAX_PROVENANCE_FIELD_CHARS) {
+        Some((end, _)) => format!("{}…", &value[..end]),
+        None => value.to_string(),
+    }
+}
+
+/// Обрабатывает время, введённое вручную после кнопки «Ввести время».
+pub async fn handle_log_time_input(
+    bot: &Bot,
+    chat_id: ChatId,
+    log_id: i32,
+    text: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    let Some(hint) = log_time::parse_datetime_input(text) else {
+        // Даём ещё одну попытку.
+        state::set_pending(chat_id.0, PendingInput::LogTime(log_id));
+        bot.send_message(chat_id, enter_time_prompt(lang)).await?;
+        return Ok(());
+    };
+
+    let entry = match queries::get_food_log(chat_id.0, log_id).await {
+        Ok(Some(entry)) => entry,
+        Ok(None) => return Ok(()),
+        Err(e) => {
+            log::error!("Error in get_food_log: {}", e);
+            bot.send_message(chat_id, &Messages::get(lang).error).await?;
+            return Ok(());
+        }
+    };
+
+    // Если указано только время — оставляем день записи.
+    let tz = get_user_tz(chat_id.0).await;
+    let hint = TimeHint {
+        date: hint.date.or(Some(entry.created_at.with_timezone(&tz).date_naive())),
+        time: hint.time.or(Some(entry.created_at.with_timezone(&tz).time())),
+        ..hint
+    };
+    let (eaten_at, _) = hint.resolve(Utc::now(), tz);
+
+    match move_entry(chat_id.0, log_id, eaten_at, tz).await {
+        Ok(meal) => {
+            let text = format!(
+                "🕒 {} — {} {} {}",
+                entry.food_name,
+                format_local(eaten_at, tz),
+                meal.emoji(),
+                meal.label(lang)
+            );
+            bot.send_message(chat_id, text)
+                .reply_markup(food_log_keyboard(log_id, meal, lang))
+                .await?;
+        }
+        Err(e) => {
+            bot.send_message(chat_id, e.for_lang(lang)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+enum MoveError {
+    Backdate(log_time::BackdateError),
+    Db,
+}
+
+impl MoveError {
+    fn for_lang(&self, lang: &str) -> String {
+        match self {
+            MoveError::Backdate(e) => e.message(lang),
+            MoveError::Db => Messages::get(lang).error,
+        }
+    }
+}
+
+/// Проверяет новое время и переносит запись; приём пищи пересчитывается по местному времени.
+async fn move_entry(
+    chat_id: i64,
+    log_id: i32,
+    eaten_at: DateTime<Utc>,
+    tz: Tz,
+) -> Result<MealType, MoveError> {
+    log_time::validate(eaten_at, Utc::now()).map_err(MoveError::Backdate)?;
+
+    let meal = MealType::from_local_time(eaten_at.with_timezone(&tz).time());
+    queries::update_food_log_time(chat_id, log_id, eaten_at, meal.as_str())
+        .await
+        .map_err(|e| {
+            log::error!("Error in update_food_log_time: {}", e);
+            MoveError::Db
+        })?;
+
+    Ok(meal)
+}
+
+fn format_local(at: DateTime<Utc>, tz: Tz) -> String {
+    at.with_timezone(&tz).format("%d.%m %H:%M").to_string()
+}
+
+fn today_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Сегодня",
+        "th" => "วันนี้",
+        "zh" => "今天",
+        _ => "Today",
+    }
+}
+
+fn source_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Источник",
+        "th" => "แหล่งที่มา",
+        "zh" => "来源",
+        _ => "Source",
+    }
+}
+
+fn model_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Модель",
+        "th" => "โมเดล",
+        "zh" => "模型",
+        _ => "Model",
+    }
+}
+
+fn no_model_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "не использовалась",
+        "th" => "ไม่ได้ใช้",
+        "zh" => "未使用",
+        _ => "not used",
+    }
+}
+
+fn transcription_model_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Распознавание речи",
+        "th" => "การถอดเสียง",
+        "zh" => "语音识别",
+        _ => "Speech recognition",
+    }
+}
+
+fn input_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Исходный текст",
+        "th" => "ข้อความต้นฉบับ",
+        "zh" => "原始输入",
+        _ => "Original input",
+    }
+}
+
+fn transcript_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Расшифровка",
+        "th" => "ข้อความที่ถอดเสียง",
+        "zh" => "语音转写",
+        _ => "Transcript",
+    }
+}
+
+fn reply_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Ответ",
+        "th" => "คำตอบ",
+        "zh" => "回复",
+        _ => "Reply",
+    }
+}
+
+fn no_provenance_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "ℹ️ Для этой записи исходные данные не сохранялись.",
+        "th" => "ℹ️ ไม่มีข้อมูลต้นทางสำหรับรายการนี้",
+        "zh" => "ℹ️ 此记录未保存原始数据。",
+        _ => "ℹ️ No source data was stored for this entry.",
+    }
+}
+
+fn change_time_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "🕒 Изменить время",
+        "th" => "🕒 เปลี่ยนเวลา",
+        "zh" => "🕒 修改时间",
+        _ => "🕒 Change time",
+    }
+}
+
+fn yesterday_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Вчера",
+        "th" => "เมื่อวาน",
+        "zh" => "昨天",
+        _ => "Yesterday",
+    }
+}
+
+fn enter_time_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "✏️ Ввести",
+        "th" => "✏️ ระบุเวลา",
+        "zh" => "✏️ 输入",
+        _ => "✏️ Enter",
+    }
+}
+
+fn enter_time_prompt(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "🕒 Введите дату и время: 2026-10-18 19:30 (или только 19:30)",
+        "th" => "🕒 ระบุวันที่และเวลา: 2026-10-18 19:30 (หรือแค่ 19:30)",
+        "zh" => "🕒 请输入日期和时间: 2026-10-18 19:30（或仅 19:30）",
+        _ => "🕒 Enter date and time: 2026-10-18 19:30 (or just 19:30)",
+    }
+}
+
+fn log_usage(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "📝 Формат: /log 2026-10-18 [19:30] что вы ели",
+        "th" => "📝 รูปแบบ: /log 2026-10-18 [19:30] อาหารที่กิน",
+        "zh" => "📝 格式: /log 2026-10-18 [19:30] 吃了什么",
+        _ => "📝 Usage: /log 2026-10-18 [19:30] what you ate",
+    }
+}
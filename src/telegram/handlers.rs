@@ -1,25 +1,78 @@
+use crate::db::models::FoodProvenance;
 use crate::db::queries;
 use crate::locales::messages::Messages;
-use crate::services::chart::draw_weekly_calories_chart;
-use chrono::Utc;
+use crate::services::log_time::extract_time_hint;
+use crate::services::nutrition::{InputSource, TRANSCRIPTION_MODEL};
+use crate::telegram::state::{self, PendingInput};
+use crate::telegram::{
+    account, achievements, activity, barcode, diary_import, export, fasting, favorites, food_log,
+    history, pdf_report, recipes, reminders, reports, stats, tips, water, weight,
+};
+use chrono::{NaiveDate, Utc};
+use chrono_tz::Tz;
 use log::error;
 use reqwest::Url;
 use teloxide::{
     prelude::*,
-    types::{InlineKeyboardButton, InlineKeyboardMarkup, InputFile, Message, ParseMode},
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, Message, ParseMode},
 };
 
-pub async fn handle_message(bot: Bot, msg: Message) -> ResponseResult<()> {
-    let chat_id = msg.chat.id;
-    let user_lang = queries::get_user(chat_id.0)
+/// Возвращает язык пользователя (по умолчанию — русский).
+pub async fn get_user_lang(chat_id: i64) -> String {
+    queries::get_user(chat_id)
         .await
         .ok()
         .flatten()
         .and_then(|u| u.language_code)
-        .unwrap_or("ru".to_string());
+        .unwrap_or("ru".to_string())
+}
+
+/// Возвращает часовой пояс пользователя (по умолчанию — UTC).
+pub async fn get_user_tz(chat_id: i64) -> Tz {
+    queries::get_user(chat_id)
+        .await
+        .ok()
+        .flatten()
+        .map(|u| u.tz())
+        .unwrap_or(Tz::UTC)
+}
+
+/// Сегодняшняя дата в часовом поясе пользователя.
+pub async fn get_user_today(chat_id: i64) -> NaiveDate {
+    Utc::now().with_timezone(&get_user_tz(chat_id).await).date_naive()
+}
+
+/// Ссылка для скачивания файла Telegram (токен берётся из самого бота).
+pub fn file_url(bot: &Bot, path: &str) -> String {
+    format!("https://api.telegram.org/file/bot{}/{}", bot.token(), path)
+}
+
+pub async fn handle_message(bot: Bot, msg: Message) -> ResponseResult<()> {
+    let chat_id = msg.chat.id;
+    let user_lang = get_user_lang(chat_id.0).await;
     let messages = Messages::get(&user_lang);
 
     if let Some(text) = msg.text() {
+        if text.starts_with('/') {
+            state::clear_pending(chat_id.0);
+        } else if let Some(pending) = state::take_pending(chat_id.0) {
+            match pending {
+                PendingInput::LogTime(log_id) => {
+                    food_log::handle_log_time_input(&bot, chat_id, log_id, text, &user_lang)
+                        .await?;
+                }
+                PendingInput::ProductWeight(code) => {
+                    barcode::handle_product_weight_input(&bot, chat_id, code, text, &user_lang)
+                        .await?;
+                }
+                recipe_input => {
+                    recipes::handle_recipe_input(&bot, chat_id, recipe_input, text, &user_lang)
+                        .await?;
+                }
+            }
+            return Ok(());
+        }
+
         if text == "/start" {
             queries::register_user(chat_id.0).await.ok();
 
@@ -50,22 +103,8 @@ pub async fn handle_message(bot: Bot, msg: Message) -> ResponseResult<()> {
             return Ok(());
         }
 
-        if text == "/stats" {
-            match queries::get_daily_summary(chat_id.0).await {
-                Ok((calories, proteins, fats, carbs)) => {
-                    let summary = format!(
-                        "\u{1F4CA} *Daily Summary:*\nKcal: `{}`\nProtein: `{}`g\nFat: `{}`g\nCarbs: `{}`g",
-                        calories, proteins, fats, carbs
-                    );
-                    bot.send_message(chat_id, summary)
-                        .parse_mode(ParseMode::MarkdownV2)
-                        .await?;
-                }
-                Err(e) => {
-                    log::error!("Error in get_daily_summary: {}", e.to_string());
-                    bot.send_message(chat_id, &messages.error).await?;
-                }
-            }
+        if text == "/stats" || text.starts_with("/stats ") {
+            stats::handle_stats_command(&bot, chat_id, text, &user_lang).await?;
             return Ok(());
         }
 
@@ -75,7 +114,7 @@ pub async fn handle_message(bot: Bot, msg: Message) -> ResponseResult<()> {
                     bot.send_message(chat_id, &messages.reset_done).await?;
                 }
                 Err(e) => {
-                    log::error!("Error in reset_today_logs: {}", e.to_string());
+                    log::error!("Error in reset_today_logs: {}", e);
                     bot.send_message(chat_id, &messages.error).await?;
                 }
             }
@@ -83,45 +122,107 @@ pub async fn handle_message(bot: Bot, msg: Message) -> ResponseResult<()> {
         }
 
         if text == "/week" {
-            let weekly = queries::get_weekly_calories(chat_id.0)
-                .await
-                .unwrap_or_default();
-
-            if weekly.is_empty() {
-                bot.send_message(chat_id, &messages.week_empty).await?;
-            } else {
-                let data: Vec<(String, f32)> = weekly
-                    .into_iter()
-                    .map(|(date, val)| (date.format("%d.%m").to_string(), val))
-                    .collect();
-
-                let file_path = format!("temp/weekly_calories_{}.png", chat_id);
-                if let Err(e) = std::fs::create_dir_all("temp") {
-                    log::warn!("Failed to create temp directory: {}", e.to_string());
-                }
+            stats::handle_week_command(&bot, chat_id, &user_lang).await?;
+            return Ok(());
+        }
 
-                match draw_weekly_calories_chart(&data, &file_path) {
-                    Ok(_) => {
-                        if bot
-                            .send_photo(chat_id, InputFile::file(&file_path))
-                            .await
-                            .is_ok()
-                        {
-                            if let Err(e) = std::fs::remove_file(&file_path) {
-                                log::warn!(
-                                    "Failed to delete chart file {}: {}",
-                                    file_path,
-                                    e.to_string()
-                                );
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        log::error!("Error drawing chart: {}", e.to_string());
-                        bot.send_message(chat_id, &messages.graph_error).await?;
-                    }
-                }
-            }
+        if text == "/month" {
+            stats::handle_month_command(&bot, chat_id, &user_lang).await?;
+            return Ok(());
+        }
+
+        if text == "/chart" || text.starts_with("/chart ") {
+            stats::handle_chart_command(&bot, chat_id, text, &user_lang).await?;
+            return Ok(());
+        }
+
+        if text == "/timezone" || text.starts_with("/timezone ") {
+            handle_timezone_command(&bot, chat_id, text, &user_lang).await?;
+            return Ok(());
+        }
+
+        if text == "/log" || text.starts_with("/log ") {
+            food_log::handle_log_command(&bot, chat_id, text, &user_lang).await?;
+            return Ok(());
+        }
+
+        if text == "/favorites" {
+            favorites::handle_favorites_command(&bot, chat_id, &user_lang).await?;
+            return Ok(());
+        }
+
+        if text == "/recipes" || text == "/recipe" || text.starts_with("/recipe ") {
+            recipes::handle_recipe_command(&bot, chat_id, text, &user_lang).await?;
+            return Ok(());
+        }
+
+        if text == "/water" || text.starts_with("/water ") {
+            water::handle_water_command(&bot, chat_id, text, &user_lang).await?;
+            return Ok(());
+        }
+
+        if text == "/weight" || text.starts_with("/weight ") {
+            weight::handle_weight_command(&bot, chat_id, text, &user_lang).await?;
+            return Ok(());
+        }
+
+        if text == "/exercise" || text.starts_with("/exercise ") {
+            activity::handle_exercise_command(&bot, chat_id, text, &user_lang).await?;
+            return Ok(());
+        }
+
+        if text == "/fast" || text.starts_with("/fast ") {
+            fasting::handle_fast_command(&bot, chat_id, text, &user_lang).await?;
+            return Ok(());
+        }
+
+        if text == "/digest" || text.starts_with("/digest ") {
+            reports::handle_digest_command(&bot, chat_id, text, &user_lang).await?;
+            return Ok(());
+        }
+
+        if text == "/remind" || text.starts_with("/remind ") {
+            reminders::handle_remind_command(&bot, chat_id, text, &user_lang).await?;
+            return Ok(());
+        }
+
+        if text == "/tips" || text.starts_with("/tips ") {
+            tips::handle_tips_command(&bot, chat_id, text, &user_lang).await?;
+            return Ok(());
+        }
+
+        if text == "/achievements" {
+            achievements::handle_achievements_command(&bot, chat_id, &user_lang).await?;
+            return Ok(());
+        }
+
+        if text == "/export" || text.starts_with("/export ") {
+            export::handle_export_command(&bot, chat_id, text, &user_lang).await?;
+            return Ok(());
+        }
+
+        if text == "/report" || text.starts_with("/report ") {
+            pdf_report::handle_report_command(&bot, chat_id, text, &user_lang).await?;
+            return Ok(());
+        }
+
+        if text == "/import" {
+            diary_import::handle_import_command(&bot, chat_id, &user_lang).await?;
+            return Ok(());
+        }
+
+        if text == "/mydata" {
+            account::handle_mydata_command(&bot, chat_id, &user_lang).await?;
+            return Ok(());
+        }
+
+        if text == "/deleteme" {
+            account::handle_deleteme_command(&bot, chat_id, &user_lang).await?;
+            return Ok(());
+        }
+
+        if text == "/history" {
+            history::handle_history_command(&bot, chat_id, &user_lang).await?;
             return Ok(());
         }
 
@@ -140,34 +241,25 @@ pub async fn handle_message(bot: Bot, msg: Message) -> ResponseResult<()> {
             return Ok(());
         }
 
-        match crate::services::nutrition::analyze_food_description(text, &user_lang).await {
+        if activity::try_log_activity_text(&bot, chat_id, text, &user_lang).await? {
+            return Ok(());
+        }
+
+        if recipes::try_log_recipe_text(&bot, chat_id, text, &user_lang).await? {
+            return Ok(());
+        }
+
+        match crate::services::food_db::resolve_food_description(text, &user_lang).await {
             Ok((summary, suggestion)) => {
-                queries::add_food_log(
-                    chat_id.0,
-                    &summary.name,
-                    summary.calories,
-                    summary.proteins,
-                    summary.fats,
-                    summary.carbs,
-                )
-                .await
-                .ok();
-
-                let (cal, pr, fa, ch) =
-                    queries::get_daily_summary(chat_id.0)
-                        .await
-                        .unwrap_or_else(|e| {
-                            log::warn!("get_daily_summary failed: {}", e.to_string());
-                            (0.0, 0.0, 0.0, 0.0)
-                        });
-                let response = format!(
-                    "✅ {}\n📊 Today: {:.0} kcal | 🥩 {:.1}P / 🧈 {:.1}F / 🍞 {:.1}C",
-                    suggestion, cal, pr, fa, ch
-                );
-                bot.send_message(chat_id, response).await?;
+                let provenance = FoodProvenance {
+                    input_text: Some(text.to_string()),
+                    ..InputSource::Text.provenance()
+                };
+                food_log::log_food_and_reply(&bot, chat_id, &user_lang, &summary, &suggestion, provenance)
+                    .await?;
             }
             Err(e) => {
-                log::error!("Error in analyze_food_description: {}", e);
+                log::error!("Error in resolve_food_description: {}", e);
                 bot.send_message(chat_id, &messages.unknown).await?;
             }
         }
@@ -178,33 +270,24 @@ pub async fn handle_message(bot: Bot, msg: Message) -> ResponseResult<()> {
         if let Some(photo) = photos.last() {
             let file_id = &photo.file.id;
             let file = bot.get_file(file_id).send().await?;
-            let token = std::env::var("TELEGRAM_BOT_TOKEN").unwrap();
-            let url = format!("https://api.telegram.org/file/bot{}/{}", token, file.path);
+            let url = file_url(&bot, &file.path);
+
+            if barcode::try_handle_barcode(&bot, chat_id, &url, &user_lang).await? {
+                return Ok(());
+            }
 
             match crate::services::nutrition::analyze_image(&url, &user_lang).await {
-                Ok((summary, suggestion)) => {
-                    queries::add_food_log(
-                        chat_id.0,
-                        &summary.name,
-                        summary.calories,
-                        summary.proteins,
-                        summary.fats,
-                        summary.carbs,
-                    )
-                    .await
-                    .ok();
-
-                    let (cal, pr, fa, ch) = queries::get_daily_summary(chat_id.0)
-                        .await
-                        .unwrap_or_else(|e| {
-                            log::warn!("get_daily_summary failed: {}", e.to_string());
-                            (0.0, 0.0, 0.0, 0.0)
-                        });
-                    let response = format!(
-                        "✅ {}\n📊 Today: {:.0} kcal | 🥩 {:.1}P / 🧈 {:.1}F / 🍞 {:.1}C",
-                        suggestion, cal, pr, fa, ch
-                    );
-                    bot.send_message(chat_id, response).await?;
+                Ok((mut summary, suggestion)) => {
+                    if let Some(caption) = msg.caption() {
+                        summary.time_hint = extract_time_hint(caption);
+                    }
+                    let provenance = FoodProvenance {
+                        input_text: msg.caption().map(str::to_string),
+                        telegram_file_id: Some(file_id.to_string()),
+                        ..InputSource::Photo.provenance()
+                    };
+                    food_log::log_food_and_reply(&bot, chat_id, &user_lang, &summary, &suggestion, provenance)
+                        .await?;
                 }
                 Err(e) => {
                     log::error!("Error in analyze_image: {}", e);
@@ -218,43 +301,46 @@ pub async fn handle_message(bot: Bot, msg: Message) -> ResponseResult<()> {
     if let Some(voice) = msg.voice() {
         let file_id = &voice.file.id;
         let file = bot.get_file(file_id).send().await?;
-        let token = std::env::var("TELEGRAM_BOT_TOKEN").unwrap();
-        let url = format!("https://api.telegram.org/file/bot{}/{}", token, file.path);
+        let url = file_url(&bot, &file.path);
+
+        let transcript = match crate::services::nutrition::transcribe_audio(&url).await {
+            Ok(transcript) => transcript,
+            Err(e) => {
+                log::error!("Error in transcribe_audio: {}", e);
+                bot.send_message(chat_id, &messages.unknown).await?;
+                return Ok(());
+            }
+        };
 
-        match crate::services::nutrition::analyze_audio(&url, &user_lang).await {
+        if activity::try_log_activity_text(&bot, chat_id, &transcript, &user_lang).await? {
+            return Ok(());
+        }
+
+        match crate::services::food_db::resolve_food_description(&transcript, &user_lang).await {
             Ok((summary, suggestion)) => {
-                queries::add_food_log(
-                    chat_id.0,
-                    &summary.name,
-                    summary.calories,
-                    summary.proteins,
-                    summary.fats,
-                    summary.carbs,
-                )
-                .await
-                .ok();
-
-                let (cal, pr, fa, ch) =
-                    queries::get_daily_summary(chat_id.0)
-                        .await
-                        .unwrap_or_else(|e| {
-                            log::warn!("get_daily_summary failed: {}", e.to_string());
-                            (0.0, 0.0, 0.0, 0.0)
-                        });
-                let response = format!(
-                    "✅ {}\n📊 Today: {:.0} kcal | 🥩 {:.1}P / 🧈 {:.1}F / 🍞 {:.1}C",
-                    suggestion, cal, pr, fa, ch
-                );
-                bot.send_message(chat_id, response).await?;
+                let provenance = FoodProvenance {
+                    telegram_file_id: Some(file_id.to_string()),
+                    transcript: Some(transcript.clone()),
+                    transcription_model: Some(TRANSCRIPTION_MODEL.to_string()),
+                    ..InputSource::Voice.provenance()
+                };
+                food_log::log_food_and_reply(&bot, chat_id, &user_lang, &summary, &suggestion, provenance)
+                    .await?;
             }
             Err(e) => {
-                log::error!("Error in analyze_audio: {}", e);
+                log::error!("Error in resolve_food_description: {}", e);
                 bot.send_message(chat_id, &messages.unknown).await?;
             }
         }
         return Ok(());
     }
 
+    if let Some(document) = msg.document()
+        && diary_import::try_handle_document(&bot, chat_id, document, &user_lang).await?
+    {
+        return Ok(());
+    }
+
     bot.send_message(chat_id, &messages.unknown).await?;
     Ok(())
 }
@@ -263,6 +349,66 @@ pub async fn handle_callback(bot: Bot, q: CallbackQuery) -> ResponseResult<()> {
     if let Some(data) = q.data.as_deref() {
         let chat_id = q.message.as_ref().map(|m| m.chat().id).unwrap_or(ChatId(0));
 
+        if data.starts_with("meal:") {
+            let user_lang = get_user_lang(chat_id.0).await;
+            return food_log::handle_meal_callback(&bot, &q, data, &user_lang).await;
+        }
+
+        if data.starts_with("time:") {
+            let user_lang = get_user_lang(chat_id.0).await;
+            return food_log::handle_time_callback(&bot, &q, data, &user_lang).await;
+        }
+
+        if data.starts_with("src:") {
+            let user_lang = get_user_lang(chat_id.0).await;
+            return food_log::handle_source_callback(&bot, &q, data, &user_lang).await;
+        }
+
+        if data.starts_with("fav:") || data.starts_with("freq:") {
+            let user_lang = get_user_lang(chat_id.0).await;
+            return favorites::handle_favorites_callback(&bot, &q, data, &user_lang).await;
+        }
+
+        if data.starts_with("bc:") {
+            let user_lang = get_user_lang(chat_id.0).await;
+            return barcode::handle_barcode_callback(&bot, &q, data, &user_lang).await;
+        }
+
+        if data.starts_with("rcp:") {
+            let user_lang = get_user_lang(chat_id.0).await;
+            return recipes::handle_recipe_callback(&bot, &q, data, &user_lang).await;
+        }
+
+        if data.starts_with("water:") {
+            let user_lang = get_user_lang(chat_id.0).await;
+            return water::handle_water_callback(&bot, &q, data, &user_lang).await;
+        }
+
+        if data.starts_with("rem:") {
+            let user_lang = get_user_lang(chat_id.0).await;
+            return reminders::handle_reminder_callback(&bot, &q, data, &user_lang).await;
+        }
+
+        if data.starts_with("tip:") {
+            let user_lang = get_user_lang(chat_id.0).await;
+            return tips::handle_tip_callback(&bot, &q, data, &user_lang).await;
+        }
+
+        if data.starts_with("imp:") {
+            let user_lang = get_user_lang(chat_id.0).await;
+            return diary_import::handle_import_callback(&bot, &q, data, &user_lang).await;
+        }
+
+        if data.starts_with("acc:") {
+            let user_lang = get_user_lang(chat_id.0).await;
+            return account::handle_account_callback(&bot, &q, data, &user_lang).await;
+        }
+
+        if data.starts_with("hist:") {
+            let user_lang = get_user_lang(chat_id.0).await;
+            return history::handle_history_callback(&bot, &q, data, &user_lang).await;
+        }
+
         let lang_code = match data {
             "lang_ru" => "ru",
             "lang_en" => "en",
@@ -287,6 +433,58 @@ pub async fn handle_callback(bot: Bot, q: CallbackQuery) -> ResponseResult<()> {
     Ok(())
 }
 
+/// `/timezone` — показывает или меняет часовой пояс пользователя.
+async fn handle_timezone_command(
+    bot: &Bot,
+    chat_id: ChatId,
+    text: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    let arg = text.trim_start_matches("/timezone").trim();
+
+    if arg.is_empty() {
+        let current = get_user_tz(chat_id.0).await;
+        let text = match lang {
+            "ru" => format!("🕒 Ваш часовой пояс: {}\nЧтобы изменить: /timezone Europe/Moscow", current),
+            "th" => format!("🕒 เขตเวลาของคุณ: {}\nเปลี่ยนได้ด้วย: /timezone Asia/Bangkok", current),
+            "zh" => format!("🕒 您的时区: {}\n修改方法: /timezone Asia/Shanghai", current),
+            _ => format!("🕒 Your time zone: {}\nTo change it: /timezone Europe/London", current),
+        };
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    }
+
+    let Ok(tz) = arg.parse::<Tz>() else {
+        let text = match lang {
+            "ru" => "❌ Неизвестный часовой пояс. Пример: /timezone Europe/Moscow",
+            "th" => "❌ ไม่รู้จักเขตเวลานี้ ตัวอย่าง: /timezone Asia/Bangkok",
+            "zh" => "❌ 未知时区。示例: /timezone Asia/Shanghai",
+            _ => "❌ Unknown time zone. Example: /timezone Europe/London",
+        };
+        bot.send_message(chat_id, text).await?;
+        return Ok(());
+    };
+
+    match queries::update_timezone(chat_id.0, tz.name()).await {
+        Ok(()) => {
+            let text = match lang {
+                "ru" => format!("✅ Часовой пояс установлен: {}", tz.name()),
+                "th" => format!("✅ ตั้งค่าเขตเวลา: {}", tz.name()),
+                "zh" => format!("✅ 时区已设置为: {}", tz.name()),
+                _ => format!("✅ Time zone set to {}", tz.name()),
+            };
+            bot.send_message(chat_id, text).await?;
+        }
+        Err(e) => {
+            log::error!("Error in update_timezone: {}", e);
+            bot.send_message(chat_id, &Messages::get(lang).error).await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[allow(dead_code)]
 pub async fn handle_subscribe(bot: Bot, msg: Message) -> ResponseResult<()> {
     let chat_id = msg.chat.id;
     let user_lang = queries::get_user(chat_id.0)
@@ -315,14 +513,15 @@ pub async fn handle_subscribe(bot: Bot, msg: Message) -> ResponseResult<()> {
 }
 
 pub async fn check_subscription(chat_id: ChatId) -> bool {
-    if let Ok(Some(user)) = queries::get_user(chat_id.0).await {
-        if let Some(ends_at) = user.subscription_ends_at {
-            return ends_at > Utc::now();
-        }
+    if let Ok(Some(user)) = queries::get_user(chat_id.0).await
+        && let Some(ends_at) = user.subscription_ends_at
+    {
+        return ends_at > Utc::now();
     }
     false
 }
 
+#[allow(dead_code)]
 pub async fn prompt_subscription(bot: &Bot, chat_id: ChatId, lang: &str) {
     let text = match lang {
         "ru" => "🔒 Доступно только по подписке. Подпишитесь за 299₽/мес для продолжения.",
@@ -344,17 +543,6 @@ pub async fn prompt_subscription(bot: &Bot, chat_id: ChatId, lang: &str) {
         .ok();
 }
 
-pub async fn send_daily_tip(bot: &Bot, chat_id: ChatId, lang: &str) {
-    let tip = match lang {
-        "ru" => "💡 Совет дня: Пей больше воды и следи за белками в рационе.",
-        "en" => "💡 Tip: Drink more water and mind your protein intake.",
-        "th" => "💡 เคล็ดลับ: ดื่มน้ำให้มากขึ้นและระวังโปรตีนในอาหาร.",
-        "zh" => "💡 小贴士：多喝水，注意蛋白质摄入。",
-        _ => "💡 Tip: Stay hydrated and eat balanced meals.",
-    };
-    bot.send_message(chat_id, tip).await.ok();
-}
-
 pub async fn handle_subscribe_command(bot: &Bot, msg: &Message, lang: &str) -> Result<(), Box<dyn std::error::Error>> {
     let chat_id = msg.chat.id;
     let payment_url = get_url_link_pay(chat_id.0);
@@ -0,0 +1,279 @@
+use crate::db::models::{DailyTotals, FoodLog};
+use crate::db::queries;
+use crate::locales::messages::Messages;
+use crate::services::meal::MealType;
+use crate::telegram::handlers::get_user_tz;
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, MessageId},
+};
+
+/// Сколько дней показывается на одной странице истории.
+const HISTORY_PAGE_DAYS: i64 = 7;
+
+/// Обрабатывает команду `/history`: показывает первую (самую свежую) страницу.
+pub async fn handle_history_command(bot: &Bot, chat_id: ChatId, lang: &str) -> ResponseResult<()> {
+    let messages = Messages::get(lang);
+
+    match render_page(chat_id.0, 0, lang).await {
+        Ok(Some((text, markup))) => {
+            bot.send_message(chat_id, text).reply_markup(markup).await?;
+        }
+        Ok(None) => {
+            bot.send_message(chat_id, &messages.history_empty).await?;
+        }
+        Err(e) => {
+            log::error!("Error loading history for {}: {}", chat_id, e);
+            bot.send_message(chat_id, &messages.error).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Обрабатывает нажатия на кнопки истории (`hist:page:N`, `hist:day:YYYY-MM-DD:N`).
+pub async fn handle_history_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    data: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    bot.answer_callback_query(q.id.clone()).await?;
+
+    let Some(message) = q.message.as_ref() else {
+        return Ok(());
+    };
+    let chat_id = message.chat().id;
+    let message_id = message.id();
+    let messages = Messages::get(lang);
+
+    let mut parts = data.split(':').skip(1);
+    let result = match (parts.next(), parts.next(), parts.next()) {
+        (Some("page"), Some(page), None) => {
+            let page = page.parse::<i64>().unwrap_or(0).max(0);
+            render_page(chat_id.0, page, lang).await
+        }
+        (Some("day"), Some(date), Some(page)) => {
+            let page = page.parse::<i64>().unwrap_or(0).max(0);
+            match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+                Ok(date) => {
+                    let tz = get_user_tz(chat_id.0).await;
+                    render_day(chat_id.0, date, page, lang, tz).await.map(Some)
+                }
+                Err(_) => Ok(None),
+            }
+        }
+        _ => {
+            log::warn!("Unknown history callback: {}", data);
+            return Ok(());
+        }
+    };
+
+    match result {
+        Ok(Some((text, markup))) => edit(bot, chat_id, message_id, text, markup).await,
+        Ok(None) => {
+            bot.edit_message_text(chat_id, message_id, &messages.history_empty)
+                .await?;
+            Ok(())
+        }
+        Err(e) => {
+            log::error!("Error loading history for {}: {}", chat_id, e);
+            bot.send_message(chat_id, &messages.error).await?;
+            Ok(())
+        }
+    }
+}
+
+async fn edit(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    text: String,
+    markup: InlineKeyboardMarkup,
+) -> ResponseResult<()> {
+    match bot
+        .edit_message_text(chat_id, message_id, text)
+        .reply_markup(markup)
+        .await
+    {
+        Ok(_) => Ok(()),
+        // Telegram отвечает ошибкой, если содержимое не изменилось — это не страшно.
+        Err(teloxide::RequestError::Api(teloxide::ApiError::MessageNotModified)) => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Формирует страницу со списком дней. Возвращает `None`, если записей нет.
+async fn render_page(
+    chat_id: i64,
+    page: i64,
+    lang: &str,
+) -> Result<Option<(String, InlineKeyboardMarkup)>, sqlx::Error> {
+    let total_days = queries::count_history_days(chat_id).await?;
+    if total_days == 0 {
+        return Ok(None);
+    }
+
+    let total_pages = (total_days + HISTORY_PAGE_DAYS - 1) / HISTORY_PAGE_DAYS;
+    let page = page.min(total_pages - 1);
+    let days = queries::get_history_days(chat_id, HISTORY_PAGE_DAYS, page * HISTORY_PAGE_DAYS).await?;
+
+    let mut text = format!("{} ({}/{})\n", page_title(lang), page + 1, total_pages);
+    for day in &days {
+        text.push('\n');
+        text.push_str(&format_day_line(day, lang));
+    }
+
+    let mut rows: Vec<Vec<InlineKeyboardButton>> = days
+        .iter()
+        .map(|day| {
+            vec![InlineKeyboardButton::callback(
+                format!("📅 {} · {:.0} kcal", day.date.format("%d.%m"), day.calories),
+                format!("hist:day:{}:{}", day.date.format("%Y-%m-%d"), page),
+            )]
+        })
+        .collect();
+
+    // ◀️ — в прошлое (более старые дни), ▶️ — ближе к сегодняшнему дню.
+    let mut nav = Vec::new();
+    if page + 1 < total_pages {
+        nav.push(InlineKeyboardButton::callback("◀️", format!("hist:page:{}", page + 1)));
+    }
+    if page > 0 {
+        nav.push(InlineKeyboardButton::callback("▶️", format!("hist:page:{}", page - 1)));
+    }
+    if !nav.is_empty() {
+        rows.push(nav);
+    }
+
+    Ok(Some((text, InlineKeyboardMarkup::new(rows))))
+}
+
+/// Формирует подробный список записей за выбранный день, сгруппированный по приёмам пищи.
+async fn render_day(
+    chat_id: i64,
+    date: NaiveDate,
+    page: i64,
+    lang: &str,
+    tz: Tz,
+) -> Result<(String, InlineKeyboardMarkup), sqlx::Error> {
+    let logs = queries::get_food_logs_by_date(chat_id, date).await?;
+
+    let mut text = format!("📅 {}\n", date.format("%d.%m.%Y"));
+    for meal in MealType::ALL {
+        let items: Vec<&FoodLog> = logs
+            .iter()
+            .filter(|l| MealType::parse(&l.meal_type).unwrap_or(MealType::Snack) == meal)
+            .collect();
+        if items.is_empty() {
+            continue;
+        }
+
+        let (cal, pr, fa, ch) = sum_logs(&items);
+        text.push_str(&format!(
+            "\n{} {} — {:.0} kcal | 🥩 {:.1}P / 🧈 {:.1}F / 🍞 {:.1}C\n",
+            meal.emoji(),
+            meal.label(lang),
+            cal,
+            pr,
+            fa,
+            ch
+        ));
+        for log in items {
+            text.push_str(&format_log_line(log, tz));
+            text.push('\n');
+        }
+    }
+
+    let all: Vec<&FoodLog> = logs.iter().collect();
+    let (cal, pr, fa, ch) = sum_logs(&all);
+    text.push_str(&format!(
+        "\n📊 {}: {:.0} kcal | 🥩 {:.1}P / 🧈 {:.1}F / 🍞 {:.1}C",
+        total_label(lang),
+        cal,
+        pr,
+        fa,
+        ch
+    ));
+
+    let markup = InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(
+        back_label(lang),
+        format!("hist:page:{}", page),
+    )]]);
+
+    Ok((text, markup))
+}
+
+fn sum_logs(logs: &[&FoodLog]) -> (f32, f32, f32, f32) {
+    logs.iter().fold((0.0, 0.0, 0.0, 0.0), |acc, l| {
+        (
+            acc.0 + l.calories.unwrap_or(0.0),
+            acc.1 + l.proteins.unwrap_or(0.0),
+            acc.2 + l.fats.unwrap_or(0.0),
+            acc.3 + l.carbs.unwrap_or(0.0),
+        )
+    })
+}
+
+fn format_day_line(day: &DailyTotals, lang: &str) -> String {
+    format!(
+        "📅 {} — {:.0} kcal ({} {})\n    🥩 {:.1}P / 🧈 {:.1}F / 🍞 {:.1}C",
+        day.date.format("%d.%m.%Y"),
+        day.calories,
+        day.entries,
+        entries_label(lang),
+        day.proteins,
+        day.fats,
+        day.carbs
+    )
+}
+
+fn format_log_line(log: &FoodLog, tz: Tz) -> String {
+    format!(
+        "   🕒 {} {} — {:.0} kcal | 🥩 {:.1}P / 🧈 {:.1}F / 🍞 {:.1}C",
+        log.created_at.with_timezone(&tz).format("%H:%M"),
+        log.food_name,
+        log.calories.unwrap_or(0.0),
+        log.proteins.unwrap_or(0.0),
+        log.fats.unwrap_or(0.0),
+        log.carbs.unwrap_or(0.0)
+    )
+}
+
+fn page_title(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "📜 История питания",
+        "th" => "📜 ประวัติการกิน",
+        "zh" => "📜 饮食记录",
+        _ => "📜 Food history",
+    }
+}
+
+fn entries_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "зап.",
+        "th" => "รายการ",
+        "zh" => "条",
+        _ => "entries",
+    }
+}
+
+fn total_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Итого",
+        "th" => "รวม",
+        "zh" => "合计",
+        _ => "Total",
+    }
+}
+
+fn back_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "⬅️ Назад",
+        "th" => "⬅️ กลับ",
+        "zh" => "⬅️ 返回",
+        _ => "⬅️ Back",
+    }
+}
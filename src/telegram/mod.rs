@@ -1 +1,21 @@
+pub mod account;
+pub mod achievements;
+pub mod activity;
+pub mod barcode;
+pub mod diary_import;
+pub mod export;
+pub mod fasting;
+pub mod favorites;
+pub mod food_log;
 pub mod handlers;
+pub mod history;
+pub mod pdf_report;
+pub mod recipes;
+pub mod reminders;
+pub mod reports;
+pub mod scheduler;
+pub mod state;
+pub mod stats;
+pub mod tips;
+pub mod water;
+pub mod weight;
\ No newline at end of file
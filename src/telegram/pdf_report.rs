@@ -0,0 +1,338 @@
+use crate::db::models::User;
+use crate::db::queries;
+use crate::locales::messages::Messages;
+use crate::services::chart::{
+    draw_calorie_chart, draw_macro_donut_chart, draw_weight_chart, CalorieChart, MacroDonut,
+};
+use crate::services::log_time::local_to_utc;
+use crate::services::pdf_report::{render_pdf_report, PdfReport, ReportDay, ReportLabels};
+use crate::services::profile::{bmi, bmi_category, macro_split, macro_targets_g};
+use crate::telegram::handlers::get_user_tz;
+use crate::telegram::stats::{calorie_legend, day_bars, macro_labels};
+use crate::telegram::weight::{daily_weights, weight_legend};
+use chrono::{Duration, NaiveDate, NaiveTime, Utc};
+use std::collections::BTreeMap;
+use teloxide::{prelude::*, types::InputFile};
+
+/// Период по умолчанию, дней.
+const DEFAULT_DAYS: i64 = 30;
+/// Самый длинный период отчёта, дней.
+const MAX_RANGE_DAYS: i64 = 366;
+/// Окно скользящей средней на графике веса, дней.
+const TREND_WINDOW_DAYS: i64 = 7;
+
+/// `/report [YYYY-MM-DD YYYY-MM-DD | <дней>]` — PDF-отчёт о питании для диетолога;
+/// без периода — за последние 30 дней.
+pub async fn handle_report_command(
+    bot: &Bot,
+    chat_id: ChatId,
+    text: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    let messages = Messages::get(lang);
+    let tz = get_user_tz(chat_id.0).await;
+    let today = Utc::now().with_timezone(&tz).date_naive();
+    let Some((from, to)) = parse_args(text, today) else {
+        bot.send_message(chat_id, usage_text(lang)).await?;
+        return Ok(());
+    };
+
+    let loaded = async {
+        let user = queries::get_user(chat_id.0).await?;
+        let totals = queries::get_daily_totals_range(chat_id.0, from, to).await?;
+        let burned = queries::get_burned_by_day(chat_id.0, from, to).await?;
+        let since = local_to_utc(from, NaiveTime::MIN, tz).unwrap_or_else(Utc::now);
+        let until = local_to_utc(to + Duration::days(1), NaiveTime::MIN, tz).unwrap_or_else(Utc::now);
+        let weights = queries::get_weight_logs_between(chat_id.0, since, until).await?;
+        Ok::<_, sqlx::Error>((user, totals, burned, weights))
+    };
+    let (user, totals, burned, weights) = match loaded.await {
+        Ok(data) => data,
+        Err(e) => {
+            log::error!("Error loading data for PDF report: {}", e);
+            bot.send_message(chat_id, &messages.error).await?;
+            return Ok(());
+        }
+    };
+    if totals.is_empty() {
+        bot.send_message(chat_id, empty_text(lang)).await?;
+        return Ok(());
+    }
+
+    // В DejaVuSans нет тайских и китайских глифов, поэтому документ для них — на английском
+    let pdf_lang = if lang == "ru" { "ru" } else { "en" };
+    let burned: BTreeMap<NaiveDate, f32> = burned.into_iter().collect();
+    let bars = day_bars(from, to, &totals, &burned);
+    let days: Vec<ReportDay> = from
+        .iter_days()
+        .take_while(|date| *date <= to)
+        .map(|date| {
+            let day = totals.iter().find(|t| t.date == date);
+            ReportDay {
+                date,
+                entries: day.map_or(0, |d| d.entries),
+                calories: day.map_or(0.0, |d| d.calories),
+                proteins: day.map_or(0.0, |d| d.proteins),
+                fats: day.map_or(0.0, |d| d.fats),
+                carbs: day.map_or(0.0, |d| d.carbs),
+                burned: burned.get(&date).copied().unwrap_or(0.0),
+            }
+        })
+        .collect();
+    let weights = daily_weights(&weights, tz);
+
+    let goal = user.as_ref().and_then(|u| u.goal.as_deref());
+    let target = user.as_ref().and_then(|u| u.calorie_target);
+    let target_grams = target.map(|t| macro_targets_g(t, goal));
+    let logged: Vec<&ReportDay> = days.iter().filter(|d| d.entries > 0).collect();
+    let per_day = logged.len().max(1) as f32;
+    let average_grams = [
+        logged.iter().map(|d| d.proteins).sum::<f32>() / per_day,
+        logged.iter().map(|d| d.fats).sum::<f32>() / per_day,
+        logged.iter().map(|d| d.carbs).sum::<f32>() / per_day,
+    ];
+
+    let mut charts = Vec::new();
+    let calorie_chart = CalorieChart {
+        title: chart_titles(pdf_lang)[0],
+        days: &bars,
+        target: target.map(|t| t as f32),
+        legend: calorie_legend(pdf_lang),
+    };
+    let macro_chart = MacroDonut {
+        title: chart_titles(pdf_lang)[1],
+        labels: macro_labels(pdf_lang),
+        grams: average_grams,
+        target_percent: macro_split(goal),
+        target_grams,
+        target_label: target_label(pdf_lang),
+    };
+    let rendered = [
+        draw_calorie_chart(&calorie_chart),
+        draw_macro_donut_chart(&macro_chart),
+    ];
+    for result in rendered {
+        match result {
+            Ok(png) => charts.push(png),
+            Err(e) => log::warn!("Chart for PDF report skipped: {}", e),
+        }
+    }
+    if weights.len() >= 2 {
+        match draw_weight_chart(&weights, TREND_WINDOW_DAYS, &weight_legend(TREND_WINDOW_DAYS, pdf_lang)) {
+            Ok(png) => charts.push(png),
+            Err(e) => log::warn!("Weight chart for PDF report skipped: {}", e),
+        }
+    }
+
+    let period = format!("{} – {}", from.format("%d.%m.%Y"), to.format("%d.%m.%Y"));
+    let subtitle = format!("{} · {} {}", period, generated_label(pdf_lang), today.format("%d.%m.%Y"));
+    let profile = profile_rows(user.as_ref(), pdf_lang);
+    let targets = target_rows(target, target_grams, pdf_lang);
+    let summary = summary_rows(&days, target, &weights, pdf_lang);
+    let report = PdfReport {
+        title: caption_text(pdf_lang),
+        subtitle: &subtitle,
+        profile: &profile,
+        targets: &targets,
+        summary: &summary,
+        days: &days,
+        target_percent: macro_split(goal),
+        target_grams,
+        charts: &charts,
+        labels: report_labels(pdf_lang),
+    };
+
+    let bytes = match render_pdf_report(&report) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            log::error!("Error rendering PDF report: {}", e);
+            bot.send_message(chat_id, &messages.error).await?;
+            return Ok(());
+        }
+    };
+
+    let file_name = format!("kalorik-report-{}_{}.pdf", from, to);
+    bot.send_document(chat_id, InputFile::memory(bytes).file_name(file_name))
+        .caption(format!("📄 {} ({})", caption_text(lang), period))
+        .await?;
+
+    Ok(())
+}
+
+/// Разбирает период. `None` — непонятные аргументы или слишком длинный период.
+fn parse_args(text: &str, today: NaiveDate) -> Option<(NaiveDate, NaiveDate)> {
+    let args: Vec<&str> = text.trim_start_matches("/report").split_whitespace().collect();
+    let (from, to) = match args.as_slice() {
+        [] => (today - Duration::days(DEFAULT_DAYS - 1), today),
+        [days] => {
+            let days = days.parse::<i64>().ok().filter(|d| (1..=MAX_RANGE_DAYS).contains(d))?;
+            (today - Duration::days(days - 1), today)
+        }
+        [from, to] => (
+            NaiveDate::parse_from_str(from, "%Y-%m-%d").ok()?,
+            NaiveDate::parse_from_str(to, "%Y-%m-%d").ok()?,
+        ),
+        _ => return None,
+    };
+    (from <= to && (to - from).num_days() < MAX_RANGE_DAYS).then_some((from, to))
+}
+
+fn profile_rows(user: Option<&User>, lang: &str) -> Vec<(String, String)> {
+    let keys = match lang {
+        "ru" => ["Возраст", "Пол", "Рост", "Вес", "ИМТ", "Активность", "Цель"],
+        _ => ["Age", "Sex", "Height", "Weight", "BMI", "Activity", "Goal"],
+    };
+    let dash = || "—".to_string();
+    let Some(user) = user else {
+        return keys.iter().map(|key| (key.to_string(), dash())).collect();
+    };
+    let values = [
+        user.age.map_or_else(dash, |age| age.to_string()),
+        user.gender.clone().unwrap_or_else(dash),
+        user.height_cm.map_or_else(dash, |h| format!("{:.0} cm", h)),
+        user.weight_kg.map_or_else(dash, |w| format!("{:.1} kg", w)),
+        user.weight_kg
+            .and_then(|w| bmi(w, user.height_cm))
+            .map_or_else(dash, |b| format!("{:.1} ({})", b, bmi_category(b, lang))),
+        user.activity_level.clone().unwrap_or_else(dash),
+        user.goal.clone().unwrap_or_else(dash),
+    ];
+    keys.iter().map(|key| key.to_string()).zip(values).collect()
+}
+
+fn target_rows(target: Option<i32>, grams: Option<[f32; 3]>, lang: &str) -> Vec<(String, String)> {
+    let (Some(target), Some(grams)) = (target, grams) else {
+        return Vec::new();
+    };
+    let [calories, per_day] = match lang {
+        "ru" => ["Калории", "в день"],
+        _ => ["Calories", "per day"],
+    };
+    let mut rows = vec![(calories.to_string(), format!("{} kcal {}", target, per_day))];
+    for (label, value) in macro_labels(lang).iter().zip(grams) {
+        rows.push((label.to_string(), format!("{:.0} g {}", value, per_day)));
+    }
+    rows
+}
+
+/// Итоги периода: дни с записями, средние калории, дни в пределах нормы, изменение веса.
+fn summary_rows(
+    days: &[ReportDay],
+    target: Option<i32>,
+    weights: &[(NaiveDate, f32)],
+    lang: &str,
+) -> Vec<(String, String)> {
+    let keys = match lang {
+        "ru" => ["Дней с записями", "Записей о еде", "Калорий в среднем", "Сожжено в среднем", "Дней в пределах нормы", "Изменение веса"],
+        _ => ["Days logged", "Food entries", "Average calories", "Average burned", "Days within target", "Weight change"],
+    };
+    let logged: Vec<&ReportDay> = days.iter().filter(|d| d.entries > 0).collect();
+    let count = logged.len().max(1) as f32;
+    let mut rows = vec![
+        (keys[0].to_string(), format!("{} / {}", logged.len(), days.len())),
+        (keys[1].to_string(), logged.iter().map(|d| d.entries).sum::<i64>().to_string()),
+        (
+            keys[2].to_string(),
+            format!("{:.0} kcal", logged.iter().map(|d| d.calories).sum::<f32>() / count),
+        ),
+        (
+            keys[3].to_string(),
+            format!("{:.0} kcal", logged.iter().map(|d| d.burned).sum::<f32>() / count),
+        ),
+    ];
+    if let Some(target) = target {
+        let within = logged
+            .iter()
+            .filter(|d| d.calories - d.burned <= target as f32)
+            .count();
+        rows.push((keys[4].to_string(), format!("{} / {}", within, logged.len())));
+    }
+    if let (Some((_, first)), Some((_, last))) = (weights.first(), weights.last())
+        && weights.len() >= 2
+    {
+        rows.push((
+            keys[5].to_string(),
+            format!("{:.1} → {:.1} kg ({:+.1})", first, last, last - first),
+        ));
+    }
+    rows
+}
+
+fn report_labels(lang: &str) -> ReportLabels<'static> {
+    match lang {
+        "ru" => ReportLabels {
+            profile: "Профиль",
+            targets: "Суточные нормы",
+            summary: "Итоги периода",
+            macro_split: "Распределение БЖУ",
+            charts: "Графики",
+            daily: "По дням",
+            macros: macro_labels(lang),
+            macro_columns: ["Нутриент", "В среднем", "Доля энергии", "Рекомендуется", "Норма"],
+            day_columns: ["Дата", "Записей", "ккал", "Белки", "Жиры", "Углев.", "Сожжено", "Итого"],
+            average: "Среднее",
+            page: "стр.",
+        },
+        _ => ReportLabels {
+            profile: "Profile",
+            targets: "Daily targets",
+            summary: "Period summary",
+            macro_split: "Macronutrient split",
+            charts: "Charts",
+            daily: "Day by day",
+            macros: macro_labels(lang),
+            macro_columns: ["Nutrient", "Average", "Energy share", "Recommended", "Target"],
+            day_columns: ["Date", "Entries", "kcal", "Protein", "Fat", "Carbs", "Burned", "Net"],
+            average: "Average",
+            page: "page",
+        },
+    }
+}
+
+fn target_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "норма",
+        _ => "target",
+    }
+}
+
+fn generated_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "сформирован",
+        _ => "generated",
+    }
+}
+
+fn chart_titles(lang: &str) -> [&'static str; 2] {
+    match lang {
+        "ru" => ["Калории по дням", "БЖУ в среднем за день"],
+        _ => ["Calories per day", "Average daily macros"],
+    }
+}
+
+fn caption_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Отчёт о питании",
+        "th" => "รายงานโภชนาการ",
+        "zh" => "营养报告",
+        _ => "Nutrition report",
+    }
+}
+
+fn empty_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "📭 Нет записей о еде за этот период — отчёт строить не из чего.",
+        "th" => "📭 ไม่มีบันทึกอาหารในช่วงนี้ จึงสร้างรายงานไม่ได้",
+        "zh" => "📭 这段时间没有饮食记录，无法生成报告。",
+        _ => "📭 No food logged for this period, so there's nothing to report.",
+    }
+}
+
+fn usage_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "/report — PDF-отчёт за последние 30 дней\n/report 14 — за последние 14 дней\n/report 2026-09-01 2026-09-30 — за период (не длиннее года)",
+        "th" => "/report — รายงาน PDF 30 วันล่าสุด\n/report 14 — 14 วันล่าสุด\n/report 2026-09-01 2026-09-30 — ตามช่วงวันที่ (ไม่เกิน 1 ปี)",
+        "zh" => "/report — 最近30天的 PDF 报告\n/report 14 — 最近14天\n/report 2026-09-01 2026-09-30 — 指定时间段（不超过一年）",
+        _ => "/report — a PDF report for the last 30 days\n/report 14 — the last 14 days\n/report 2026-09-01 2026-09-30 — a date range (up to a year)",
+    }
+}
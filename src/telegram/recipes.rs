@@ -0,0 +1,472 @@
+use crate::db::models::{Micronutrients, NewRecipe, Recipe, RecipeIngredient};
+use crate::db::queries;
+use crate::locales::messages::Messages;
+use crate::services::food_db;
+use crate::services::log_time::{extract_time_hint, TimeHint};
+use crate::services::nutrition::{FoodSummary, InputSource};
+use crate::services::recipes::{find_recipe_portion, RecipePortion};
+use crate::telegram::food_log;
+use crate::telegram::state::{self, PendingInput};
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup},
+};
+
+/// Максимальная длина названия рецепта.
+const RECIPE_NAME_LEN: usize = 64;
+
+/// `/recipe new` начинает диалог создания рецепта, `/recipe` и `/recipes` показывают список.
+pub async fn handle_recipe_command(
+    bot: &Bot,
+    chat_id: ChatId,
+    text: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    let arg = text
+        .trim_start_matches("/recipes")
+        .trim_start_matches("/recipe")
+        .trim();
+
+    if arg == "new" {
+        state::set_pending(chat_id.0, PendingInput::RecipeName);
+        bot.send_message(chat_id, name_prompt(lang)).await?;
+        return Ok(());
+    }
+
+    match render_recipes(chat_id.0, lang).await {
+        Ok(Some((text, markup))) => {
+            bot.send_message(chat_id, text).reply_markup(markup).await?;
+        }
+        Ok(None) => {
+            bot.send_message(chat_id, empty_text(lang)).await?;
+        }
+        Err(e) => {
+            log::error!("Error loading recipes for {}: {}", chat_id, e);
+            bot.send_message(chat_id, &Messages::get(lang).error).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Обрабатывает очередной шаг диалога `/recipe new`.
+pub async fn handle_recipe_input(
+    bot: &Bot,
+    chat_id: ChatId,
+    pending: PendingInput,
+    text: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    match pending {
+        PendingInput::RecipeName => {
+            let name = text.trim();
+            if name.is_empty() || name.chars().count() > RECIPE_NAME_LEN {
+                state::set_pending(chat_id.0, PendingInput::RecipeName);
+                bot.send_message(chat_id, name_prompt(lang)).await?;
+                return Ok(());
+            }
+            let draft = NewRecipe {
+                name: name.to_string(),
+                servings: 1,
+                ..Default::default()
+            };
+            state::set_pending(chat_id.0, PendingInput::RecipeIngredients(draft));
+            bot.send_message(chat_id, ingredients_prompt(lang)).await?;
+        }
+        PendingInput::RecipeIngredients(mut draft) => {
+            // Список «500 г фарша, 250 г пасты» — отдельные ингредиенты, чтобы каждый
+            // можно было увидеть и пересчитать в рецепте.
+            let segments = food_db::split_segments(text)
+                .into_iter()
+                .map(str::trim)
+                .filter(|segment| !food_db::strip_stopwords(segment).is_empty());
+            let mut added = Vec::new();
+            let mut failed = Vec::new();
+            for segment in segments {
+                match food_db::resolve_food_description(segment, lang).await {
+                    Ok((summary, suggestion)) => {
+                        draft.ingredients.push(RecipeIngredient {
+                            description: segment.to_string(),
+                            calories: summary.calories.unwrap_or(0.0),
+                            proteins: summary.proteins.unwrap_or(0.0),
+                            fats: summary.fats.unwrap_or(0.0),
+                            carbs: summary.carbs.unwrap_or(0.0),
+                        });
+                        added.push(format!("➕ {}", suggestion));
+                    }
+                    Err(e) => {
+                        log::error!("Error resolving recipe ingredient: {}", e);
+                        failed.push(segment);
+                    }
+                }
+            }
+            if added.is_empty() {
+                state::set_pending(chat_id.0, PendingInput::RecipeIngredients(draft));
+                bot.send_message(chat_id, &Messages::get(lang).unknown).await?;
+                return Ok(());
+            }
+
+            let total: f32 = draft.ingredients.iter().map(|i| i.calories).sum();
+            let mut reply = added.join("\n\n");
+            if !failed.is_empty() {
+                reply.push_str(&format!("\n\n❓ {}: {}", not_recognized_label(lang), failed.join(", ")));
+            }
+            reply.push_str(&format!(
+                "\n\n{}: {} · {:.0} kcal",
+                total_label(lang),
+                draft.ingredients.len(),
+                total
+            ));
+            state::set_pending(chat_id.0, PendingInput::RecipeIngredients(draft));
+            bot.send_message(chat_id, reply)
+                .reply_markup(done_keyboard(lang))
+                .await?;
+        }
+        PendingInput::RecipeWeight(mut draft) => {
+            let grams = food_db::extract_grams(text)
+                .map(|(grams, _)| grams)
+                .or_else(|| parse_number(text))
+                .filter(|g| *g > 0.0);
+            let Some(grams) = grams else {
+                state::set_pending(chat_id.0, PendingInput::RecipeWeight(draft));
+                bot.send_message(chat_id, weight_prompt(lang)).await?;
+                return Ok(());
+            };
+            draft.cooked_weight_g = grams;
+            state::set_pending(chat_id.0, PendingInput::RecipeServings(draft));
+            bot.send_message(chat_id, servings_prompt(lang)).await?;
+        }
+        PendingInput::RecipeServings(mut draft) => {
+            let servings = parse_number(text)
+                .map(|n| n.round() as i32)
+                .filter(|n| (1..=100).contains(n));
+            let Some(servings) = servings else {
+                state::set_pending(chat_id.0, PendingInput::RecipeServings(draft));
+                bot.send_message(chat_id, servings_prompt(lang)).await?;
+                return Ok(());
+            };
+            draft.servings = servings;
+            save(bot, chat_id, &draft, lang).await?;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+async fn save(bot: &Bot, chat_id: ChatId, draft: &NewRecipe, lang: &str) -> ResponseResult<()> {
+    let recipe = match queries::save_recipe(chat_id.0, draft).await {
+        Ok(id) => queries::get_recipe(chat_id.0, id).await,
+        Err(e) => Err(e),
+    };
+
+    match recipe {
+        Ok(Some(recipe)) => {
+            let (calories, proteins, fats, carbs) = recipe.nutrition_for(RecipePortion::Servings(1.0));
+            let (calories_100, _, _, _) = recipe.nutrition_for(RecipePortion::Grams(100.0));
+            let text = format!(
+                "✅ {} «{}»\n⚖️ {:.0} g · {} × {:.0} g\n🍽 {:.0} kcal | 🥩 {:.1}P / 🧈 {:.1}F / 🍞 {:.1}C\n💯 100 g: {:.0} kcal\n\n{}",
+                saved_label(lang),
+                recipe.name,
+                recipe.cooked_weight_g,
+                recipe.servings,
+                recipe.serving_weight_g(),
+                calories,
+                proteins,
+                fats,
+                carbs,
+                calories_100,
+                usage_hint(lang, &recipe.name)
+            );
+            bot.send_message(chat_id, text)
+                .reply_markup(InlineKeyboardMarkup::new(vec![vec![
+                    InlineKeyboardButton::callback(
+                        log_serving_label(lang),
+                        format!("rcp:log:{}", recipe.id),
+                    ),
+                ]]))
+                .await?;
+        }
+        Ok(None) => {}
+        Err(e) => {
+            log::error!("Error saving recipe for {}: {}", chat_id, e);
+            bot.send_message(chat_id, &Messages::get(lang).error).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Обрабатывает кнопки `rcp:done`, `rcp:log:<id>` и `rcp:del:<id>`.
+pub async fn handle_recipe_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    data: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    bot.answer_callback_query(q.id.clone()).await?;
+
+    let Some(message) = q.message.as_ref() else {
+        return Ok(());
+    };
+    let chat_id = message.chat().id;
+    let parts: Vec<&str> = data.split(':').collect();
+
+    match parts.as_slice() {
+        ["rcp", "done"] => match state::take_pending(chat_id.0) {
+            Some(PendingInput::RecipeIngredients(draft)) if !draft.ingredients.is_empty() => {
+                state::set_pending(chat_id.0, PendingInput::RecipeWeight(draft));
+                bot.send_message(chat_id, weight_prompt(lang)).await?;
+            }
+            Some(other) => state::set_pending(chat_id.0, other),
+            None => {}
+        },
+        ["rcp", "log", id] => {
+            let Ok(id) = id.parse::<i32>() else {
+                return Ok(());
+            };
+            match queries::get_recipe(chat_id.0, id).await {
+                Ok(Some(recipe)) => {
+                    log_recipe(
+                        bot,
+                        chat_id,
+                        lang,
+                        &recipe,
+                        RecipePortion::Servings(1.0),
+                        TimeHint::default(),
+                    )
+                    .await?;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    log::error!("Error in get_recipe: {}", e);
+                    bot.send_message(chat_id, &Messages::get(lang).error).await?;
+                }
+            }
+        }
+        ["rcp", "del", id] => {
+            let Ok(id) = id.parse::<i32>() else {
+                return Ok(());
+            };
+            if let Err(e) = queries::delete_recipe(chat_id.0, id).await {
+                log::error!("Error in delete_recipe: {}", e);
+                bot.send_message(chat_id, &Messages::get(lang).error).await?;
+                return Ok(());
+            }
+            match render_recipes(chat_id.0, lang).await {
+                Ok(Some((text, markup))) => {
+                    bot.edit_message_text(chat_id, message.id(), text)
+                        .reply_markup(markup)
+                        .await?;
+                }
+                Ok(None) => {
+                    bot.edit_message_text(chat_id, message.id(), empty_text(lang))
+                        .await?;
+                }
+                Err(e) => log::error!("Error loading recipes for {}: {}", chat_id, e),
+            }
+        }
+        _ => log::warn!("Unknown recipe callback: {}", data),
+    }
+
+    Ok(())
+}
+
+/// Записывает сообщение вида «150 г моей лазаньи», если оно ссылается на рецепт
+/// пользователя. Возвращает `false`, если рецепт не найден.
+pub async fn try_log_recipe_text(
+    bot: &Bot,
+    chat_id: ChatId,
+    text: &str,
+    lang: &str,
+) -> ResponseResult<bool> {
+    let recipes = match queries::get_recipes(chat_id.0).await {
+        Ok(recipes) => recipes,
+        Err(e) => {
+            log::error!("Error in get_recipes: {}", e);
+            return Ok(false);
+        }
+    };
+
+    let Some((recipe, portion)) = find_recipe_portion(text, &recipes) else {
+        return Ok(false);
+    };
+    log_recipe(bot, chat_id, lang, recipe, portion, extract_time_hint(text)).await?;
+
+    Ok(true)
+}
+
+async fn log_recipe(
+    bot: &Bot,
+    chat_id: ChatId,
+    lang: &str,
+    recipe: &Recipe,
+    portion: RecipePortion,
+    time_hint: TimeHint,
+) -> ResponseResult<()> {
+    let grams = portion.grams(recipe);
+    let (calories, proteins, fats, carbs) = recipe.nutrition_for(portion);
+    let summary = FoodSummary {
+        name: format!("{} — {:.0} g", recipe.name, grams),
+        calories: Some(calories),
+        proteins: Some(proteins),
+        fats: Some(fats),
+        carbs: Some(carbs),
+        time_hint,
+        micros: Micronutrients::default(),
+        model: None,
+    };
+    let suggestion = format!(
+        "🍲 {} · {:.0} g: {:.0} kcal | 🥩 {:.1}P / 🧈 {:.1}F / 🍞 {:.1}C",
+        recipe.name, grams, calories, proteins, fats, carbs
+    );
+
+    let provenance = InputSource::Recipe.provenance();
+    food_log::log_food_and_reply(bot, chat_id, lang, &summary, &suggestion, provenance).await
+}
+
+/// Формирует список рецептов с кнопками записи порции. Возвращает `None`, если рецептов нет.
+async fn render_recipes(
+    chat_id: i64,
+    lang: &str,
+) -> Result<Option<(String, InlineKeyboardMarkup)>, sqlx::Error> {
+    let recipes = queries::get_recipes(chat_id).await?;
+    if recipes.is_empty() {
+        return Ok(None);
+    }
+
+    let rows = recipes
+        .iter()
+        .map(|recipe| {
+            let (calories, _, _, _) = recipe.nutrition_for(RecipePortion::Servings(1.0));
+            vec![
+                InlineKeyboardButton::callback(
+                    format!(
+                        "🍲 {} · {:.0} g · {:.0} kcal",
+                        recipe.name,
+                        recipe.serving_weight_g(),
+                        calories
+                    ),
+                    format!("rcp:log:{}", recipe.id),
+                ),
+                InlineKeyboardButton::callback("🗑", format!("rcp:del:{}", recipe.id)),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Some((title_text(lang).to_string(), InlineKeyboardMarkup::new(rows))))
+}
+
+fn done_keyboard(lang: &str) -> InlineKeyboardMarkup {
+    let label = match lang {
+        "ru" => "✅ Готово",
+        "th" => "✅ เสร็จแล้ว",
+        "zh" => "✅ 完成",
+        _ => "✅ Done",
+    };
+    InlineKeyboardMarkup::new(vec![vec![InlineKeyboardButton::callback(label, "rcp:done")]])
+}
+
+fn parse_number(text: &str) -> Option<f32> {
+    text.split_whitespace().next()?.replace(',', ".").parse().ok()
+}
+
+fn name_prompt(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "🍲 Как называется блюдо? Например: Лазанья",
+        "th" => "🍲 เมนูนี้ชื่ออะไร? เช่น ลาซานญ่า",
+        "zh" => "🍲 这道菜叫什么名字？例如：千层面",
+        _ => "🍲 What is the dish called? For example: Lasagna",
+    }
+}
+
+fn ingredients_prompt(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "🥕 Отправляйте ингредиенты с весом — по одному или списком, например: «500 г фарша, 250 г пасты». Когда закончите, нажмите «✅ Готово».",
+        "th" => "🥕 ส่งวัตถุดิบพร้อมน้ำหนัก ทีละรายการหรือเป็นรายการ เช่น «หมูสับ 500 ก., พาสต้า 250 ก.» เสร็จแล้วกด «✅ เสร็จแล้ว»",
+        "zh" => "🥕 发送食材及重量，可逐条或一次列出，例如：«500克肉末，250克意面»。完成后点击 «✅ 完成»。",
+        _ => "🥕 Send the ingredients with weights, one by one or as a list, e.g. \"500 g minced beef, 250 g pasta\". Tap \"✅ Done\" when finished.",
+    }
+}
+
+fn weight_prompt(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "⚖️ Сколько весит готовое блюдо? Например: 1800 г",
+        "th" => "⚖️ อาหารที่ปรุงเสร็จหนักเท่าไหร่? เช่น 1800 ก.",
+        "zh" => "⚖️ 做好的菜总重多少？例如：1800克",
+        _ => "⚖️ What is the total cooked weight? For example: 1800 g",
+    }
+}
+
+fn servings_prompt(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "🍽 На сколько порций рассчитано блюдо?",
+        "th" => "🍽 เมนูนี้แบ่งได้กี่ที่?",
+        "zh" => "🍽 这道菜一共几份？",
+        _ => "🍽 How many servings does it make?",
+    }
+}
+
+fn not_recognized_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Не удалось распознать",
+        "th" => "ไม่รู้จัก",
+        "zh" => "无法识别",
+        _ => "Not recognised",
+    }
+}
+
+fn total_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Ингредиентов",
+        "th" => "วัตถุดิบ",
+        "zh" => "食材",
+        _ => "Ingredients",
+    }
+}
+
+fn saved_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Рецепт сохранён:",
+        "th" => "บันทึกสูตรแล้ว:",
+        "zh" => "食谱已保存：",
+        _ => "Recipe saved:",
+    }
+}
+
+fn log_serving_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "🍽 Записать порцию",
+        "th" => "🍽 บันทึก 1 ที่",
+        "zh" => "🍽 记录一份",
+        _ => "🍽 Log a serving",
+    }
+}
+
+fn usage_hint(lang: &str, name: &str) -> String {
+    let name = name.to_lowercase();
+    match lang {
+        "ru" => format!("Чтобы записать, напишите «150 г {}» или откройте /recipes.", name),
+        "th" => format!("บันทึกได้โดยพิมพ์ «{} 150 ก.» หรือเปิด /recipes", name),
+        "zh" => format!("记录时发送 «150克 {}» 或打开 /recipes。", name),
+        _ => format!("To log it, send \"150 g of my {}\" or open /recipes.", name),
+    }
+}
+
+fn title_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "🍲 Ваши рецепты — нажмите, чтобы записать порцию:",
+        "th" => "🍲 สูตรอาหารของคุณ — แตะเพื่อบันทึก 1 ที่:",
+        "zh" => "🍲 您的食谱 — 点击记录一份：",
+        _ => "🍲 Your recipes — tap to log a serving:",
+    }
+}
+
+fn empty_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "🍲 Рецептов пока нет. Создайте первый: /recipe new",
+        "th" => "🍲 ยังไม่มีสูตรอาหาร สร้างสูตรแรก: /recipe new",
+        "zh" => "🍲 还没有食谱。创建第一个：/recipe new",
+        _ => "🍲 No recipes yet. Create one: /recipe new",
+    }
+}
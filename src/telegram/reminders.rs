@@ -0,0 +1,389 @@
+use crate::db::models::MealReminder;
+use crate::db::queries;
+use crate::locales::messages::Messages;
+use crate::services::meal::MealType;
+use crate::services::reminders::{in_quiet_hours, is_due, parse_quiet_hours, parse_time};
+use crate::telegram::handlers::{get_user_lang, get_user_tz};
+use chrono::{Duration, Utc};
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup},
+};
+
+/// Варианты отсрочки напоминания, минут.
+const SNOOZE_OPTIONS: [i64; 2] = [30, 60];
+
+/// `/remind lunch 14:00` — напомнить, если обед не записан к 14:00;
+/// `/remind lunch 13:00 always` — напоминать всегда; `/remind lunch off`;
+/// `/remind quiet 22:00-08:00` / `/remind quiet off`; без аргументов — список напоминаний.
+pub async fn handle_remind_command(
+    bot: &Bot,
+    chat_id: ChatId,
+    text: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    let args: Vec<&str> = text
+        .trim_start_matches("/remind")
+        .split_whitespace()
+        .collect();
+    let messages = Messages::get(lang);
+
+    let result = match args.as_slice() {
+        [] => {
+            bot.send_message(chat_id, list_text(chat_id.0, lang).await)
+                .await?;
+            return Ok(());
+        }
+        ["quiet", "off"] => queries::set_quiet_hours(chat_id.0, None).await,
+        ["quiet", range] => {
+            let Some(quiet_hours) = parse_quiet_hours(range) else {
+                bot.send_message(chat_id, usage_text(lang)).await?;
+                return Ok(());
+            };
+            queries::register_user(chat_id.0).await.ok();
+            queries::set_quiet_hours(chat_id.0, Some(quiet_hours)).await
+        }
+        [meal, "off"] => {
+            let Some(meal) = MealType::from_name(meal) else {
+                bot.send_message(chat_id, usage_text(lang)).await?;
+                return Ok(());
+            };
+            let reminders = queries::get_meal_reminders(chat_id.0)
+                .await
+                .unwrap_or_default();
+            match reminders.iter().find(|r| r.meal_type == meal.as_str()) {
+                Some(reminder) => queries::delete_meal_reminder(chat_id.0, reminder.id)
+                    .await
+                    .map(|_| ()),
+                None => Ok(()),
+            }
+        }
+        [meal, time, rest @ ..] if rest.is_empty() || rest == ["always"] => {
+            let (Some(meal), Some(time)) = (MealType::from_name(meal), parse_time(time)) else {
+                bot.send_message(chat_id, usage_text(lang)).await?;
+                return Ok(());
+            };
+            queries::register_user(chat_id.0).await.ok();
+            queries::upsert_meal_reminder(chat_id.0, meal.as_str(), time, rest.is_empty())
+                .await
+                .map(|_| ())
+        }
+        _ => {
+            bot.send_message(chat_id, usage_text(lang)).await?;
+            return Ok(());
+        }
+    };
+
+    match result {
+        Ok(()) => {
+            bot.send_message(chat_id, format!("✅ {}", list_text(chat_id.0, lang).await))
+                .await?;
+        }
+        Err(e) => {
+            log::error!("Error saving reminder settings: {}", e);
+            bot.send_message(chat_id, &messages.error).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Кнопки под напоминанием: `rem:snooze:{id}:{минут}` и `rem:off:{id}`.
+pub async fn handle_reminder_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    data: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    let Some(message) = q.message.as_ref() else {
+        bot.answer_callback_query(q.id.clone()).await?;
+        return Ok(());
+    };
+    let chat_id = message.chat().id;
+    let parts: Vec<&str> = data.split(':').collect();
+
+    let reply = match parts.as_slice() {
+        ["rem", "snooze", id, minutes] => match (id.parse::<i32>(), minutes.parse::<i64>()) {
+            (Ok(id), Ok(minutes)) if SNOOZE_OPTIONS.contains(&minutes) => {
+                let until = Utc::now() + Duration::minutes(minutes);
+                queries::snooze_meal_reminder(chat_id.0, id, until)
+                    .await
+                    .map(|found| found.then(|| snoozed_text(minutes, lang)))
+            }
+            _ => Ok(None),
+        },
+        ["rem", "off", id] => match id.parse::<i32>() {
+            Ok(id) => queries::delete_meal_reminder(chat_id.0, id)
+                .await
+                .map(|found| found.then(|| turned_off_text(lang).to_string())),
+            Err(_) => Ok(None),
+        },
+        _ => {
+            log::warn!("Unknown reminder callback: {}", data);
+            Ok(None)
+        }
+    };
+
+    match reply {
+        Ok(Some(text)) => {
+            bot.answer_callback_query(q.id.clone()).await?;
+            bot.edit_message_text(chat_id, message.id(), text).await?;
+        }
+        Ok(None) => {
+            bot.answer_callback_query(q.id.clone()).await?;
+        }
+        Err(e) => {
+            log::error!("Error in reminder callback: {}", e);
+            bot.answer_callback_query(q.id.clone())
+                .text(Messages::get(lang).error)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Отправляет напоминания, время которых наступило. Вызывается планировщиком;
+/// состояние хранится в базе, поэтому после перезапуска бота ничего не теряется.
+pub async fn send_due_reminders(bot: &Bot) {
+    let reminders = match queries::get_all_meal_reminders().await {
+        Ok(reminders) => reminders,
+        Err(e) => {
+            log::error!("Error in get_all_meal_reminders: {}", e);
+            return;
+        }
+    };
+
+    let now = Utc::now();
+    for reminder in reminders {
+        let tz = get_user_tz(reminder.chat_id).await;
+        let local = now.with_timezone(&tz);
+        let today = local.date_naive();
+        if !is_due(&reminder, today, local.time(), now) {
+            continue;
+        }
+        let quiet_hours = queries::get_quiet_hours(reminder.chat_id)
+            .await
+            .ok()
+            .flatten();
+        if in_quiet_hours(local.time(), quiet_hours) {
+            continue;
+        }
+
+        if reminder.only_if_not_logged {
+            let midnight = today
+                .and_hms_opt(0, 0, 0)
+                .and_then(|dt| dt.and_local_timezone(tz).earliest())
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or(now - Duration::hours(24));
+            match queries::has_meal_logged_since(reminder.chat_id, &reminder.meal_type, midnight)
+                .await
+            {
+                Ok(false) => {}
+                Ok(true) => {
+                    mark_sent(&reminder, today).await;
+                    continue;
+                }
+                Err(e) => {
+                    log::error!("Error in has_meal_logged_since: {}", e);
+                    continue;
+                }
+            }
+        }
+
+        let lang = get_user_lang(reminder.chat_id).await;
+        let meal = MealType::parse(&reminder.meal_type).unwrap_or(MealType::Snack);
+        let sent = bot
+            .send_message(ChatId(reminder.chat_id), reminder_text(meal, &lang))
+            .reply_markup(reminder_keyboard(reminder.id, &lang))
+            .await;
+        if let Err(e) = sent {
+            log::warn!("Failed to send reminder {}: {}", reminder.id, e);
+        }
+        // Отмечаем даже при ошибке отправки, чтобы не повторять каждую минуту
+        mark_sent(&reminder, today).await;
+    }
+}
+
+/// Отложенное напоминание только снимает отсрочку: если оно сработало после полуночи,
+/// пометка `last_sent` новой датой отменила бы сегодняшнее обычное напоминание.
+async fn mark_sent(reminder: &MealReminder, today: chrono::NaiveDate) {
+    if reminder.snoozed_until.is_some() {
+        if let Err(e) = queries::clear_reminder_snooze(reminder.id).await {
+            log::error!("Error in clear_reminder_snooze: {}", e);
+        }
+    } else if let Err(e) = queries::mark_reminder_sent(reminder.id, today).await {
+        log::error!("Error in mark_reminder_sent: {}", e);
+    }
+}
+
+async fn list_text(chat_id: i64, lang: &str) -> String {
+    let reminders = queries::get_meal_reminders(chat_id)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("get_meal_reminders failed: {}", e);
+            Vec::new()
+        });
+    let quiet_hours = queries::get_quiet_hours(chat_id).await.ok().flatten();
+
+    let mut text = format!("⏰ {}:", title(lang));
+    if reminders.is_empty() {
+        text.push_str(&format!("\n{}", none_text(lang)));
+    }
+    for reminder in &reminders {
+        let meal = MealType::parse(&reminder.meal_type).unwrap_or(MealType::Snack);
+        text.push_str(&format!(
+            "\n{} {} — {}{}",
+            meal.emoji(),
+            meal.label(lang),
+            reminder.remind_at.format("%H:%M"),
+            if reminder.only_if_not_logged {
+                format!(" ({})", conditional_label(lang))
+            } else {
+                String::new()
+            }
+        ));
+    }
+    if let Some((start, end)) = quiet_hours {
+        text.push_str(&format!(
+            "\n🌙 {}: {}–{}",
+            quiet_label(lang),
+            start.format("%H:%M"),
+            end.format("%H:%M")
+        ));
+    }
+    text.push_str(&format!("\n\n{}", usage_text(lang)));
+    text
+}
+
+fn reminder_keyboard(reminder_id: i32, lang: &str) -> InlineKeyboardMarkup {
+    let mut row: Vec<InlineKeyboardButton> = SNOOZE_OPTIONS
+        .iter()
+        .map(|minutes| {
+            InlineKeyboardButton::callback(
+                format!("⏰ {}", snooze_label(*minutes, lang)),
+                format!("rem:snooze:{}:{}", reminder_id, minutes),
+            )
+        })
+        .collect();
+    row.push(InlineKeyboardButton::callback(
+        format!("🔕 {}", off_label(lang)),
+        format!("rem:off:{}", reminder_id),
+    ));
+    InlineKeyboardMarkup::new(vec![row])
+}
+
+fn reminder_text(meal: MealType, lang: &str) -> String {
+    let text = match lang {
+        "ru" => "Не забудьте записать",
+        "th" => "อย่าลืมบันทึก",
+        "zh" => "别忘了记录",
+        _ => "Don't forget to log your",
+    };
+    let hint = match lang {
+        "ru" => "Просто отправьте, что вы съели, текстом, фото или голосом.",
+        "th" => "ส่งสิ่งที่คุณกินเป็นข้อความ รูปภาพ หรือเสียงได้เลย",
+        "zh" => "直接发送文字、照片或语音描述您吃了什么。",
+        _ => "Just send what you ate as text, a photo or a voice message.",
+    };
+    let label = if lang == "ru" || lang == "th" || lang == "zh" {
+        meal.label(lang).to_string()
+    } else {
+        meal.label(lang).to_lowercase()
+    };
+    format!("⏰ {} {} {}\n{}", text, meal.emoji(), label, hint)
+}
+
+fn snooze_label(minutes: i64, lang: &str) -> String {
+    match (minutes % 60, lang) {
+        (0, "ru") => format!("{} ч", minutes / 60),
+        (0, "th") => format!("{} ชม.", minutes / 60),
+        (0, "zh") => format!("{}小时", minutes / 60),
+        (0, _) => format!("{} h", minutes / 60),
+        (_, "ru") => format!("{} мин", minutes),
+        (_, "th") => format!("{} นาที", minutes),
+        (_, "zh") => format!("{}分钟", minutes),
+        (_, _) => format!("{} min", minutes),
+    }
+}
+
+fn snoozed_text(minutes: i64, lang: &str) -> String {
+    let text = match lang {
+        "ru" => "Напомню через",
+        "th" => "จะเตือนอีกครั้งใน",
+        "zh" => "将在以下时间后再次提醒：",
+        _ => "I'll remind you again in",
+    };
+    format!("⏰ {} {}", text, snooze_label(minutes, lang))
+}
+
+fn turned_off_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "🔕 Напоминание отключено. Включить снова: /remind",
+        "th" => "🔕 ปิดการเตือนแล้ว เปิดอีกครั้ง: /remind",
+        "zh" => "🔕 提醒已关闭。重新开启：/remind",
+        _ => "🔕 Reminder turned off. Turn it back on: /remind",
+    }
+}
+
+fn off_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Отключить",
+        "th" => "ปิด",
+        "zh" => "关闭",
+        _ => "Turn off",
+    }
+}
+
+fn title(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Напоминания",
+        "th" => "การแจ้งเตือน",
+        "zh" => "提醒",
+        _ => "Reminders",
+    }
+}
+
+fn none_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Напоминаний пока нет.",
+        "th" => "ยังไม่มีการแจ้งเตือน",
+        "zh" => "暂无提醒。",
+        _ => "No reminders yet.",
+    }
+}
+
+fn conditional_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "если не записан",
+        "th" => "ถ้ายังไม่ได้บันทึก",
+        "zh" => "仅在未记录时",
+        _ => "if not logged",
+    }
+}
+
+fn quiet_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Тихие часы",
+        "th" => "ช่วงเวลาเงียบ",
+        "zh" => "免打扰时段",
+        _ => "Quiet hours",
+    }
+}
+
+fn usage_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => {
+            "/remind lunch 14:00 — напомнить, если обед не записан к 14:00\n/remind breakfast 8:30 always — напоминать всегда\n/remind lunch off — удалить напоминание\n/remind quiet 22:00-08:00 — тихие часы (/remind quiet off — отключить)\nПриёмы пищи: breakfast, lunch, dinner, snack (или завтрак, обед, ужин, перекус)"
+        }
+        "th" => {
+            "/remind lunch 14:00 — เตือนถ้ายังไม่ได้บันทึกมื้อกลางวันภายใน 14:00\n/remind breakfast 8:30 always — เตือนทุกครั้ง\n/remind lunch off — ลบการเตือน\n/remind quiet 22:00-08:00 — ช่วงเวลาเงียบ (/remind quiet off — ปิด)\nมื้ออาหาร: breakfast, lunch, dinner, snack"
+        }
+        "zh" => {
+            "/remind lunch 14:00 — 如果14:00前未记录午餐则提醒\n/remind breakfast 8:30 always — 每天都提醒\n/remind lunch off — 删除提醒\n/remind quiet 22:00-08:00 — 免打扰时段（/remind quiet off 关闭）\n餐次：breakfast、lunch、dinner、snack（或 早餐、午餐、晚餐、加餐）"
+        }
+        _ => {
+            "/remind lunch 14:00 — remind me if lunch isn't logged by 14:00\n/remind breakfast 8:30 always — always remind\n/remind lunch off — remove the reminder\n/remind quiet 22:00-08:00 — quiet hours (/remind quiet off to disable)\nMeals: breakfast, lunch, dinner, snack"
+        }
+    }
+}
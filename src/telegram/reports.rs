@@ -0,0 +1,393 @@
+use crate::db::models::ReportSettings;
+use crate::db::queries;
+use crate::locales::messages::Messages;
+use crate::services::chart::{draw_calorie_chart, label_lang, CalorieChart};
+use crate::services::profile::macro_targets_g;
+use crate::services::report::{build_report, logging_streak, PeriodReport, ReportKind};
+use crate::telegram::handlers::{get_user_lang, get_user_tz};
+use crate::telegram::stats::{calorie_legend, day_bars, macro_labels};
+use chrono::{NaiveDate, NaiveTime, Utc};
+use std::collections::BTreeMap;
+use teloxide::{prelude::*, types::InputFile};
+
+/// Максимальная длина подписи к фото в Telegram.
+const CAPTION_LIMIT: usize = 1024;
+
+/// За сколько дней назад искать начало серии записей.
+const STREAK_LOOKBACK_DAYS: i64 = 365;
+
+/// `/digest weekly on`, `/digest monthly off`, `/digest time 20:00`, `/digest now`;
+/// без аргументов — текущие настройки.
+pub async fn handle_digest_command(
+    bot: &Bot,
+    chat_id: ChatId,
+    text: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    let args: Vec<&str> = text.trim_start_matches("/digest").split_whitespace().collect();
+    let mut settings = match queries::get_report_settings(chat_id.0).await {
+        Ok(settings) => settings.unwrap_or_else(|| ReportSettings::new(chat_id.0)),
+        Err(e) => {
+            log::error!("Error in get_report_settings: {}", e);
+            bot.send_message(chat_id, &Messages::get(lang).error).await?;
+            return Ok(());
+        }
+    };
+
+    match args.as_slice() {
+        [] => {
+            bot.send_message(chat_id, settings_text(&settings, lang)).await?;
+            return Ok(());
+        }
+        ["now"] => {
+            let today = Utc::now().with_timezone(&get_user_tz(chat_id.0).await).date_naive();
+            return send_report(bot, chat_id, ReportKind::Weekly, today, lang).await;
+        }
+        [kind @ ("weekly" | "monthly"), toggle] => {
+            let Some(enabled) = parse_toggle(toggle) else {
+                bot.send_message(chat_id, usage_text(lang)).await?;
+                return Ok(());
+            };
+            if *kind == "weekly" {
+                settings.weekly = enabled;
+            } else {
+                settings.monthly = enabled;
+            }
+        }
+        ["time", time] => match NaiveTime::parse_from_str(time, "%H:%M") {
+            Ok(time) => settings.send_time = time,
+            Err(_) => {
+                bot.send_message(chat_id, usage_text(lang)).await?;
+                return Ok(());
+            }
+        },
+        _ => {
+            bot.send_message(chat_id, usage_text(lang)).await?;
+            return Ok(());
+        }
+    }
+
+    queries::register_user(chat_id.0).await.ok();
+    if let Err(e) = queries::save_report_settings(&settings).await {
+        log::error!("Error in save_report_settings: {}", e);
+        bot.send_message(chat_id, &Messages::get(lang).error).await?;
+        return Ok(());
+    }
+    bot.send_message(chat_id, format!("✅ {}", settings_text(&settings, lang)))
+        .await?;
+
+    Ok(())
+}
+
+/// Отправляет отчёты, время которых наступило по местному времени пользователя.
+/// Вызывается планировщиком.
+pub async fn send_due_reports(bot: &Bot) {
+    let subscriptions = match queries::get_enabled_report_settings().await {
+        Ok(subscriptions) => subscriptions,
+        Err(e) => {
+            log::error!("Error in get_enabled_report_settings: {}", e);
+            return;
+        }
+    };
+
+    for settings in subscriptions {
+        let local = Utc::now().with_timezone(&get_user_tz(settings.chat_id).await);
+        let (today, now) = (local.date_naive(), local.time());
+        let due = [
+            (ReportKind::Weekly, settings.weekly, settings.last_weekly_sent),
+            (ReportKind::Monthly, settings.monthly, settings.last_monthly_sent),
+        ];
+        for (kind, enabled, last_sent) in due {
+            if !enabled || !kind.is_due(today, now, settings.send_time, last_sent) {
+                continue;
+            }
+            let lang = get_user_lang(settings.chat_id).await;
+            if let Err(e) = send_report(bot, ChatId(settings.chat_id), kind, today, &lang).await {
+                log::warn!("Failed to send report to {}: {}", settings.chat_id, e);
+            }
+            // Отмечаем даже при ошибке отправки, чтобы не повторять отчёт каждую минуту
+            if let Err(e) = queries::mark_report_sent(settings.chat_id, kind == ReportKind::Weekly, today).await {
+                log::error!("Error in mark_report_sent: {}", e);
+            }
+        }
+    }
+}
+
+/// Собирает и отправляет отчёт за период, предшествующий местной дате `today`.
+async fn send_report(
+    bot: &Bot,
+    chat_id: ChatId,
+    kind: ReportKind,
+    today: NaiveDate,
+    lang: &str,
+) -> ResponseResult<()> {
+    let (from, to) = kind.period(today);
+    let totals = match queries::get_daily_totals_range(chat_id.0, from, to).await {
+        Ok(totals) => totals,
+        Err(e) => {
+            log::error!("Error in get_daily_totals_range: {}", e);
+            bot.send_message(chat_id, &Messages::get(lang).error).await?;
+            return Ok(());
+        }
+    };
+    let burned: BTreeMap<NaiveDate, f32> = queries::get_burned_by_day(chat_id.0, from, to)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("get_burned_by_day failed: {}", e);
+            Vec::new()
+        })
+        .into_iter()
+        .collect();
+    let user = queries::get_user(chat_id.0).await.ok().flatten();
+    let target = user.as_ref().and_then(|u| u.calorie_target);
+    let weights_days = (Utc::now().date_naive() - from).num_days() as i32 + 31;
+    let weights = queries::get_weight_logs(chat_id.0, weights_days)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("get_weight_logs failed: {}", e);
+            Vec::new()
+        });
+
+    let tz = get_user_tz(chat_id.0).await;
+    let report = build_report(from, to, &totals, &burned, target.map(|t| t as f32), &weights, tz);
+    let chart_title = title(kind, &report, label_lang(lang));
+    let title = title(kind, &report, lang);
+    if report.logged_days == 0 {
+        bot.send_message(chat_id, format!("📅 {}\n\n{}", title, empty_text(lang)))
+            .await?;
+        return Ok(());
+    }
+
+    let streak_dates: Vec<NaiveDate> = queries::get_daily_totals_range(
+        chat_id.0,
+        to - chrono::Duration::days(STREAK_LOOKBACK_DAYS),
+        to,
+    )
+    .await
+    .map(|days| days.into_iter().map(|d| d.date).collect())
+    .unwrap_or_default();
+    let streak = logging_streak(&streak_dates, to);
+
+    let macro_targets = target.map(|t| macro_targets_g(t, user.as_ref().and_then(|u| u.goal.as_deref())));
+    let text = format!(
+        "📅 {}\n\n{}",
+        title,
+        report_text(&report, target, macro_targets, streak, lang)
+    );
+
+    let days = day_bars(from, to, &totals, &burned);
+    let chart = CalorieChart {
+        title: &chart_title,
+        days: &days,
+        target: target.map(|t| t as f32),
+        legend: calorie_legend(label_lang(lang)),
+    };
+    match draw_calorie_chart(&chart) {
+        Ok(png) if text.chars().count() <= CAPTION_LIMIT => {
+            bot.send_photo(chat_id, InputFile::memory(png).file_name("report.png"))
+                .caption(text)
+                .await?;
+        }
+        Ok(png) => {
+            bot.send_photo(chat_id, InputFile::memory(png).file_name("report.png"))
+                .await?;
+            bot.send_message(chat_id, text).await?;
+        }
+        Err(e) => {
+            log::error!("Error drawing report chart: {}", e);
+            bot.send_message(chat_id, text).await?;
+        }
+    }
+
+    Ok(())
+}
+
+fn report_text(
+    report: &PeriodReport,
+    target: Option<i32>,
+    macro_targets: Option<[f32; 3]>,
+    streak: u32,
+    lang: &str,
+) -> String {
+    let mut text = format!(
+        "🍽 {}: {:.0} kcal",
+        average_label(lang),
+        report.avg_calories
+    );
+    if let Some(target) = target {
+        text.push_str(&format!(" / {}", target));
+    }
+    text.push_str(&format!(
+        " · 📝 {}/{}",
+        report.logged_days,
+        report.days()
+    ));
+
+    let labels = macro_labels(lang);
+    let emojis = ["🥩", "🧈", "🍞"];
+    let macros: Vec<String> = (0..3)
+        .map(|i| match macro_targets {
+            Some(targets) => format!(
+                "{} {} {:.0}/{:.0} g",
+                emojis[i], labels[i], report.avg_macros[i], targets[i]
+            ),
+            None => format!("{} {} {:.0} g", emojis[i], labels[i], report.avg_macros[i]),
+        })
+        .collect();
+    text.push_str(&format!("\n{}", macros.join(" · ")));
+
+    if let Some((date, calories)) = report.best_day {
+        text.push_str(&format!(
+            "\n🏆 {}: {} — {:.0} kcal",
+            best_label(lang),
+            date.format("%d.%m"),
+            calories
+        ));
+    }
+    if let Some((date, calories)) = report.worst_day.filter(|_| report.logged_days > 1) {
+        text.push_str(&format!(
+            "\n📉 {}: {} — {:.0} kcal",
+            worst_label(lang),
+            date.format("%d.%m"),
+            calories
+        ));
+    }
+    if let Some(adherence) = report.adherence_percent {
+        text.push_str(&format!("\n🎯 {}: {:.0}%", adherence_label(lang), adherence));
+    }
+    if let Some(change) = report.weight_change {
+        text.push_str(&format!("\n⚖️ {}: {:+.1} kg", weight_label(lang), change));
+    }
+    if streak > 0 {
+        text.push_str(&format!("\n🔥 {}: {}", streak_label(lang), streak));
+    }
+    text
+}
+
+fn parse_toggle(value: &str) -> Option<bool> {
+    match value {
+        "on" | "вкл" => Some(true),
+        "off" | "выкл" => Some(false),
+        _ => None,
+    }
+}
+
+fn title(kind: ReportKind, report: &PeriodReport, lang: &str) -> String {
+    let label = match (kind, lang) {
+        (ReportKind::Weekly, "ru") => "Отчёт за неделю",
+        (ReportKind::Weekly, "th") => "รายงานประจำสัปดาห์",
+        (ReportKind::Weekly, "zh") => "每周报告",
+        (ReportKind::Weekly, _) => "Weekly report",
+        (ReportKind::Monthly, "ru") => "Отчёт за месяц",
+        (ReportKind::Monthly, "th") => "รายงานประจำเดือน",
+        (ReportKind::Monthly, "zh") => "每月报告",
+        (ReportKind::Monthly, _) => "Monthly report",
+    };
+    format!(
+        "{} {}–{}",
+        label,
+        report.from.format("%d.%m"),
+        report.to.format("%d.%m")
+    )
+}
+
+fn settings_text(settings: &ReportSettings, lang: &str) -> String {
+    let state = |enabled: bool| match (enabled, lang) {
+        (true, "ru") => "вкл",
+        (false, "ru") => "выкл",
+        (true, "th") => "เปิด",
+        (false, "th") => "ปิด",
+        (true, "zh") => "开",
+        (false, "zh") => "关",
+        (true, _) => "on",
+        (false, _) => "off",
+    };
+    let (weekly, monthly, time) = match lang {
+        "ru" => ("Еженедельный отчёт (пн)", "Ежемесячный отчёт (1-го числа)", "Время отправки"),
+        "th" => ("รายงานประจำสัปดาห์ (จันทร์)", "รายงานประจำเดือน (วันที่ 1)", "เวลาส่ง"),
+        "zh" => ("每周报告（周一）", "每月报告（每月1日）", "发送时间"),
+        _ => ("Weekly report (Mon)", "Monthly report (1st)", "Send time"),
+    };
+    format!(
+        "📅 {}: {}\n🗓 {}: {}\n🕘 {}: {}\n\n{}",
+        weekly,
+        state(settings.weekly),
+        monthly,
+        state(settings.monthly),
+        time,
+        settings.send_time.format("%H:%M"),
+        usage_text(lang)
+    )
+}
+
+fn average_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "В среднем",
+        "th" => "เฉลี่ย",
+        "zh" => "平均",
+        _ => "Average",
+    }
+}
+
+fn best_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Лучший день",
+        "th" => "วันที่ดีที่สุด",
+        "zh" => "最佳一天",
+        _ => "Best day",
+    }
+}
+
+fn worst_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Худший день",
+        "th" => "วันที่แย่ที่สุด",
+        "zh" => "最差一天",
+        _ => "Worst day",
+    }
+}
+
+fn adherence_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Дней в пределах нормы",
+        "th" => "วันที่อยู่ในเป้าหมาย",
+        "zh" => "达标天数比例",
+        _ => "Days on target",
+    }
+}
+
+fn weight_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Вес",
+        "th" => "น้ำหนัก",
+        "zh" => "体重",
+        _ => "Weight",
+    }
+}
+
+fn streak_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Дней подряд с записями",
+        "th" => "บันทึกต่อเนื่อง (วัน)",
+        "zh" => "连续记录天数",
+        _ => "Logging streak, days",
+    }
+}
+
+fn empty_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "📭 За этот период нет записей о еде. Начните с сообщения вроде «овсянка 200 г».",
+        "th" => "📭 ไม่มีบันทึกอาหารในช่วงนี้ ลองส่งข้อความเช่น «ข้าวต้ม 200 กรัม»",
+        "zh" => "📭 这段时间没有饮食记录。试着发送 «燕麦粥 200克»。",
+        _ => "📭 No food logged in this period. Try sending something like \"oatmeal 200 g\".",
+    }
+}
+
+fn usage_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "/digest weekly on|off — еженедельный отчёт\n/digest monthly on|off — ежемесячный отчёт\n/digest time 20:00 — время отправки (по вашему часовому поясу)\n/digest now — отчёт за последние 7 дней сейчас",
+        "th" => "/digest weekly on|off — รายงานประจำสัปดาห์\n/digest monthly on|off — รายงานประจำเดือน\n/digest time 20:00 — เวลาส่ง (ตามเขตเวลาของคุณ)\n/digest now — รายงาน 7 วันล่าสุดตอนนี้",
+        "zh" => "/digest weekly on|off — 每周报告\n/digest monthly on|off — 每月报告\n/digest time 20:00 — 发送时间（按您的时区）\n/digest now — 立即查看最近7天报告",
+        _ => "/digest weekly on|off — weekly report\n/digest monthly on|off — monthly report\n/digest time 20:00 — send time (in your time zone)\n/digest now — report for the last 7 days right now",
+    }
+}
@@ -0,0 +1,24 @@
+use crate::services::retention;
+use crate::telegram::{fasting, reminders, reports, tips};
+use std::time::Duration;
+use teloxide::prelude::*;
+
+/// Как часто планировщик проверяет, не пора ли что-нибудь отправить.
+const TICK: Duration = Duration::from_secs(60);
+
+/// Запускает фоновые задачи бота: уведомления о завершении голодания, регулярные отчёты,
+/// напоминания о приёмах пищи, советы дня и ежесуточную очистку по правилам хранения.
+pub fn spawn(bot: Bot) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(TICK);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            interval.tick().await;
+            fasting::notify_due_fasts(&bot).await;
+            reports::send_due_reports(&bot).await;
+            reminders::send_due_reminders(&bot).await;
+            tips::send_due_tips(&bot).await;
+            retention::run_if_due().await;
+        }
+    });
+}
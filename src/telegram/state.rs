@@ -0,0 +1,81 @@
+use crate::db::models::{NewFoodLog, NewRecipe};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Текстовый ввод, которого бот ждёт от пользователя в многошаговых сценариях.
+#[derive(Debug, Clone)]
+pub enum PendingInput {
+    /// Новое время для записи `food_logs.id`.
+    LogTime(i32),
+    /// Вес порции продукта, найденного по штрихкоду.
+    ProductWeight(String),
+    /// Шаги диалога `/recipe new`: название, ингредиенты, вес готового блюда, порции.
+    RecipeName,
+    RecipeIngredients(NewRecipe),
+    RecipeWeight(NewRecipe),
+    RecipeServings(NewRecipe),
+}
+
+static PENDING: Lazy<Mutex<HashMap<i64, PendingInput>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub fn set_pending(chat_id: i64, input: PendingInput) {
+    PENDING.lock().unwrap().insert(chat_id, input);
+}
+
+/// Забирает ожидаемый ввод (после этого состояние сбрасывается).
+pub fn take_pending(chat_id: i64) -> Option<PendingInput> {
+    PENDING.lock().unwrap().remove(&chat_id)
+}
+
+pub fn clear_pending(chat_id: i64) {
+    PENDING.lock().unwrap().remove(&chat_id);
+}
+
+/// Сколько превью импорта остаётся действительным.
+const PENDING_IMPORT_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// Записи из загруженного файла, ожидающие подтверждения импорта.
+struct PendingImport {
+    id: u64,
+    created_at: Instant,
+    entries: Vec<NewFoodLog>,
+}
+
+static PENDING_IMPORTS: Lazy<Mutex<HashMap<i64, PendingImport>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+static NEXT_IMPORT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Сохраняет записи до подтверждения и возвращает номер импорта для кнопок превью.
+/// Более раннее превью того же чата перестаёт действовать.
+pub fn set_pending_import(chat_id: i64, entries: Vec<NewFoodLog>) -> u64 {
+    let id = NEXT_IMPORT_ID.fetch_add(1, Ordering::Relaxed);
+    let mut imports = PENDING_IMPORTS.lock().unwrap();
+    imports.retain(|_, import| import.created_at.elapsed() < PENDING_IMPORT_TTL);
+    imports.insert(
+        chat_id,
+        PendingImport {
+            id,
+            created_at: Instant::now(),
+            entries,
+        },
+    );
+    id
+}
+
+/// Забирает записи импорта `id` (после этого они удаляются). Превью с другим номером
+/// не трогает; устаревшее превью удаляет и возвращает `None`.
+pub fn take_pending_import(chat_id: i64, id: u64) -> Option<Vec<NewFoodLog>> {
+    let mut imports = PENDING_IMPORTS.lock().unwrap();
+    if imports.get(&chat_id)?.id != id {
+        return None;
+    }
+    let import = imports.remove(&chat_id)?;
+    (import.created_at.elapsed() < PENDING_IMPORT_TTL).then_some(import.entries)
+}
+
+pub fn clear_pending_import(chat_id: i64) {
+    PENDING_IMPORTS.lock().unwrap().remove(&chat_id);
+}
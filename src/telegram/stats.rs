@@ -0,0 +1,573 @@
+use crate::db::models::DailyTotals;
+use crate::db::queries;
+use crate::locales::messages::Messages;
+use crate::services::chart::{
+    draw_calorie_chart, draw_macro_donut_chart, label_lang, CalorieChart, CalorieLegend, DayBar,
+    MacroDonut,
+};
+use crate::services::meal::MealType;
+use crate::services::micronutrients::{Nutrient, Sex};
+use crate::services::profile::{macro_split, macro_targets_g};
+use crate::services::water::progress_bar;
+use crate::telegram::handlers::get_user_today;
+use crate::telegram::{fasting, water};
+use chrono::{Duration, NaiveDate};
+use std::collections::BTreeMap;
+use teloxide::{
+    prelude::*,
+    types::{InputFile, ParseMode},
+};
+
+/// Максимальный период для диаграммы БЖУ, дней.
+const MAX_CHART_DAYS: i64 = 90;
+
+/// `/stats` — итоги за сегодня; `/stats full` добавляет микронутриенты и нормы,
+/// `/stats chart [дней]` — диаграмму БЖУ за сегодня или за последние дни.
+pub async fn handle_stats_command(
+    bot: &Bot,
+    chat_id: ChatId,
+    text: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    let args: Vec<&str> = text.trim_start_matches("/stats").split_whitespace().collect();
+    let full = args.contains(&"full");
+    let chart_days = args
+        .iter()
+        .any(|a| matches!(*a, "chart" | "macros"))
+        .then(|| {
+            args.iter()
+                .find_map(|a| a.parse::<i64>().ok())
+                .unwrap_or(1)
+                .clamp(1, MAX_CHART_DAYS)
+        });
+    let today = get_user_today(chat_id.0).await;
+
+    match queries::get_daily_summary(chat_id.0).await {
+        Ok((calories, proteins, fats, carbs)) => {
+            let mut summary = format!(
+                "\u{1F4CA} *Daily Summary:*\nKcal: `{}`\nProtein: `{}`g\nFat: `{}`g\nCarbs: `{}`g",
+                calories, proteins, fats, carbs
+            );
+            let meals = queries::get_meal_summary(chat_id.0, today)
+                .await
+                .unwrap_or_else(|e| {
+                    log::warn!("get_meal_summary failed: {}", e);
+                    Vec::new()
+                });
+            if !meals.is_empty() {
+                summary.push('\n');
+            }
+            for meal in MealType::ALL {
+                if let Some(totals) = meals.iter().find(|m| m.meal_type == meal.as_str()) {
+                    summary.push_str(&format!(
+                        "\n{} {}: `{:.0}` kcal \\| `{:.1}`P / `{:.1}`F / `{:.1}`C",
+                        meal.emoji(),
+                        meal.label(lang),
+                        totals.calories,
+                        totals.proteins,
+                        totals.fats,
+                        totals.carbs
+                    ));
+                }
+            }
+            let burned = queries::get_daily_burned(chat_id.0, today)
+                .await
+                .unwrap_or_else(|e| {
+                    log::warn!("get_daily_burned failed: {}", e);
+                    0.0
+                });
+            if burned > 0.0 {
+                summary.push_str(&format!(
+                    "\n\n🔥 {}: `{:.0}` kcal \\| {}: `{:.0}` kcal",
+                    burned_label(lang),
+                    burned,
+                    net_label(lang),
+                    calories - burned
+                ));
+            }
+            if let Some(left) = remaining_budget(chat_id.0, calories, burned).await {
+                summary.push_str(&format!("\n{}", remaining_text(left, lang)));
+            }
+            if let Some((drunk, goal)) = water::daily_water(chat_id.0).await {
+                summary.push_str(&format!(
+                    "\n\n💧 {}: `{}` / {} ml {}",
+                    water_label(lang),
+                    drunk,
+                    goal,
+                    progress_bar(drunk, goal)
+                ));
+            }
+            if let Some(line) = fasting::stats_line(chat_id.0, lang).await {
+                summary.push_str(&format!("\n\n{}", line));
+            }
+            if full {
+                summary.push_str(&micronutrients_section(chat_id.0, lang).await);
+            }
+            bot.send_message(chat_id, summary)
+                .parse_mode(ParseMode::MarkdownV2)
+                .await?;
+        }
+        Err(e) => {
+            log::error!("Error in get_daily_summary: {}", e);
+            bot.send_message(chat_id, &Messages::get(lang).error).await?;
+            return Ok(());
+        }
+    }
+
+    if let Some(days) = chart_days {
+        send_macro_chart(bot, chat_id, days, lang).await?;
+    }
+
+    Ok(())
+}
+
+/// Отправляет кольцевую диаграмму БЖУ за последние `days` дней в сравнении с нормой.
+async fn send_macro_chart(bot: &Bot, chat_id: ChatId, days: i64, lang: &str) -> ResponseResult<()> {
+    let messages = Messages::get(lang);
+    let today = get_user_today(chat_id.0).await;
+    let from = today - Duration::days(days - 1);
+    let (calories, proteins, fats, carbs) = match queries::get_period_totals(chat_id.0, from, today).await {
+        Ok(totals) => totals,
+        Err(e) => {
+            log::error!("Error in get_period_totals: {}", e);
+            bot.send_message(chat_id, &messages.error).await?;
+            return Ok(());
+        }
+    };
+    if calories <= 0.0 && proteins + fats + carbs <= 0.0 {
+        bot.send_message(chat_id, period_empty_text(lang)).await?;
+        return Ok(());
+    }
+
+    let user = queries::get_user(chat_id.0).await.ok().flatten();
+    let goal = user.as_ref().and_then(|u| u.goal.as_deref());
+    let target_grams = user
+        .as_ref()
+        .and_then(|u| u.calorie_target)
+        .map(|target| macro_targets_g(target, goal));
+    let per_day = days as f32;
+    let chart_lang = label_lang(lang);
+    let title = if days == 1 {
+        macro_title_today(chart_lang).to_string()
+    } else {
+        macro_title_period(days, chart_lang)
+    };
+    let labels = macro_labels(chart_lang);
+    let chart = MacroDonut {
+        title: &title,
+        labels,
+        grams: [proteins / per_day, fats / per_day, carbs / per_day],
+        target_percent: macro_split(goal),
+        target_grams,
+        target_label: target_label(chart_lang),
+    };
+
+    match draw_macro_donut_chart(&chart) {
+        Ok(png) => {
+            bot.send_photo(chat_id, InputFile::memory(png).file_name("macros.png"))
+                .await?;
+        }
+        Err(e) => {
+            log::error!("Error drawing macro chart: {}", e);
+            bot.send_message(chat_id, &messages.graph_error).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Самый длинный период для `/chart`, дней.
+const MAX_RANGE_DAYS: i64 = 366;
+
+/// `/week` — график калорий за 7 дней: съеденные и сожжённые.
+pub async fn handle_week_command(bot: &Bot, chat_id: ChatId, lang: &str) -> ResponseResult<()> {
+    let today = get_user_today(chat_id.0).await;
+    let from = today - Duration::days(6);
+    send_calorie_chart(bot, chat_id, from, today, week_title(label_lang(lang)), lang).await
+}
+
+/// `/month` — график калорий за последние 30 дней.
+pub async fn handle_month_command(bot: &Bot, chat_id: ChatId, lang: &str) -> ResponseResult<()> {
+    let today = get_user_today(chat_id.0).await;
+    let from = today - Duration::days(29);
+    send_calorie_chart(bot, chat_id, from, today, month_title(label_lang(lang)), lang).await
+}
+
+/// `/chart 2026-09-01 2026-09-30` — график калорий за произвольный период.
+pub async fn handle_chart_command(
+    bot: &Bot,
+    chat_id: ChatId,
+    text: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    let dates: Vec<NaiveDate> = text
+        .trim_start_matches("/chart")
+        .split_whitespace()
+        .filter_map(|arg| NaiveDate::parse_from_str(arg, "%Y-%m-%d").ok())
+        .collect();
+    let range = match dates.as_slice() {
+        [from, to] if from <= to && (*to - *from).num_days() < MAX_RANGE_DAYS => Some((*from, *to)),
+        _ => None,
+    };
+    let Some((from, to)) = range else {
+        bot.send_message(chat_id, chart_usage_text(lang)).await?;
+        return Ok(());
+    };
+
+    let title = format!(
+        "{} {}–{}",
+        range_title(label_lang(lang)),
+        from.format("%d.%m.%Y"),
+        to.format("%d.%m.%Y")
+    );
+    send_calorie_chart(bot, chat_id, from, to, &title, lang).await
+}
+
+/// Строит и отправляет график калорий за период с `from` по `to` включительно.
+/// `title` уже на языке подписей графика (`label_lang`).
+async fn send_calorie_chart(
+    bot: &Bot,
+    chat_id: ChatId,
+    from: NaiveDate,
+    to: NaiveDate,
+    title: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    let messages = Messages::get(lang);
+    let totals = match queries::get_daily_totals_range(chat_id.0, from, to).await {
+        Ok(totals) => totals,
+        Err(e) => {
+            log::error!("Error in get_daily_totals_range: {}", e);
+            bot.send_message(chat_id, &messages.error).await?;
+            return Ok(());
+        }
+    };
+    let burned = queries::get_burned_by_day(chat_id.0, from, to)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("get_burned_by_day failed: {}", e);
+            Vec::new()
+        });
+
+    if totals.is_empty() && burned.is_empty() {
+        let empty = if (to - from).num_days() == 6 {
+            messages.week_empty.as_str()
+        } else {
+            period_empty_text(lang)
+        };
+        bot.send_message(chat_id, empty).await?;
+        return Ok(());
+    }
+
+    let burned: BTreeMap<NaiveDate, f32> = burned.into_iter().collect();
+    let days = day_bars(from, to, &totals, &burned);
+
+    let target = queries::get_user(chat_id.0)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|u| u.calorie_target)
+        .map(|t| t as f32);
+    let chart = CalorieChart {
+        title,
+        days: &days,
+        target,
+        legend: calorie_legend(label_lang(lang)),
+    };
+
+    match draw_calorie_chart(&chart) {
+        Ok(png) => {
+            let mut caption = period_caption(&days, target, lang);
+            if days.len() == 7 {
+                caption.push('\n');
+                caption.push_str(&water::weekly_water_caption(chat_id.0, lang).await);
+            }
+            bot.send_photo(chat_id, InputFile::memory(png).file_name("calories.png"))
+                .caption(caption)
+                .await?;
+        }
+        Err(e) => {
+            log::error!("Error drawing chart: {}", e);
+            bot.send_message(chat_id, &messages.graph_error).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Столбцы графика для каждого дня периода, в том числе без записей.
+pub fn day_bars(
+    from: NaiveDate,
+    to: NaiveDate,
+    totals: &[DailyTotals],
+    burned: &BTreeMap<NaiveDate, f32>,
+) -> Vec<DayBar> {
+    from.iter_days()
+        .take_while(|date| *date <= to)
+        .map(|date| {
+            let day = totals.iter().find(|t| t.date == date);
+            DayBar {
+                label: date.format("%d.%m").to_string(),
+                calories: day.map_or(0.0, |d| d.calories),
+                proteins: day.map_or(0.0, |d| d.proteins),
+                fats: day.map_or(0.0, |d| d.fats),
+                carbs: day.map_or(0.0, |d| d.carbs),
+                burned: burned.get(&date).copied().unwrap_or(0.0),
+            }
+        })
+        .collect()
+}
+
+/// Подписи графика калорий на языке пользователя.
+pub fn calorie_legend(lang: &str) -> CalorieLegend<'static> {
+    CalorieLegend {
+        unit: "kcal",
+        eaten: eaten_label(lang),
+        burned: burned_label(lang),
+        target: target_label(lang),
+        macros: macro_labels(lang),
+    }
+}
+
+/// Подпись к графику: среднее за дни с записями и сколько из них уложились в норму.
+fn period_caption(days: &[DayBar], target: Option<f32>, lang: &str) -> String {
+    let logged: Vec<&DayBar> = days.iter().filter(|d| d.calories > 0.0).collect();
+    if logged.is_empty() {
+        return format!("📊 {}: —", average_label(lang));
+    }
+    let average = logged.iter().map(|d| d.calories).sum::<f32>() / logged.len() as f32;
+    let mut caption = format!("📊 {}: {:.0} kcal", average_label(lang), average);
+    if let Some(target) = target {
+        let on_target = logged
+            .iter()
+            .filter(|d| d.calories - d.burned <= target)
+            .count();
+        caption.push_str(&format!(" · 🎯 {}/{}", on_target, logged.len()));
+    }
+    caption
+}
+
+/// Остаток калорий на сегодня: норма плюс сожжённое минус съеденное.
+/// `None`, если норма в профиле не рассчитана.
+pub async fn remaining_budget(chat_id: i64, eaten: f32, burned: f32) -> Option<f32> {
+    let target = queries::get_user(chat_id).await.ok().flatten()?.calorie_target?;
+    Some(target as f32 + burned - eaten)
+}
+
+/// «🎯 Осталось: N kcal» или «⚠️ Превышение: N kcal».
+pub fn remaining_text(left: f32, lang: &str) -> String {
+    if left >= 0.0 {
+        let label = match lang {
+            "ru" => "Осталось",
+            "th" => "เหลือ",
+            "zh" => "剩余",
+            _ => "Left",
+        };
+        format!("🎯 {}: {:.0} kcal", label, left)
+    } else {
+        let label = match lang {
+            "ru" => "Превышение",
+            "th" => "เกิน",
+            "zh" => "超出",
+            _ => "Over by",
+        };
+        format!("⚠️ {}: {:.0} kcal", label, -left)
+    }
+}
+
+/// Микронутриенты за сегодня в сравнении с нормой по полу и возрасту из профиля.
+async fn micronutrients_section(chat_id: i64, lang: &str) -> String {
+    let micros = match queries::get_daily_micronutrients(chat_id, get_user_today(chat_id).await).await {
+        Ok(micros) => micros,
+        Err(e) => {
+            log::warn!("get_daily_micronutrients failed: {}", e);
+            return String::new();
+        }
+    };
+    let user = queries::get_user(chat_id).await.ok().flatten();
+    let sex = user.as_ref().and_then(|u| u.gender.as_deref()).and_then(Sex::parse);
+    let age = user.as_ref().and_then(|u| u.age);
+
+    let mut section = format!("\n\n🔬 *{}*", title(lang));
+    for nutrient in Nutrient::ALL {
+        let reference = nutrient.daily_reference(sex, age);
+        let line = match nutrient.value(&micros) {
+            Some(value) => {
+                let percent = value / reference * 100.0;
+                let mark = match (nutrient.is_limit(), percent >= 100.0) {
+                    (true, true) => " ⚠️",
+                    (false, true) => " ✅",
+                    _ => "",
+                };
+                format!(
+                    "\n{} {}: `{:.1}` / {}{:.0} {} \\({:.0}%\\){}",
+                    nutrient.emoji(),
+                    nutrient.label(lang),
+                    value,
+                    if nutrient.is_limit() { "≤" } else { "" },
+                    reference,
+                    nutrient.unit(),
+                    percent,
+                    mark
+                )
+            }
+            None => format!(
+                "\n{} {}: — / {}{:.0} {}",
+                nutrient.emoji(),
+                nutrient.label(lang),
+                if nutrient.is_limit() { "≤" } else { "" },
+                reference,
+                nutrient.unit()
+            ),
+        };
+        section.push_str(&line);
+    }
+    if sex.is_none() {
+        section.push_str(&format!("\n\n_{}_", no_profile_note(lang)));
+    }
+    section
+}
+
+fn burned_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Сожжено",
+        "th" => "เผาผลาญ",
+        "zh" => "消耗",
+        _ => "Burned",
+    }
+}
+
+fn net_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Итого",
+        "th" => "สุทธิ",
+        "zh" => "净摄入",
+        _ => "Net",
+    }
+}
+
+fn water_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Вода",
+        "th" => "น้ำ",
+        "zh" => "饮水",
+        _ => "Water",
+    }
+}
+
+fn title(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Микронутриенты и нормы:",
+        "th" => "สารอาหารรองและปริมาณแนะนำ:",
+        "zh" => "微量营养素与参考摄入量：",
+        _ => "Micronutrients vs daily reference:",
+    }
+}
+
+fn no_profile_note(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Нормы общие: укажите пол и возраст в профиле для точных значений",
+        "th" => "ค่าทั่วไป: ระบุเพศและอายุในโปรไฟล์เพื่อค่าที่แม่นยำขึ้น",
+        "zh" => "通用参考值：在个人资料中填写性别和年龄可获得更准确的数值",
+        _ => "General values: set gender and age in your profile for personal targets",
+    }
+}
+
+pub fn macro_labels(lang: &str) -> [&'static str; 3] {
+    match lang {
+        "ru" => ["Белки", "Жиры", "Углеводы"],
+        "th" => ["โปรตีน", "ไขมัน", "คาร์บ"],
+        "zh" => ["蛋白质", "脂肪", "碳水"],
+        _ => ["Protein", "Fat", "Carbs"],
+    }
+}
+
+fn target_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "норма",
+        "th" => "เป้าหมาย",
+        "zh" => "目标",
+        _ => "target",
+    }
+}
+
+fn macro_title_today(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "БЖУ за сегодня",
+        "th" => "สัดส่วนสารอาหารวันนี้",
+        "zh" => "今日宏量营养素",
+        _ => "Today's macros",
+    }
+}
+
+fn macro_title_period(days: i64, lang: &str) -> String {
+    match lang {
+        "ru" => format!("БЖУ за {} дн. (в среднем за день)", days),
+        "th" => format!("สัดส่วนสารอาหาร {} วัน (เฉลี่ยต่อวัน)", days),
+        "zh" => format!("{}天宏量营养素（日均）", days),
+        _ => format!("Macros, last {} days (daily average)", days),
+    }
+}
+
+fn period_empty_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "📭 Нет записей о еде за этот период.",
+        "th" => "📭 ไม่มีบันทึกอาหารในช่วงนี้",
+        "zh" => "📭 这段时间没有饮食记录。",
+        _ => "📭 No food logged for this period.",
+    }
+}
+
+fn eaten_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Съедено",
+        "th" => "กินแล้ว",
+        "zh" => "摄入",
+        _ => "Eaten",
+    }
+}
+
+fn average_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "В среднем за день",
+        "th" => "เฉลี่ยต่อวัน",
+        "zh" => "日均",
+        _ => "Daily average",
+    }
+}
+
+fn week_title(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Калории за неделю",
+        "th" => "แคลอรี่รายสัปดาห์",
+        "zh" => "本周卡路里",
+        _ => "Weekly calories",
+    }
+}
+
+fn month_title(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Калории за 30 дней",
+        "th" => "แคลอรี่ 30 วัน",
+        "zh" => "30天卡路里",
+        _ => "Calories, last 30 days",
+    }
+}
+
+fn range_title(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Калории",
+        "th" => "แคลอรี่",
+        "zh" => "卡路里",
+        _ => "Calories",
+    }
+}
+
+fn chart_usage_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "📈 Укажите период: /chart 2026-09-01 2026-09-30 (не больше года)",
+        "th" => "📈 ระบุช่วงเวลา: /chart 2026-09-01 2026-09-30 (ไม่เกินหนึ่งปี)",
+        "zh" => "📈 请指定时间段：/chart 2026-09-01 2026-09-30（最长一年）",
+        _ => "📈 Specify a period: /chart 2026-09-01 2026-09-30 (up to a year)",
+    }
+}
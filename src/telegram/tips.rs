@@ -0,0 +1,281 @@
+use crate::db::models::User;
+use crate::db::queries;
+use crate::locales::messages::Messages;
+use crate::services::profile::{goal_key, macro_targets_g};
+use crate::services::reminders::{in_quiet_hours, parse_time};
+use crate::services::tips::{is_due, pick_tip, relevant_topics, RecentData};
+use crate::telegram::handlers::{get_user_lang, get_user_tz};
+use crate::telegram::water::water_goal;
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use std::collections::HashMap;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup},
+};
+
+/// Советы получают только те, кто записывал еду за последние столько дней.
+const ACTIVE_DAYS: i32 = 14;
+
+/// `/tips on|off`, `/tips time 10:00`, `/tips now`; без аргументов — текущие настройки.
+pub async fn handle_tips_command(
+    bot: &Bot,
+    chat_id: ChatId,
+    text: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    let args: Vec<&str> = text.trim_start_matches("/tips").split_whitespace().collect();
+
+    let result = match args.as_slice() {
+        [] => Ok(()),
+        ["now"] => {
+            let today = Utc::now().with_timezone(&get_user_tz(chat_id.0).await).date_naive();
+            if let Err(e) = send_tip(bot, chat_id, today, lang).await {
+                log::error!("Error sending tip: {}", e);
+                bot.send_message(chat_id, &Messages::get(lang).error).await?;
+            }
+            return Ok(());
+        }
+        [toggle @ ("on" | "off")] => {
+            queries::register_user(chat_id.0).await.ok();
+            queries::set_tips_enabled(chat_id.0, *toggle == "on").await
+        }
+        ["time", time] => {
+            let Some(time) = parse_time(time) else {
+                bot.send_message(chat_id, usage_text(lang)).await?;
+                return Ok(());
+            };
+            queries::register_user(chat_id.0).await.ok();
+            queries::set_tip_time(chat_id.0, time).await
+        }
+        _ => {
+            bot.send_message(chat_id, usage_text(lang)).await?;
+            return Ok(());
+        }
+    };
+
+    if let Err(e) = result {
+        log::error!("Error saving tip settings: {}", e);
+        bot.send_message(chat_id, &Messages::get(lang).error).await?;
+        return Ok(());
+    }
+    let prefix = if args.is_empty() { "" } else { "✅ " };
+    bot.send_message(chat_id, format!("{}{}", prefix, settings_text(chat_id.0, lang).await))
+        .await?;
+
+    Ok(())
+}
+
+/// Кнопка «Отключить советы» под советом дня.
+pub async fn handle_tip_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    data: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    let Some(message) = q.message.as_ref() else {
+        bot.answer_callback_query(q.id.clone()).await?;
+        return Ok(());
+    };
+    let chat_id = message.chat().id;
+
+    if data != "tip:off" {
+        log::warn!("Unknown tip callback: {}", data);
+        bot.answer_callback_query(q.id.clone()).await?;
+        return Ok(());
+    }
+
+    match queries::set_tips_enabled(chat_id.0, false).await {
+        Ok(()) => {
+            bot.answer_callback_query(q.id.clone())
+                .text(turned_off_text(lang))
+                .await?;
+            bot.edit_message_reply_markup(chat_id, message.id()).await?;
+        }
+        Err(e) => {
+            log::error!("Error in set_tips_enabled: {}", e);
+            bot.answer_callback_query(q.id.clone())
+                .text(Messages::get(lang).error)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Отправляет совет дня тем, у кого наступило время отправки по местному времени.
+/// Вызывается планировщиком; тихие часы учитываются.
+pub async fn send_due_tips(bot: &Bot) {
+    let recipients = match queries::get_tip_recipients(ACTIVE_DAYS).await {
+        Ok(recipients) => recipients,
+        Err(e) => {
+            log::error!("Error in get_tip_recipients: {}", e);
+            return;
+        }
+    };
+
+    for settings in recipients {
+        let local = Utc::now().with_timezone(&get_user_tz(settings.chat_id).await);
+        let (today, now) = (local.date_naive(), local.time());
+        if !is_due(today, now, settings.send_time, settings.last_sent) {
+            continue;
+        }
+        let quiet_hours = queries::get_quiet_hours(settings.chat_id).await.ok().flatten();
+        if in_quiet_hours(now, quiet_hours) {
+            continue;
+        }
+
+        let lang = get_user_lang(settings.chat_id).await;
+        if let Err(e) = send_tip(bot, ChatId(settings.chat_id), today, &lang).await {
+            log::warn!("Failed to send tip to {}: {}", settings.chat_id, e);
+        }
+        // Отмечаем даже при ошибке отправки, чтобы не повторять каждую минуту
+        if let Err(e) = queries::mark_tip_day_sent(settings.chat_id, today).await {
+            log::error!("Error in mark_tip_day_sent: {}", e);
+        }
+    }
+}
+
+/// Подбирает совет по данным за последнюю неделю и отправляет его.
+async fn send_tip(bot: &Bot, chat_id: ChatId, today: NaiveDate, lang: &str) -> ResponseResult<()> {
+    let history: HashMap<String, _> = queries::get_tip_history(chat_id.0)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("get_tip_history failed: {}", e);
+            Vec::new()
+        })
+        .into_iter()
+        .collect();
+    let user = queries::get_user(chat_id.0).await.ok().flatten();
+    let goal = goal_key(user.as_ref().and_then(|u| u.goal.as_deref()));
+    let topics = relevant_topics(&recent_data(chat_id.0, today, user.as_ref()).await);
+    let seed = (today.num_days_from_ce() as i64 + chat_id.0).unsigned_abs();
+
+    let Some(tip) = pick_tip(goal, &topics, &history, seed) else {
+        log::warn!("Tips catalogue is empty");
+        return Ok(());
+    };
+
+    bot.send_message(chat_id, format!("💡 {}\n\n{}", title(lang), tip.text(lang)))
+        .reply_markup(InlineKeyboardMarkup::new(vec![vec![
+            InlineKeyboardButton::callback(format!("🔕 {}", off_label(lang)), "tip:off"),
+        ]]))
+        .await?;
+    if let Err(e) = queries::record_tip_sent(chat_id.0, &tip.id).await {
+        log::error!("Error in record_tip_sent: {}", e);
+    }
+
+    Ok(())
+}
+
+/// Питание, вода и активность за 7 дней по `today` включительно.
+async fn recent_data(
+    chat_id: i64,
+    today: NaiveDate,
+    user: Option<&User>,
+) -> RecentData {
+    let from = today - Duration::days(6);
+    let totals = queries::get_daily_totals_range(chat_id, from, today)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("get_daily_totals_range failed: {}", e);
+            Vec::new()
+        });
+    let logged: Vec<_> = totals.iter().filter(|d| d.calories > 0.0).collect();
+    let count = logged.len().max(1) as f32;
+
+    let water = queries::get_weekly_water(chat_id).await.unwrap_or_else(|e| {
+        log::warn!("get_weekly_water failed: {}", e);
+        Vec::new()
+    });
+    let avg_water_ml = (!water.is_empty())
+        .then(|| water.iter().map(|(_, ml)| *ml as f32).sum::<f32>() / water.len() as f32);
+
+    let active_days = queries::get_burned_by_day(chat_id, from, today)
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("get_burned_by_day failed: {}", e);
+            Vec::new()
+        })
+        .iter()
+        .filter(|(_, kcal)| *kcal > 0.0)
+        .count();
+
+    let calorie_target = user.and_then(|u| u.calorie_target);
+    RecentData {
+        logged_days: logged.len(),
+        avg_calories: logged.iter().map(|d| d.calories).sum::<f32>() / count,
+        avg_protein: logged.iter().map(|d| d.proteins).sum::<f32>() / count,
+        calorie_target: calorie_target.map(|t| t as f32),
+        protein_target: calorie_target
+            .map(|t| macro_targets_g(t, user.and_then(|u| u.goal.as_deref()))[0]),
+        avg_water_ml,
+        water_goal_ml: water_goal(chat_id).await as f32,
+        active_days,
+    }
+}
+
+async fn settings_text(chat_id: i64, lang: &str) -> String {
+    let settings = queries::get_tip_settings(chat_id).await.ok().flatten();
+    let enabled = settings.as_ref().is_none_or(|s| s.enabled);
+    let status = match (enabled, lang) {
+        (true, "ru") => "включены",
+        (true, "th") => "เปิดอยู่",
+        (true, "zh") => "已开启",
+        (true, _) => "on",
+        (false, "ru") => "отключены",
+        (false, "th") => "ปิดอยู่",
+        (false, "zh") => "已关闭",
+        (false, _) => "off",
+    };
+    let mut text = format!("💡 {}: {}", settings_title(lang), status);
+    if let Some(settings) = settings.filter(|s| s.enabled) {
+        text.push_str(&format!(" · {}", settings.send_time.format("%H:%M")));
+    }
+    text.push_str(&format!("\n\n{}", usage_text(lang)));
+    text
+}
+
+fn title(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Совет дня",
+        "th" => "เคล็ดลับประจำวัน",
+        "zh" => "每日小贴士",
+        _ => "Tip of the day",
+    }
+}
+
+fn settings_title(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Советы дня",
+        "th" => "เคล็ดลับประจำวัน",
+        "zh" => "每日小贴士",
+        _ => "Daily tips",
+    }
+}
+
+fn off_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Отключить советы",
+        "th" => "ปิดเคล็ดลับ",
+        "zh" => "关闭小贴士",
+        _ => "Turn off tips",
+    }
+}
+
+fn turned_off_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Советы отключены. Включить снова: /tips on",
+        "th" => "ปิดเคล็ดลับแล้ว เปิดอีกครั้ง: /tips on",
+        "zh" => "小贴士已关闭。重新开启：/tips on",
+        _ => "Tips turned off. Turn them back on: /tips on",
+    }
+}
+
+fn usage_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "/tips on | off — включить или отключить советы\n/tips time 10:00 — время отправки\n/tips now — получить совет сейчас",
+        "th" => "/tips on | off — เปิดหรือปิดเคล็ดลับ\n/tips time 10:00 — เวลาส่ง\n/tips now — รับเคล็ดลับตอนนี้",
+        "zh" => "/tips on | off — 开启或关闭小贴士\n/tips time 10:00 — 发送时间\n/tips now — 立即获取一条小贴士",
+        _ => "/tips on | off — turn daily tips on or off\n/tips time 10:00 — delivery time\n/tips now — get a tip right now",
+    }
+}
@@ -0,0 +1,229 @@
+use crate::db::queries;
+use crate::locales::messages::Messages;
+use crate::services::water::{daily_goal_ml, progress_bar};
+use crate::telegram::handlers::get_user_today;
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup},
+    ApiError, RequestError,
+};
+
+/// Объёмы для быстрых кнопок, мл.
+const QUICK_ADD_ML: [i32; 2] = [250, 500];
+
+/// Максимальный объём одной записи, мл.
+const MAX_AMOUNT_ML: i32 = 3000;
+
+/// `/water` — прогресс за сегодня с кнопками +250/+500 мл; `/water 300` сразу добавляет объём.
+pub async fn handle_water_command(
+    bot: &Bot,
+    chat_id: ChatId,
+    text: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    let arg = text.trim_start_matches("/water").trim();
+    if !arg.is_empty() {
+        let amount = arg
+            .trim_end_matches(|c: char| c.is_alphabetic() || c.is_whitespace())
+            .parse::<i32>()
+            .ok()
+            .filter(|ml| (1..=MAX_AMOUNT_ML).contains(ml));
+        let Some(amount) = amount else {
+            bot.send_message(chat_id, usage_text(lang)).await?;
+            return Ok(());
+        };
+        if let Err(e) = queries::add_water_log(chat_id.0, amount).await {
+            log::error!("Error in add_water_log: {}", e);
+            bot.send_message(chat_id, &Messages::get(lang).error).await?;
+            return Ok(());
+        }
+    }
+
+    match render_progress(chat_id.0, lang).await {
+        Ok(text) => {
+            bot.send_message(chat_id, text)
+                .reply_markup(water_keyboard(lang))
+                .await?;
+        }
+        Err(e) => {
+            log::error!("Error loading water progress for {}: {}", chat_id, e);
+            bot.send_message(chat_id, &Messages::get(lang).error).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Обрабатывает кнопки `water:add:<мл>` и `water:undo`.
+pub async fn handle_water_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    data: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    let Some(message) = q.message.as_ref() else {
+        bot.answer_callback_query(q.id.clone()).await?;
+        return Ok(());
+    };
+    let chat_id = message.chat().id;
+    let parts: Vec<&str> = data.split(':').collect();
+
+    let result = match parts.as_slice() {
+        ["water", "add", amount] => match amount.parse::<i32>() {
+            Ok(amount) if (1..=MAX_AMOUNT_ML).contains(&amount) => {
+                queries::add_water_log(chat_id.0, amount).await.map(|_| true)
+            }
+            _ => Ok(false),
+        },
+        ["water", "undo"] => queries::delete_last_water_log(chat_id.0, get_user_today(chat_id.0).await).await,
+        _ => {
+            log::warn!("Unknown water callback: {}", data);
+            Ok(false)
+        }
+    };
+
+    match result {
+        Ok(true) => {
+            bot.answer_callback_query(q.id.clone()).await?;
+            match render_progress(chat_id.0, lang).await {
+                Ok(text) => {
+                    let edited = bot
+                        .edit_message_text(chat_id, message.id(), text)
+                        .reply_markup(water_keyboard(lang))
+                        .await;
+                    match edited {
+                        Ok(_) | Err(RequestError::Api(ApiError::MessageNotModified)) => {}
+                        Err(e) => return Err(e),
+                    }
+                }
+                Err(e) => log::error!("Error loading water progress for {}: {}", chat_id, e),
+            }
+        }
+        Ok(false) => {
+            bot.answer_callback_query(q.id.clone()).await?;
+        }
+        Err(e) => {
+            log::error!("Error updating water log for {}: {}", chat_id, e);
+            bot.answer_callback_query(q.id.clone())
+                .text(Messages::get(lang).error)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Выпито за сегодня и норма (мл) для `/stats`.
+pub async fn daily_water(chat_id: i64) -> Option<(i64, i64)> {
+    let today = get_user_today(chat_id).await;
+    let drunk = queries::get_daily_water(chat_id, today)
+        .await
+        .map_err(|e| log::warn!("get_daily_water failed: {}", e))
+        .ok()?;
+    Some((drunk, water_goal(chat_id).await))
+}
+
+/// Подпись к недельному графику: сколько воды в среднем выпито за дни с записями.
+pub async fn weekly_water_caption(chat_id: i64, lang: &str) -> String {
+    let weekly = queries::get_weekly_water(chat_id).await.unwrap_or_else(|e| {
+        log::warn!("get_weekly_water failed: {}", e);
+        Vec::new()
+    });
+    let goal = water_goal(chat_id).await;
+    if weekly.is_empty() {
+        return format!("💧 {}: — / {} ml", weekly_label(lang), goal);
+    }
+
+    let average = weekly.iter().map(|(_, ml)| ml).sum::<i64>() / weekly.len() as i64;
+    let days_on_goal = weekly.iter().filter(|(_, ml)| *ml >= goal).count();
+    format!(
+        "💧 {}: {} / {} ml · ✅ {}/{}",
+        weekly_label(lang),
+        average,
+        goal,
+        days_on_goal,
+        weekly.len()
+    )
+}
+
+/// Норма воды пользователя по весу из профиля.
+pub async fn water_goal(chat_id: i64) -> i64 {
+    let weight = queries::get_user(chat_id)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|u| u.weight_kg);
+    daily_goal_ml(weight)
+}
+
+async fn render_progress(chat_id: i64, lang: &str) -> Result<String, sqlx::Error> {
+    let drunk = queries::get_daily_water(chat_id, get_user_today(chat_id).await).await?;
+    let goal = water_goal(chat_id).await;
+
+    let mut text = format!(
+        "💧 {}: {} / {} ml\n{} {:.0}%",
+        title(lang),
+        drunk,
+        goal,
+        progress_bar(drunk, goal),
+        drunk as f64 / goal as f64 * 100.0
+    );
+    if drunk >= goal {
+        text.push_str(&format!("\n\n🎉 {}", goal_reached_text(lang)));
+    }
+    Ok(text)
+}
+
+fn water_keyboard(lang: &str) -> InlineKeyboardMarkup {
+    let mut row: Vec<InlineKeyboardButton> = QUICK_ADD_ML
+        .iter()
+        .map(|ml| InlineKeyboardButton::callback(format!("+{} ml", ml), format!("water:add:{}", ml)))
+        .collect();
+    row.push(InlineKeyboardButton::callback(undo_label(lang), "water:undo"));
+    InlineKeyboardMarkup::new(vec![row])
+}
+
+fn title(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Вода сегодня",
+        "th" => "น้ำวันนี้",
+        "zh" => "今日饮水",
+        _ => "Water today",
+    }
+}
+
+fn weekly_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Вода в среднем за день",
+        "th" => "น้ำเฉลี่ยต่อวัน",
+        "zh" => "日均饮水",
+        _ => "Average water per day",
+    }
+}
+
+fn goal_reached_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Норма воды на сегодня выполнена!",
+        "th" => "ดื่มน้ำครบตามเป้าหมายวันนี้แล้ว!",
+        "zh" => "今天的饮水目标已完成！",
+        _ => "You reached today's water goal!",
+    }
+}
+
+fn undo_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "↩️ Отменить",
+        "th" => "↩️ ยกเลิก",
+        "zh" => "↩️ 撤销",
+        _ => "↩️ Undo",
+    }
+}
+
+fn usage_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "💧 Укажите объём в мл, например: /water 300",
+        "th" => "💧 ระบุปริมาณเป็นมล. เช่น /water 300",
+        "zh" => "💧 请输入毫升数，例如：/water 300",
+        _ => "💧 Enter the amount in ml, e.g. /water 300",
+    }
+}
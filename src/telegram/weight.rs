@@ -0,0 +1,190 @@
+use crate::db::models::WeightLog;
+use crate::db::queries;
+use crate::locales::messages::Messages;
+use crate::services::chart::{draw_weight_chart, label_lang, WeightLegend};
+use crate::services::profile::{bmi, bmi_category, calorie_target};
+use crate::services::water::daily_goal_ml;
+use crate::telegram::handlers::get_user_tz;
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+use teloxide::{prelude::*, types::InputFile};
+
+/// За сколько дней показывать график веса.
+const WEIGHT_HISTORY_DAYS: i32 = 90;
+
+/// Окно скользящей средней на графике, дней.
+const TREND_WINDOW_DAYS: i64 = 7;
+
+/// `/weight 82.4` записывает вес и пересчитывает ИМТ и нормы; `/weight` показывает график.
+pub async fn handle_weight_command(
+    bot: &Bot,
+    chat_id: ChatId,
+    text: &str,
+    lang: &str,
+) -> ResponseResult<()> {
+    let arg = text.trim_start_matches("/weight").trim();
+    let messages = Messages::get(lang);
+
+    if !arg.is_empty() {
+        let weight = arg
+            .trim_end_matches(|c: char| c.is_alphabetic() || c.is_whitespace())
+            .replace(',', ".")
+            .parse::<f64>()
+            .ok()
+            .filter(|w| (20.0..=400.0).contains(w));
+        let Some(weight) = weight else {
+            bot.send_message(chat_id, usage_text(lang)).await?;
+            return Ok(());
+        };
+
+        queries::register_user(chat_id.0).await.ok();
+        let user = queries::get_user(chat_id.0).await.ok().flatten();
+        let imt = user.as_ref().and_then(|u| bmi(weight, u.height_cm));
+        let target = user.as_ref().and_then(|u| calorie_target(u, weight));
+
+        if let Err(e) = queries::log_weight(chat_id.0, weight, imt, target).await {
+            log::error!("Error in log_weight: {}", e);
+            bot.send_message(chat_id, &messages.error).await?;
+            return Ok(());
+        }
+
+        let previous = user.and_then(|u| u.weight_kg);
+        let mut reply = format!("⚖️ {}: {:.1} kg", saved_label(lang), weight);
+        if let Some(previous) = previous {
+            let diff = weight - previous;
+            if diff.abs() >= 0.05 {
+                reply.push_str(&format!(" ({:+.1})", diff));
+            }
+        }
+        match imt {
+            Some(imt) => reply.push_str(&format!(
+                "\n📐 {}: {:.1} — {}",
+                bmi_label(lang),
+                imt,
+                bmi_category(imt, lang)
+            )),
+            None => reply.push_str(&format!("\n📐 {}", no_height_text(lang))),
+        }
+        if let Some(target) = target {
+            reply.push_str(&format!("\n🎯 {}: {} kcal", target_label(lang), target));
+        }
+        reply.push_str(&format!("\n💧 {}: {} ml", water_label(lang), daily_goal_ml(Some(weight))));
+        bot.send_message(chat_id, reply).await?;
+    }
+
+    let logs = match queries::get_weight_logs(chat_id.0, WEIGHT_HISTORY_DAYS).await {
+        Ok(logs) => logs,
+        Err(e) => {
+            log::error!("Error in get_weight_logs: {}", e);
+            bot.send_message(chat_id, &messages.error).await?;
+            return Ok(());
+        }
+    };
+    let data = daily_weights(&logs, get_user_tz(chat_id.0).await);
+
+    if data.len() < 2 {
+        if arg.is_empty() {
+            bot.send_message(chat_id, empty_text(lang)).await?;
+        }
+        return Ok(());
+    }
+
+    match draw_weight_chart(&data, TREND_WINDOW_DAYS, &weight_legend(TREND_WINDOW_DAYS, label_lang(lang))) {
+        Ok(png) => {
+            bot.send_photo(chat_id, InputFile::memory(png).file_name("weight.png"))
+                .await?;
+        }
+        Err(e) => {
+            log::error!("Error drawing weight chart: {}", e);
+            bot.send_message(chat_id, &messages.graph_error).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Оставляет последнее измерение за каждый день (дни — по часовому поясу пользователя).
+pub fn daily_weights(logs: &[WeightLog], tz: Tz) -> Vec<(NaiveDate, f32)> {
+    let mut data: Vec<(NaiveDate, f32)> = Vec::new();
+    for log in logs {
+        let date = log.created_at.with_timezone(&tz).date_naive();
+        match data.last_mut() {
+            Some((last_date, value)) if *last_date == date => *value = log.weight_kg as f32,
+            _ => data.push((date, log.weight_kg as f32)),
+        }
+    }
+    data
+}
+
+/// Подписи графика веса; среднее — за `window_days` дней.
+pub fn weight_legend(window_days: i64, lang: &str) -> WeightLegend<'static> {
+    let (title, weight, average) = match lang {
+        "ru" => ("Динамика веса", "Вес, кг", format!("Среднее за {} дн.", window_days)),
+        "th" => ("แนวโน้มน้ำหนัก", "น้ำหนัก, กก.", format!("ค่าเฉลี่ย {} วัน", window_days)),
+        "zh" => ("体重趋势", "体重，公斤", format!("{}日平均", window_days)),
+        _ => ("Weight Trend", "Weight, kg", format!("{}-day average", window_days)),
+    };
+    WeightLegend { title, weight, average }
+}
+
+fn saved_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Вес записан",
+        "th" => "บันทึกน้ำหนักแล้ว",
+        "zh" => "体重已记录",
+        _ => "Weight saved",
+    }
+}
+
+fn bmi_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "ИМТ",
+        "zh" => "BMI指数",
+        _ => "BMI",
+    }
+}
+
+fn target_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Норма калорий",
+        "th" => "แคลอรี่ที่แนะนำต่อวัน",
+        "zh" => "每日热量目标",
+        _ => "Daily calorie target",
+    }
+}
+
+fn water_label(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Норма воды",
+        "th" => "เป้าหมายการดื่มน้ำ",
+        "zh" => "饮水目标",
+        _ => "Water goal",
+    }
+}
+
+fn no_height_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "Укажите рост в профиле, чтобы рассчитать ИМТ.",
+        "th" => "ระบุส่วนสูงในโปรไฟล์เพื่อคำนวณ BMI",
+        "zh" => "请在个人资料中填写身高以计算BMI。",
+        _ => "Add your height to the profile to calculate BMI.",
+    }
+}
+
+fn usage_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "⚖️ Укажите вес в кг, например: /weight 82.4",
+        "th" => "⚖️ ระบุน้ำหนักเป็นกก. เช่น /weight 82.4",
+        "zh" => "⚖️ 请输入体重（公斤），例如：/weight 82.4",
+        _ => "⚖️ Enter your weight in kg, e.g. /weight 82.4",
+    }
+}
+
+fn empty_text(lang: &str) -> &'static str {
+    match lang {
+        "ru" => "⚖️ Записей пока мало для графика. Отправляйте вес командой /weight 82.4",
+        "th" => "⚖️ ยังมีข้อมูลไม่พอสำหรับกราฟ บันทึกน้ำหนักด้วย /weight 82.4",
+        "zh" => "⚖️ 记录太少，无法绘制图表。使用 /weight 82.4 记录体重",
+        _ => "⚖️ Not enough entries for a chart yet. Log your weight with /weight 82.4",
+    }
+}